@@ -0,0 +1,156 @@
+use atlas_common::{ErrorCode, HashFlags, PluginError, RegisterFunc, hash_function};
+
+/// The length of a Pearson permutation table.
+const PEARSON_TABLE_LEN: usize = 256;
+
+#[unsafe(no_mangle)]
+pub extern "C" fn register_hashers(register: RegisterFunc) {
+    register(c"Adler-32".as_ptr(), HashFlags::empty(), adler32);
+    register(c"Fletcher-16".as_ptr(), HashFlags::empty(), fletcher16);
+    register(c"Fletcher-32".as_ptr(), HashFlags::empty(), fletcher32);
+    register(c"Fletcher-64".as_ptr(), HashFlags::empty(), fletcher64);
+    register(c"BSD_sum".as_ptr(), HashFlags::empty(), bsd_sum);
+    register(c"SysV_sum".as_ptr(), HashFlags::empty(), sysv_sum);
+    register(
+        c"Internet_checksum".as_ptr(),
+        HashFlags::empty(),
+        internet_checksum,
+    );
+    register(c"XOR-8".as_ptr(), HashFlags::empty(), xor8);
+    register(c"LRC".as_ptr(), HashFlags::empty(), lrc);
+    register(c"Pearson".as_ptr(), HashFlags::SECRET, pearson);
+}
+
+atlas_common::hash_batch!();
+
+/// The two running sums of a Fletcher checksum over little-endian words of
+/// `N` bytes, with a short last word padded with zeros.
+fn fletcher<const N: usize>(input: &[u8], modulus: u64) -> (u64, u64) {
+    input.chunks(N).fold((0, 0), |(a, b), chunk| {
+        let mut word = [0u8; 8];
+        word[..chunk.len()].copy_from_slice(chunk);
+        let a = (a + u64::from_le_bytes(word)) % modulus;
+        (a, (b + a) % modulus)
+    })
+}
+
+hash_function! {
+    pub fn adler32(input, _) {
+        const MOD_ADLER: u32 = 65521;
+        let (a, b) = input.iter().fold((1u32, 0u32), |(a, b), byte| {
+            let a = (a + u32::from(*byte)) % MOD_ADLER;
+            (a, (b + a) % MOD_ADLER)
+        });
+        let hash = (b << 16) | a;
+        Ok(hash.to_be_bytes())
+    }
+}
+
+hash_function! {
+    pub fn fletcher16(input, _) {
+        let (a, b) = fletcher::<1>(input, 0xff);
+        let hash = ((b << 8) | a) as u16;
+        Ok(hash.to_be_bytes())
+    }
+}
+
+hash_function! {
+    /// Sums 16-bit little-endian words.
+    pub fn fletcher32(input, _) {
+        let (a, b) = fletcher::<2>(input, 0xffff);
+        let hash = ((b << 16) | a) as u32;
+        Ok(hash.to_be_bytes())
+    }
+}
+
+hash_function! {
+    /// Sums 32-bit little-endian words.
+    pub fn fletcher64(input, _) {
+        let (a, b) = fletcher::<4>(input, 0xffff_ffff);
+        let hash = (b << 32) | a;
+        Ok(hash.to_be_bytes())
+    }
+}
+
+hash_function! {
+    /// The 16-bit checksum of BSD `sum`, or `sum -r`.
+    pub fn bsd_sum(input, _) {
+        let hash = input
+            .iter()
+            .fold(0u16, |hash, byte| hash.rotate_right(1).wrapping_add(u16::from(*byte)));
+        Ok(hash.to_be_bytes())
+    }
+}
+
+hash_function! {
+    /// The 16-bit checksum of System V `sum`, or `sum -s`.
+    pub fn sysv_sum(input, _) {
+        let sum = input
+            .iter()
+            .fold(0u32, |sum, byte| sum.wrapping_add(u32::from(*byte)));
+        let r = (sum & 0xffff) + (sum >> 16);
+        let hash = ((r & 0xffff) + (r >> 16)) as u16;
+        Ok(hash.to_be_bytes())
+    }
+}
+
+hash_function! {
+    /// The RFC 1071 checksum used by IPv4, TCP and UDP headers, over 16-bit
+    /// big-endian words.
+    pub fn internet_checksum(input, _) {
+        let mut sum = input.chunks(2).fold(0u64, |sum, chunk| {
+            let word = [chunk[0], chunk.get(1).copied().unwrap_or(0)];
+            sum + u64::from(u16::from_be_bytes(word))
+        });
+        while sum > 0xffff {
+            sum = (sum & 0xffff) + (sum >> 16);
+        }
+        let hash = !(sum as u16);
+        Ok(hash.to_be_bytes())
+    }
+}
+
+hash_function! {
+    pub fn xor8(input, _) {
+        let hash = input.iter().fold(0u8, |hash, byte| hash ^ byte);
+        Ok([hash])
+    }
+}
+
+hash_function! {
+    /// The ISO 1155 longitudinal redundancy check, the two's complement of
+    /// the sum of every byte.
+    pub fn lrc(input, _) {
+        let sum = input.iter().fold(0u8, |sum, byte| sum.wrapping_add(*byte));
+        Ok([sum.wrapping_neg()])
+    }
+}
+
+hash_function! {
+    /// The secret is the table, a permutation of the 256 byte values.
+    pub fn pearson(input, options) {
+        let table = options.secret(PEARSON_TABLE_LEN)?;
+        if table.len() != PEARSON_TABLE_LEN {
+            return Err(PluginError::new(
+                ErrorCode::InvalidInput,
+                format!(
+                    "The table must be {PEARSON_TABLE_LEN} bytes, got {}",
+                    table.len()
+                ),
+            ));
+        }
+        let mut seen = [false; PEARSON_TABLE_LEN];
+        for value in table {
+            if std::mem::replace(&mut seen[usize::from(*value)], true) {
+                return Err(PluginError::new(
+                    ErrorCode::InvalidInput,
+                    format!("The table is not a permutation, {value} appears twice"),
+                ));
+            }
+        }
+        let hash = input
+            .iter()
+            .fold(0u8, |hash, byte| table[usize::from(hash ^ byte)]);
+        Ok([hash])
+    }
+}
@@ -0,0 +1,216 @@
+use atlas_common::{ErrorCode, HashFlags, PluginError, RegisterFunc, hash_function};
+
+#[unsafe(no_mangle)]
+pub extern "C" fn register_hashers(register: RegisterFunc) {
+    register(c"djb2".as_ptr(), HashFlags::empty(), djb2);
+    register(c"djb2a".as_ptr(), HashFlags::empty(), djb2a);
+    register(c"sdbm".as_ptr(), HashFlags::empty(), sdbm);
+    register(c"lose-lose".as_ptr(), HashFlags::empty(), lose_lose);
+    register(c"ELF".as_ptr(), HashFlags::empty(), elf);
+    register(c"PJW".as_ptr(), HashFlags::empty(), pjw);
+    register(c"BKDR".as_ptr(), HashFlags::SEEDED, bkdr);
+    register(
+        c"Java_hashCode".as_ptr(),
+        HashFlags::empty(),
+        java_hash_code,
+    );
+    register(c"Python2_32".as_ptr(), HashFlags::empty(), python2_32);
+    register(c"Python2_64".as_ptr(), HashFlags::empty(), python2_64);
+    register(c"DotNet_32".as_ptr(), HashFlags::empty(), dotnet_32);
+    register(c"DotNet_64".as_ptr(), HashFlags::empty(), dotnet_64);
+}
+
+atlas_common::hash_batch!();
+
+/// The UTF-16 code units of `input`, for hashes of UTF-16 strings.
+fn utf16(input: &[u8]) -> Result<Vec<u16>, PluginError> {
+    let text = std::str::from_utf8(input).map_err(|e| {
+        PluginError::new(
+            ErrorCode::InvalidInput,
+            format!("The input is not valid UTF-8: {e}"),
+        )
+    })?;
+    Ok(text.encode_utf16().collect())
+}
+
+hash_function! {
+    pub fn djb2(input, _) {
+        let hash = input.iter().fold(5381u32, |hash, byte| {
+            hash.wrapping_mul(33).wrapping_add(u32::from(*byte))
+        });
+        Ok(hash.to_be_bytes())
+    }
+}
+
+hash_function! {
+    pub fn djb2a(input, _) {
+        let hash = input
+            .iter()
+            .fold(5381u32, |hash, byte| hash.wrapping_mul(33) ^ u32::from(*byte));
+        Ok(hash.to_be_bytes())
+    }
+}
+
+hash_function! {
+    pub fn sdbm(input, _) {
+        let hash = input.iter().fold(0u32, |hash, byte| {
+            u32::from(*byte)
+                .wrapping_add(hash << 6)
+                .wrapping_add(hash << 16)
+                .wrapping_sub(hash)
+        });
+        Ok(hash.to_be_bytes())
+    }
+}
+
+hash_function! {
+    /// The sum of every byte, from the first edition of K&R.
+    pub fn lose_lose(input, _) {
+        let hash = input
+            .iter()
+            .fold(0u32, |hash, byte| hash.wrapping_add(u32::from(*byte)));
+        Ok(hash.to_be_bytes())
+    }
+}
+
+hash_function! {
+    /// The hash from the System V ELF specification.
+    pub fn elf(input, _) {
+        let mut hash = 0u32;
+        for byte in input {
+            hash = (hash << 4).wrapping_add(u32::from(*byte));
+            let high = hash & 0xf000_0000;
+            if high != 0 {
+                hash ^= high >> 24;
+            }
+            hash &= !high;
+        }
+        Ok(hash.to_be_bytes())
+    }
+}
+
+hash_function! {
+    /// Peter J. Weinberger's hash, as written in the Dragon Book for 32-bit
+    /// integers. This gives the same results as [`elf`].
+    pub fn pjw(input, _) {
+        const BITS: u32 = u32::BITS;
+        const THREE_QUARTERS: u32 = BITS * 3 / 4;
+        const ONE_EIGHTH: u32 = BITS / 8;
+        const HIGH_BITS: u32 = u32::MAX << (BITS - ONE_EIGHTH);
+
+        let mut hash = 0u32;
+        for byte in input {
+            hash = (hash << ONE_EIGHTH).wrapping_add(u32::from(*byte));
+            let high = hash & HIGH_BITS;
+            if high != 0 {
+                hash = (hash ^ (high >> THREE_QUARTERS)) & !HIGH_BITS;
+            }
+        }
+        Ok(hash.to_be_bytes())
+    }
+}
+
+hash_function! {
+    /// The hash from Kernighan and Ritchie's "The C Programming Language". The
+    /// multiplier is the seed, with `0` picking the usual `131`.
+    pub fn bkdr(input, options) {
+        let multiplier = match options.seed_u32()? {
+            0 => 131,
+            multiplier => multiplier,
+        };
+        let hash = input.iter().fold(0u32, |hash, byte| {
+            hash.wrapping_mul(multiplier).wrapping_add(u32::from(*byte))
+        });
+        Ok(hash.to_be_bytes())
+    }
+}
+
+hash_function! {
+    /// Java's `String.hashCode`, over the UTF-16 encoding of the input.
+    pub fn java_hash_code(input, _) {
+        let hash = utf16(input)?
+            .into_iter()
+            .fold(0i32, |hash, unit| hash.wrapping_mul(31).wrapping_add(i32::from(unit)));
+        Ok(hash.to_be_bytes())
+    }
+}
+
+macro_rules! python2_impl {
+    ($name:ident, $long:ty) => {
+        hash_function! {
+            /// Python 2's `str.__hash__`, without hash randomisation (`-R`).
+            pub fn $name(input, _) {
+                let Some(first) = input.first() else {
+                    return Ok(<$long>::default().to_be_bytes());
+                };
+                let mut hash = <$long>::from(*first) << 7;
+                for byte in input {
+                    hash = hash.wrapping_mul(1000003) ^ <$long>::from(*byte);
+                }
+                hash ^= input.len() as $long;
+                if hash == -1 {
+                    hash = -2;
+                }
+                Ok(hash.to_be_bytes())
+            }
+        }
+    };
+}
+
+// Python 2 hashes are a C `long`, which is 32 bits on Windows.
+python2_impl!(python2_32, i32);
+python2_impl!(python2_64, i64);
+
+/// The `(hash << 5) + hash` step shared by both .NET variants.
+fn dotnet_step(hash: i32) -> i32 {
+    (hash << 5).wrapping_add(hash)
+}
+
+hash_function! {
+    /// .NET Framework's `String.GetHashCode` on 32-bit runtimes, without
+    /// randomized string hashing. The UTF-16 code units are read two at a time
+    /// as little-endian `int`s.
+    pub fn dotnet_32(input, _) {
+        let mut units = utf16(input)?;
+        let mut len = units.len() as isize;
+        // The string's NUL terminator, and padding for the reads past it.
+        units.extend([0; 4]);
+        let words: Vec<i32> = units
+            .chunks_exact(2)
+            .map(|pair| i32::from(pair[0]) | (i32::from(pair[1]) << 16))
+            .collect();
+
+        let mut hash1 = (5381i32 << 16).wrapping_add(5381);
+        let mut hash2 = hash1;
+        let mut words = words.into_iter();
+        let mut next = || words.next().unwrap_or_default();
+        while len > 2 {
+            hash1 = dotnet_step(hash1).wrapping_add(hash1 >> 27) ^ next();
+            hash2 = dotnet_step(hash2).wrapping_add(hash2 >> 27) ^ next();
+            len -= 4;
+        }
+        if len > 0 {
+            hash1 = dotnet_step(hash1).wrapping_add(hash1 >> 27) ^ next();
+        }
+        let hash = hash1.wrapping_add(hash2.wrapping_mul(1566083941));
+        Ok(hash.to_be_bytes())
+    }
+}
+
+hash_function! {
+    /// .NET Framework's `String.GetHashCode` on 64-bit runtimes, without
+    /// randomized string hashing. Hashing stops at the first NUL character.
+    pub fn dotnet_64(input, _) {
+        let units = utf16(input)?;
+        let mut hash1 = 5381i32;
+        let mut hash2 = hash1;
+        for pair in units.split(|unit| *unit == 0).next().unwrap_or_default().chunks(2) {
+            hash1 = dotnet_step(hash1) ^ i32::from(pair[0]);
+            if let Some(unit) = pair.get(1) {
+                hash2 = dotnet_step(hash2) ^ i32::from(*unit);
+            }
+        }
+        let hash = hash1.wrapping_add(hash2.wrapping_mul(1566083941));
+        Ok(hash.to_be_bytes())
+    }
+}
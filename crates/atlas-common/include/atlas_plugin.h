@@ -0,0 +1,254 @@
+#ifndef ATLAS_PLUGIN_H
+#define ATLAS_PLUGIN_H
+
+/* Generated by cbindgen from atlas-common, do not edit by hand. Regenerate with `ATLAS_UPDATE_HEADER=1 cargo test -p atlas-common --test header`. */
+
+#include <stddef.h>
+#include <stdint.h>
+
+/**
+ * Status codes returned by a [`HashFunction`](crate::HashFunction).
+ *
+ * `0` means success, every error is negative.
+ */
+enum ErrorCode
+#if defined(__cplusplus) || __STDC_VERSION__ >= 202311L
+  : int32_t
+#endif // defined(__cplusplus) || __STDC_VERSION__ >= 202311L
+ {
+  ErrorCode_Ok = 0,
+  /**
+   * A required pointer argument was null.
+   */
+  ErrorCode_NullPointer = -1,
+  /**
+   * A pointer argument wasn't aligned for its type.
+   */
+  ErrorCode_BadAlignment = -2,
+  /**
+   * The hasher needs an option (seed, key or secret) that wasn't given.
+   */
+  ErrorCode_MissingOption = -3,
+  /**
+   * The seed doesn't fit in the hasher's seed width.
+   */
+  ErrorCode_InvalidSeedWidth = -4,
+  /**
+   * The secret is shorter than the hasher's minimum.
+   */
+  ErrorCode_SecretTooShort = -5,
+  /**
+   * The hasher can't process input of this length.
+   */
+  ErrorCode_UnsupportedInputLength = -6,
+  /**
+   * The hasher panicked.
+   */
+  ErrorCode_InternalPanic = -7,
+  /**
+   * The underlying implementation reported an error.
+   */
+  ErrorCode_Internal = -8,
+  /**
+   * The output buffer is too small; its `len` has been set to the size needed.
+   */
+  ErrorCode_OutputTooSmall = -9,
+  /**
+   * The input isn't valid for the hasher, e.g. a string hash given invalid UTF-8.
+   */
+  ErrorCode_InvalidInput = -10,
+  /**
+   * The key isn't a length the hasher accepts.
+   */
+  ErrorCode_InvalidKeyLength = -11,
+  /**
+   * A parameter value isn't one the hasher declared.
+   */
+  ErrorCode_InvalidParameter = -12,
+};
+#ifndef __cplusplus
+#if __STDC_VERSION__ >= 202311L
+typedef enum ErrorCode ErrorCode;
+#else
+typedef int32_t ErrorCode;
+#endif // __STDC_VERSION__ >= 202311L
+#endif // __cplusplus
+
+typedef struct Slice_u8 {
+  uint8_t *ptr;
+  size_t len;
+} Slice_u8;
+
+typedef struct Slice_u64 {
+  uint64_t *ptr;
+  size_t len;
+} Slice_u64;
+
+/**
+ * The option passed to a hasher, borrowed from the host for the duration of the call.
+ *
+ * `data` depends on the hasher's [`HashFlags`]: a `u64` seed for
+ * [`SEEDED`](HashFlags::SEEDED), or a `Slice<u8>` of key or secret bytes for
+ * [`KEYED`](HashFlags::KEYED) and [`SECRET`](HashFlags::SECRET). It is null
+ * when the hasher takes no options.
+ *
+ * `params` holds a value for every [`HashParam`] the hasher declared, in the
+ * order they were declared, and is empty for hashers that declare none.
+ */
+typedef struct CHashOptions {
+  const void *data;
+  struct Slice_u64 params;
+} CHashOptions;
+
+/**
+ * A caller-owned buffer a hasher writes its hash into.
+ *
+ * The caller allocates the buffer and sets `capacity`. The hasher writes the
+ * hash and sets `len`, or if the hash doesn't fit, sets `len` to the size
+ * needed and returns [`ErrorCode::OutputTooSmall`] so the caller can retry
+ * with a larger buffer.
+ */
+typedef struct OutputBuffer {
+  uint8_t *ptr;
+  size_t capacity;
+  size_t len;
+} OutputBuffer;
+
+/**
+ * A caller-owned buffer a hasher can write an error message into.
+ *
+ * The caller allocates the buffer and sets `capacity`, the hasher writes at
+ * most `capacity` bytes of UTF-8 and sets `len`. Passing a null
+ * `*mut ErrorMessage` to a hasher is allowed, in which case no message is written.
+ */
+typedef struct ErrorMessage {
+  uint8_t *ptr;
+  size_t capacity;
+  size_t len;
+} ErrorMessage;
+
+/**
+ * The `HashFunction` type defines the function signature that all
+ * hashing plugins for Atlas must use.
+ *
+ * `input` holds the data to hash, borrowed for the duration of the call.
+ * `options` may be null, otherwise its `data` points to the option matching
+ * the hasher's [`HashFlags`], also borrowed. The hash is written to `output`,
+ * see [`OutputBuffer`]. `error` may be null, otherwise the hasher may write a
+ * message describing a failure into it.
+ *
+ * Returns `0` on success, or a negative [`ErrorCode`]. Hashers must never
+ * unwind across this boundary: the function is `extern "C"`, so a panic or
+ * C++ exception escaping it aborts the host. Plugins catch their own panics
+ * and report them as [`ErrorCode::InternalPanic`], which
+ * [`hash_function!`](crate::hash_function) does for them.
+ */
+typedef int32_t (*HashFunction)(const struct Slice_u8 *input,
+                                const struct CHashOptions *options,
+                                struct OutputBuffer *output,
+                                struct ErrorMessage *error);
+
+typedef struct Slice_usize {
+  size_t *ptr;
+  size_t len;
+} Slice_usize;
+
+/**
+ * Many inputs packed into one buffer, borrowed from the host for a
+ * [`BatchHashFunction`] call.
+ *
+ * Input `i` is `data[offsets[i]..offsets[i + 1]]`, so `offsets` holds one
+ * more entry than there are inputs.
+ */
+typedef struct BatchInput {
+  struct Slice_u8 data;
+  struct Slice_usize offsets;
+} BatchInput;
+
+/**
+ * A caller-owned array a [`BatchHashFunction`] writes fixed-width hashes into.
+ *
+ * The caller allocates `width` bytes per input and sets `width`. The hasher
+ * writes hash `i` at `ptr + i * width`, and sets `len` to the number of hashes
+ * written, which on failure is the index of the input that failed. If a hash
+ * isn't exactly `width` bytes, the hasher sets `width` to its length and
+ * returns [`ErrorCode::OutputTooSmall`], so the caller can retry. If that
+ * happens after the first hash, with `len` above zero, the hashes differ in
+ * width and no retry will fit them.
+ */
+typedef struct BatchOutput {
+  uint8_t *ptr;
+  size_t width;
+  size_t len;
+} BatchOutput;
+
+/**
+ * The `BatchHashFunction` type defines the signature of the optional
+ * `hash_batch` function a plugin may export, to hash many inputs in one call.
+ *
+ * `hasher` is one of the [`HashFunction`]s the plugin registered, and every
+ * input is hashed as it would be with the same `options`. The hashes are
+ * written to `outputs`, see [`BatchOutput`]. Plugins that don't export it
+ * are called once per input instead.
+ *
+ * Returns `0` on success, or the negative [`ErrorCode`] of the first input
+ * that failed. Like a [`HashFunction`], it must never unwind, and
+ * [`hash_batch!`](crate::hash_batch) catches panics for it.
+ */
+typedef int32_t (*BatchHashFunction)(HashFunction hasher,
+                                     const struct BatchInput *inputs,
+                                     const struct CHashOptions *options,
+                                     struct BatchOutput *outputs,
+                                     struct ErrorMessage *error);
+
+typedef struct HashFlags {
+  uint16_t bits;
+} HashFlags;
+#define HashFlags_SEEDED (HashFlags){ .bits = (uint16_t)1 }
+#define HashFlags_SECRET (HashFlags){ .bits = (uint16_t)2 }
+#define HashFlags_KEYED (HashFlags){ .bits = (uint16_t)4 }
+
+/**
+ * The `RegisterFunc` type defines the function signature that Atlas
+ * uses to register plugins.
+ */
+typedef void (*RegisterFunc)(const char *name, struct HashFlags flags, HashFunction hasher);
+
+/**
+ * The `RegisterHashersFunc` type defines the signature of the `register_hashers`
+ * function every plugin exports, which calls `register_hasher` once per hasher.
+ */
+typedef void (*RegisterHashersFunc)(RegisterFunc register_hasher);
+
+/**
+ * A numeric parameter a hasher declares, such as a variant or an output size,
+ * so one registration can cover every combination.
+ *
+ * The accepted values are `min`, `min + step`, `min + 2 * step`, ... up to `max`.
+ */
+typedef struct HashParam {
+  const char *name;
+  uint64_t min;
+  uint64_t max;
+  uint64_t step;
+  /**
+   * The value used when the caller doesn't set one (`default` is a C keyword).
+   */
+  uint64_t default_value;
+} HashParam;
+
+/**
+ * The `RegisterParamFunc` type defines the function signature that Atlas
+ * uses to declare a parameter of a hasher the plugin registered.
+ */
+typedef void (*RegisterParamFunc)(const char *hasher, const struct HashParam *param);
+
+/**
+ * The `RegisterParamsFunc` type defines the signature of the optional
+ * `register_params` function a plugin may export.
+ *
+ * It calls `register_param` once per [`HashParam`], in the order the hasher reads them.
+ */
+typedef void (*RegisterParamsFunc)(RegisterParamFunc register_param);
+
+#endif  /* ATLAS_PLUGIN_H */
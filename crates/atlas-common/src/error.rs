@@ -0,0 +1,181 @@
+/// Status codes returned by a [`HashFunction`](crate::HashFunction).
+///
+/// `0` means success, every error is negative.
+#[repr(i32)]
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum ErrorCode {
+    Ok = 0,
+    /// A required pointer argument was null.
+    NullPointer = -1,
+    /// A pointer argument wasn't aligned for its type.
+    BadAlignment = -2,
+    /// The hasher needs an option (seed, key or secret) that wasn't given.
+    MissingOption = -3,
+    /// The seed doesn't fit in the hasher's seed width.
+    InvalidSeedWidth = -4,
+    /// The secret is shorter than the hasher's minimum.
+    SecretTooShort = -5,
+    /// The hasher can't process input of this length.
+    UnsupportedInputLength = -6,
+    /// The hasher panicked.
+    InternalPanic = -7,
+    /// The underlying implementation reported an error.
+    Internal = -8,
+    /// The output buffer is too small; its `len` has been set to the size needed.
+    OutputTooSmall = -9,
+    /// The input isn't valid for the hasher, e.g. a string hash given invalid UTF-8.
+    InvalidInput = -10,
+    /// The key isn't a length the hasher accepts.
+    InvalidKeyLength = -11,
+    /// A parameter value isn't one the hasher declared.
+    InvalidParameter = -12,
+}
+
+impl ErrorCode {
+    /// Converts a raw status code returned over FFI, if it is a known one.
+    ///
+    /// # Examples
+    /// ```
+    /// use atlas_common::ErrorCode;
+    ///
+    /// assert_eq!(ErrorCode::from_i32(-1), Some(ErrorCode::NullPointer));
+    /// assert_eq!(ErrorCode::from_i32(-1000), None);
+    /// ```
+    pub fn from_i32(code: i32) -> Option<Self> {
+        Some(match code {
+            0 => Self::Ok,
+            -1 => Self::NullPointer,
+            -2 => Self::BadAlignment,
+            -3 => Self::MissingOption,
+            -4 => Self::InvalidSeedWidth,
+            -5 => Self::SecretTooShort,
+            -6 => Self::UnsupportedInputLength,
+            -7 => Self::InternalPanic,
+            -8 => Self::Internal,
+            -9 => Self::OutputTooSmall,
+            -10 => Self::InvalidInput,
+            -11 => Self::InvalidKeyLength,
+            -12 => Self::InvalidParameter,
+            _ => return None,
+        })
+    }
+
+    /// A human readable description of the code.
+    pub fn message(self) -> &'static str {
+        match self {
+            Self::Ok => "Success",
+            Self::NullPointer => "A required pointer was null",
+            Self::BadAlignment => "A pointer was not correctly aligned",
+            Self::MissingOption => "A required option was not provided",
+            Self::InvalidSeedWidth => "The seed does not fit in the hasher's seed width",
+            Self::SecretTooShort => "The secret is too short",
+            Self::UnsupportedInputLength => "The input length is not supported",
+            Self::InternalPanic => "The hasher panicked",
+            Self::Internal => "The hasher reported an internal error",
+            Self::OutputTooSmall => "The output buffer is too small",
+            Self::InvalidInput => "The input is not valid for this hasher",
+            Self::InvalidKeyLength => "The key has the wrong length",
+            Self::InvalidParameter => "A parameter value is not accepted by this hasher",
+        }
+    }
+}
+
+impl std::fmt::Display for ErrorCode {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(self.message())
+    }
+}
+
+/// A caller-owned buffer a hasher can write an error message into.
+///
+/// The caller allocates the buffer and sets `capacity`, the hasher writes at
+/// most `capacity` bytes of UTF-8 and sets `len`. Passing a null
+/// `*mut ErrorMessage` to a hasher is allowed, in which case no message is written.
+#[repr(C)]
+pub struct ErrorMessage {
+    pub ptr: *mut u8,
+    pub capacity: usize,
+    pub len: usize,
+}
+
+impl ErrorMessage {
+    /// Creates an empty message backed by `buffer`.
+    ///
+    /// # Examples
+    /// ```
+    /// use atlas_common::ErrorMessage;
+    ///
+    /// let mut buffer = [0u8; 64];
+    /// let mut message = ErrorMessage::new(&mut buffer);
+    /// message.write("seed too large");
+    /// assert_eq!(message.as_str(), Some("seed too large"));
+    /// ```
+    pub fn new(buffer: &mut [u8]) -> Self {
+        Self {
+            ptr: buffer.as_mut_ptr(),
+            capacity: buffer.len(),
+            len: 0,
+        }
+    }
+
+    /// Writes `message`, truncating it on a character boundary if it doesn't fit.
+    pub fn write(&mut self, message: &str) {
+        if self.ptr.is_null() {
+            return;
+        }
+        let mut len = message.len().min(self.capacity);
+        while !message.is_char_boundary(len) {
+            len -= 1;
+        }
+        // SAFETY: `ptr` is non-null and valid for `capacity` bytes, and `len <= capacity`.
+        unsafe { std::ptr::copy_nonoverlapping(message.as_ptr(), self.ptr, len) };
+        self.len = len;
+    }
+
+    /// The message written so far, or `None` if nothing valid was written.
+    pub fn as_str(&self) -> Option<&str> {
+        if self.ptr.is_null() || self.len == 0 || self.len > self.capacity {
+            return None;
+        }
+        // SAFETY: `ptr` is non-null and valid for `capacity` bytes, and `len <= capacity`.
+        let bytes = unsafe { std::slice::from_raw_parts(self.ptr, self.len) };
+        std::str::from_utf8(bytes).ok()
+    }
+}
+
+/// An error returned from a hasher body, converted to an [`ErrorCode`] at the FFI boundary.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct PluginError {
+    pub code: ErrorCode,
+    pub message: Option<String>,
+}
+
+impl PluginError {
+    /// An error with a message more specific than the code's default one.
+    pub fn new(code: ErrorCode, message: impl Into<String>) -> Self {
+        Self {
+            code,
+            message: Some(message.into()),
+        }
+    }
+}
+
+impl From<ErrorCode> for PluginError {
+    fn from(code: ErrorCode) -> Self {
+        Self {
+            code,
+            message: None,
+        }
+    }
+}
+
+/// Extracts the message from a panic payload caught with [`std::panic::catch_unwind`].
+pub fn panic_message(payload: &(dyn std::any::Any + Send)) -> &str {
+    if let Some(message) = payload.downcast_ref::<&str>() {
+        message
+    } else if let Some(message) = payload.downcast_ref::<String>() {
+        message
+    } else {
+        "Box<dyn Any>"
+    }
+}
@@ -1,12 +1,26 @@
 //! Common types used in Atlas.
 //!
 //! Provides an FFI friendly boxed ``Slice<T>``, and an opaque data type ``CHashOptions``
-//! for sending seeds/secrets to the hashing functions.
+//! for sending seeds/secrets and declared [`HashParam`] values to the hashing functions.
+//!
+//! Memory never changes hands across the plugin boundary: inputs and options
+//! are borrowed from the host, and hashes are written into a host-allocated
+//! [`OutputBuffer`], so the host and plugins may use different allocators.
+//!
+//! Hashers report failures with an [`ErrorCode`], and can optionally describe
+//! them in a caller-owned [`ErrorMessage`]. The [`plugin`] module has helpers
+//! for writing plugins.
 
 use ruint::Uint;
 
+mod error;
+pub mod plugin;
+
+pub use error::{ErrorCode, ErrorMessage, PluginError, panic_message};
+
 bitflags::bitflags! {
     #[repr(C)]
+    #[derive(Clone, Copy, Debug, PartialEq, Eq)]
     pub struct HashFlags: u16 {
         const SEEDED = 0b0000_0001;
         const SECRET = 0b0000_0010;
@@ -14,9 +28,67 @@ bitflags::bitflags! {
     }
 }
 
+/// The option passed to a hasher, borrowed from the host for the duration of the call.
+///
+/// `data` depends on the hasher's [`HashFlags`]: a `u64` seed for
+/// [`SEEDED`](HashFlags::SEEDED), or a `Slice<u8>` of key or secret bytes for
+/// [`KEYED`](HashFlags::KEYED) and [`SECRET`](HashFlags::SECRET). It is null
+/// when the hasher takes no options.
+///
+/// `params` holds a value for every [`HashParam`] the hasher declared, in the
+/// order they were declared, and is empty for hashers that declare none.
 #[repr(C)]
 pub struct CHashOptions {
     pub data: *const (),
+    pub params: Slice<u64>,
+}
+
+/// A numeric parameter a hasher declares, such as a variant or an output size,
+/// so one registration can cover every combination.
+///
+/// The accepted values are `min`, `min + step`, `min + 2 * step`, ... up to `max`.
+#[repr(C)]
+pub struct HashParam {
+    pub name: *const std::ffi::c_char,
+    pub min: u64,
+    pub max: u64,
+    pub step: u64,
+    /// The value used when the caller doesn't set one (`default` is a C keyword).
+    pub default_value: u64,
+}
+
+impl HashParam {
+    /// Declares a parameter named `name`.
+    pub const fn new(
+        name: &'static std::ffi::CStr,
+        min: u64,
+        max: u64,
+        step: u64,
+        default_value: u64,
+    ) -> Self {
+        Self {
+            name: name.as_ptr(),
+            min,
+            max,
+            step,
+            default_value,
+        }
+    }
+
+    /// Whether `value` is one of the parameter's accepted values.
+    ///
+    /// # Examples
+    /// ```
+    /// use atlas_common::HashParam;
+    ///
+    /// let bits = HashParam::new(c"bits", 128, 192, 32, 192);
+    /// assert!(bits.accepts(160));
+    /// assert!(!bits.accepts(176));
+    /// assert!(!bits.accepts(224));
+    /// ```
+    pub fn accepts(&self, value: u64) -> bool {
+        (self.min..=self.max).contains(&value) && (value - self.min) % self.step.max(1) == 0
+    }
 }
 
 #[repr(C)]
@@ -42,6 +114,26 @@ impl<T> Slice<T> {
         Self { ptr, len }
     }
 
+    /// Initializes an FFI-friendly `Slice<T>` borrowing `slice`.
+    ///
+    /// The returned `Slice<T>` must not outlive `slice`, and must not be
+    /// converted back with `into_boxed_slice`.
+    ///
+    /// # Examples
+    /// ```
+    /// use atlas_common::Slice;
+    ///
+    /// let data = [0x10, 0x00, 0x40, 0x20];
+    /// let slice = Slice::from_ref(&data);
+    /// assert_eq!(slice.try_as_slice(), Ok(&data[..]));
+    /// ```
+    pub fn from_ref(slice: &[T]) -> Self {
+        Self {
+            ptr: slice.as_ptr().cast_mut(),
+            len: slice.len(),
+        }
+    }
+
     /// Initializes an FFI-friendly `Slice<T>` from a `Vec<T>`
     ///
     /// This function is a wrapper around `from_boxed_slice`,
@@ -82,6 +174,29 @@ impl<T> Slice<T> {
         }
     }
 
+    /// Borrows the Slice's data, without taking ownership of it.
+    ///
+    /// # Examples
+    /// ```
+    /// use atlas_common::Slice;
+    ///
+    /// let slice = Slice::from_vec(vec![0x10, 0x00, 0x40, 0x20]);
+    /// assert_eq!(slice.try_as_slice(), Ok(&[0x10, 0x00, 0x40, 0x20][..]));
+    /// # let _ = slice.into_boxed_slice();
+    /// ```
+    pub fn try_as_slice(&self) -> Result<&[T], ErrorCode> {
+        if self.ptr.is_null() {
+            Err(ErrorCode::NullPointer)
+        } else if !self.ptr.is_aligned() {
+            Err(ErrorCode::BadAlignment)
+        } else if self.len > isize::MAX as usize / size_of::<T>().max(1) {
+            Err(ErrorCode::UnsupportedInputLength)
+        } else {
+            // SAFETY: the pointer is guaranteed to be non-null, aligned, and the len to be in bounds
+            Ok(unsafe { std::slice::from_raw_parts(self.ptr, self.len) })
+        }
+    }
+
     pub fn as_mut_slice(&self) -> Option<&mut [T]> {
         if self.ptr.is_null() || !self.ptr.is_aligned() || self.len > isize::MAX as usize {
             None
@@ -92,17 +207,156 @@ impl<T> Slice<T> {
     }
 }
 
+/// A caller-owned buffer a hasher writes its hash into.
+///
+/// The caller allocates the buffer and sets `capacity`. The hasher writes the
+/// hash and sets `len`, or if the hash doesn't fit, sets `len` to the size
+/// needed and returns [`ErrorCode::OutputTooSmall`] so the caller can retry
+/// with a larger buffer.
+#[repr(C)]
+pub struct OutputBuffer {
+    pub ptr: *mut u8,
+    pub capacity: usize,
+    pub len: usize,
+}
+
+impl OutputBuffer {
+    /// Creates an empty output backed by `buffer`.
+    ///
+    /// # Examples
+    /// ```
+    /// use atlas_common::{ErrorCode, OutputBuffer};
+    ///
+    /// let mut buffer = [0u8; 4];
+    /// let mut output = OutputBuffer::new(&mut buffer);
+    /// assert_eq!(output.write(&[0x12, 0x34, 0x56, 0x78, 0x9A]), Err(ErrorCode::OutputTooSmall));
+    /// assert_eq!(output.len, 5);
+    ///
+    /// assert_eq!(output.write(&[0x12, 0x34]), Ok(()));
+    /// assert_eq!(output.as_slice(), Some(&[0x12, 0x34][..]));
+    /// ```
+    pub fn new(buffer: &mut [u8]) -> Self {
+        Self {
+            ptr: buffer.as_mut_ptr(),
+            capacity: buffer.len(),
+            len: 0,
+        }
+    }
+
+    /// Writes `hash` into the buffer, or records its size if it doesn't fit.
+    pub fn write(&mut self, hash: &[u8]) -> Result<(), ErrorCode> {
+        self.len = hash.len();
+        if hash.len() > self.capacity {
+            return Err(ErrorCode::OutputTooSmall);
+        }
+        if self.ptr.is_null() {
+            return Err(ErrorCode::NullPointer);
+        }
+        // SAFETY: `ptr` is non-null and valid for `capacity` bytes, and the hash fits.
+        unsafe { std::ptr::copy_nonoverlapping(hash.as_ptr(), self.ptr, hash.len()) };
+        Ok(())
+    }
+
+    /// The hash written to the buffer, or `None` if it didn't fit.
+    pub fn as_slice(&self) -> Option<&[u8]> {
+        if self.ptr.is_null() || self.len > self.capacity {
+            return None;
+        }
+        // SAFETY: `ptr` is non-null and valid for `capacity` bytes, and `len <= capacity`.
+        Some(unsafe { std::slice::from_raw_parts(self.ptr, self.len) })
+    }
+}
+
 /// The `HashFunction` type defines the function signature that all
 /// hashing plugins for Atlas must use.
-pub type HashFunction =
-    unsafe extern "C" fn(data: *mut Slice<u8>, options: *const CHashOptions) -> i32;
+///
+/// `input` holds the data to hash, borrowed for the duration of the call.
+/// `options` may be null, otherwise its `data` points to the option matching
+/// the hasher's [`HashFlags`], also borrowed. The hash is written to `output`,
+/// see [`OutputBuffer`]. `error` may be null, otherwise the hasher may write a
+/// message describing a failure into it.
+///
+/// Returns `0` on success, or a negative [`ErrorCode`]. Hashers must never
+/// unwind across this boundary: the function is `extern "C"`, so a panic or
+/// C++ exception escaping it aborts the host. Plugins catch their own panics
+/// and report them as [`ErrorCode::InternalPanic`], which
+/// [`hash_function!`](crate::hash_function) does for them.
+pub type HashFunction = unsafe extern "C" fn(
+    input: *const Slice<u8>,
+    options: *const CHashOptions,
+    output: *mut OutputBuffer,
+    error: *mut ErrorMessage,
+) -> i32;
+
+/// Many inputs packed into one buffer, borrowed from the host for a
+/// [`BatchHashFunction`] call.
+///
+/// Input `i` is `data[offsets[i]..offsets[i + 1]]`, so `offsets` holds one
+/// more entry than there are inputs.
+#[repr(C)]
+pub struct BatchInput {
+    pub data: Slice<u8>,
+    pub offsets: Slice<usize>,
+}
+
+/// A caller-owned array a [`BatchHashFunction`] writes fixed-width hashes into.
+///
+/// The caller allocates `width` bytes per input and sets `width`. The hasher
+/// writes hash `i` at `ptr + i * width`, and sets `len` to the number of hashes
+/// written, which on failure is the index of the input that failed. If a hash
+/// isn't exactly `width` bytes, the hasher sets `width` to its length and
+/// returns [`ErrorCode::OutputTooSmall`], so the caller can retry. If that
+/// happens after the first hash, with `len` above zero, the hashes differ in
+/// width and no retry will fit them.
+#[repr(C)]
+pub struct BatchOutput {
+    pub ptr: *mut u8,
+    pub width: usize,
+    pub len: usize,
+}
+
+/// The `BatchHashFunction` type defines the signature of the optional
+/// `hash_batch` function a plugin may export, to hash many inputs in one call.
+///
+/// `hasher` is one of the [`HashFunction`]s the plugin registered, and every
+/// input is hashed as it would be with the same `options`. The hashes are
+/// written to `outputs`, see [`BatchOutput`]. Plugins that don't export it
+/// are called once per input instead.
+///
+/// Returns `0` on success, or the negative [`ErrorCode`] of the first input
+/// that failed. Like a [`HashFunction`], it must never unwind, and
+/// [`hash_batch!`](crate::hash_batch) catches panics for it.
+pub type BatchHashFunction = unsafe extern "C" fn(
+    hasher: HashFunction,
+    inputs: *const BatchInput,
+    options: *const CHashOptions,
+    outputs: *mut BatchOutput,
+    error: *mut ErrorMessage,
+) -> i32;
 
 /// The `RegisterFunc` type defines the function signature that Atlas
 /// uses to register plugins.
-pub type RegisterFunc = extern "C" fn(name: *const i8, flags: HashFlags, hasher: HashFunction);
+pub type RegisterFunc =
+    extern "C" fn(name: *const std::ffi::c_char, flags: HashFlags, hasher: HashFunction);
+
+/// The `RegisterHashersFunc` type defines the signature of the `register_hashers`
+/// function every plugin exports, which calls `register_hasher` once per hasher.
+pub type RegisterHashersFunc = extern "C" fn(register_hasher: RegisterFunc);
+
+/// The `RegisterParamFunc` type defines the function signature that Atlas
+/// uses to declare a parameter of a hasher the plugin registered.
+pub type RegisterParamFunc =
+    extern "C" fn(hasher: *const std::ffi::c_char, param: *const HashParam);
+
+/// The `RegisterParamsFunc` type defines the signature of the optional
+/// `register_params` function a plugin may export.
+///
+/// It calls `register_param` once per [`HashParam`], in the order the hasher reads them.
+pub type RegisterParamsFunc = extern "C" fn(register_param: RegisterParamFunc);
 
 /// `HashResult` is a wrapper over a string, with helpers to convert from common
 /// hashing results.
+#[derive(Clone, Debug, PartialEq, Eq)]
 pub struct HashResult(pub String);
 
 impl HashResult {
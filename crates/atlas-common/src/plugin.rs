@@ -0,0 +1,385 @@
+//! Helpers for writing hashing plugins.
+//!
+//! Plugins declare their entry points with [`hash_function!`](crate::hash_function),
+//! which takes care of validating the FFI arguments and reporting errors back
+//! to the host, so hasher bodies only deal with safe types. Invoking
+//! [`hash_batch!`](crate::hash_batch) once exports a batch entry point for
+//! every hasher in the plugin.
+
+use std::marker::PhantomData;
+use std::panic::AssertUnwindSafe;
+
+use crate::{
+    BatchInput, BatchOutput, CHashOptions, ErrorCode, ErrorMessage, HashFunction, OutputBuffer,
+    PluginError, Slice, panic_message,
+};
+
+/// A safe view over the [`CHashOptions`] passed to a hasher.
+#[derive(Clone, Copy)]
+pub struct Options<'a> {
+    data: *const (),
+    params: &'a [u64],
+    _marker: PhantomData<&'a CHashOptions>,
+}
+
+impl Options<'_> {
+    /// Reads the value `data` points to, or `None` if no option was given.
+    fn get<T>(&self) -> Result<Option<&T>, PluginError> {
+        let data = self.data.cast::<T>();
+        if data.is_null() {
+            Ok(None)
+        } else if !data.is_aligned() {
+            Err(ErrorCode::BadAlignment.into())
+        } else {
+            // SAFETY: the host guarantees `data` points to a `T` matching the hasher's flags
+            // for the duration of the call, and we just checked it's non-null and aligned.
+            Ok(Some(unsafe { &*data }))
+        }
+    }
+
+    /// The seed for hashers flagged with [`HashFlags::SEEDED`](crate::HashFlags::SEEDED),
+    /// defaulting to `0`.
+    pub fn seed(&self) -> Result<u64, PluginError> {
+        Ok(self.get::<u64>()?.copied().unwrap_or(0))
+    }
+
+    /// The seed for hashers that take a 32-bit seed.
+    pub fn seed_u32(&self) -> Result<u32, PluginError> {
+        let seed = self.seed()?;
+        u32::try_from(seed).map_err(|_err| {
+            PluginError::new(
+                ErrorCode::InvalidSeedWidth,
+                format!("Seed {seed} does not fit in 32 bits"),
+            )
+        })
+    }
+
+    /// The key for hashers flagged with [`HashFlags::KEYED`](crate::HashFlags::KEYED),
+    /// which must be exactly `N` bytes long. Defaults to all zeros when no key was given.
+    pub fn key<const N: usize>(&self) -> Result<[u8; N], PluginError> {
+        let key = match self.get::<Slice<u8>>()? {
+            Some(key) => key.try_as_slice()?,
+            None => &[],
+        };
+        if key.is_empty() {
+            return Ok([0; N]);
+        }
+        key.try_into().map_err(|_err| {
+            PluginError::new(
+                ErrorCode::InvalidKeyLength,
+                format!("The key must be {N} bytes, got {}", key.len()),
+            )
+        })
+    }
+
+    /// The key for hashers flagged with [`HashFlags::KEYED`](crate::HashFlags::KEYED)
+    /// that take keys of any length up to `max_len` bytes. Empty when no key was given.
+    pub fn key_bytes(&self, max_len: usize) -> Result<&[u8], PluginError> {
+        let key = match self.get::<Slice<u8>>()? {
+            Some(key) => key.try_as_slice()?,
+            None => &[],
+        };
+        if key.len() > max_len {
+            return Err(PluginError::new(
+                ErrorCode::InvalidKeyLength,
+                format!("The key must be at most {max_len} bytes, got {}", key.len()),
+            ));
+        }
+        Ok(key)
+    }
+
+    /// The values of the hasher's `N` declared [`HashParam`](crate::HashParam)s,
+    /// in the order they were declared.
+    ///
+    /// The host checks values against the declared ranges, so hashers only need
+    /// to reject values a range can't express.
+    pub fn params<const N: usize>(&self) -> Result<[u64; N], PluginError> {
+        self.params.try_into().map_err(|_err| {
+            PluginError::new(
+                ErrorCode::MissingOption,
+                format!(
+                    "This hasher takes {N} parameters, got {}",
+                    self.params.len()
+                ),
+            )
+        })
+    }
+
+    /// The secret for hashers flagged with [`HashFlags::SECRET`](crate::HashFlags::SECRET),
+    /// which must be at least `min_len` bytes long.
+    pub fn secret(&self, min_len: usize) -> Result<&[u8], PluginError> {
+        let Some(secret) = self.get::<Slice<u8>>()? else {
+            return Err(PluginError::new(
+                ErrorCode::MissingOption,
+                "This hasher requires a secret",
+            ));
+        };
+        let secret = secret.try_as_slice()?;
+        if secret.len() < min_len {
+            return Err(PluginError::new(
+                ErrorCode::SecretTooShort,
+                format!(
+                    "The secret must be at least {min_len} bytes, got {}",
+                    secret.len()
+                ),
+            ));
+        }
+        Ok(secret)
+    }
+}
+
+/// Runs a hasher body behind an FFI entry point.
+///
+/// The input is read from `input`, and on success the hash returned by `body`
+/// is written to `output`. On failure, the error's message is written to `error`
+/// and its code is returned. Panics in `body` are caught and reported as
+/// [`ErrorCode::InternalPanic`], as unwinding into the host would abort it.
+///
+/// # Safety
+/// The arguments must follow the contract of [`HashFunction`](crate::HashFunction).
+pub unsafe fn call<H: AsRef<[u8]>>(
+    input: *const Slice<u8>,
+    options: *const CHashOptions,
+    output: *mut OutputBuffer,
+    error: *mut ErrorMessage,
+    body: impl FnOnce(&[u8], Options<'_>) -> Result<H, PluginError>,
+) -> i32 {
+    let result = std::panic::catch_unwind(AssertUnwindSafe(|| {
+        // SAFETY: forwarded from the caller.
+        unsafe { call_inner(input, options, output, body) }
+    }))
+    .unwrap_or_else(|payload| Err(panicked(&*payload)));
+    match result {
+        Ok(()) => ErrorCode::Ok as i32,
+        Err(e) => {
+            // SAFETY: forwarded from the caller.
+            unsafe { report(error, e.message.as_deref().unwrap_or(e.code.message())) };
+            e.code as i32
+        }
+    }
+}
+
+/// The error a caught panic is reported as.
+fn panicked(payload: &(dyn std::any::Any + Send)) -> PluginError {
+    PluginError::new(
+        ErrorCode::InternalPanic,
+        format!("The hasher panicked: {}", panic_message(payload)),
+    )
+}
+
+/// Writes `message` to `error`, unless it's null or misaligned.
+///
+/// # Safety
+/// A non-null, aligned `error` must point to a valid [`ErrorMessage`].
+unsafe fn report(error: *mut ErrorMessage, message: &str) {
+    if !error.is_null() && error.is_aligned() {
+        // SAFETY: the caller guarantees a non-null `error` points to a valid message.
+        unsafe { &mut *error }.write(message);
+    }
+}
+
+unsafe fn call_inner<H: AsRef<[u8]>>(
+    input: *const Slice<u8>,
+    options: *const CHashOptions,
+    output: *mut OutputBuffer,
+    body: impl FnOnce(&[u8], Options<'_>) -> Result<H, PluginError>,
+) -> Result<(), PluginError> {
+    if input.is_null() || output.is_null() {
+        return Err(ErrorCode::NullPointer.into());
+    }
+    if !input.is_aligned() || !options.is_aligned() || !output.is_aligned() {
+        return Err(ErrorCode::BadAlignment.into());
+    }
+    // SAFETY: `options` is aligned, and points to valid options when non-null.
+    let options = match unsafe { options.as_ref() } {
+        Some(options) => Options {
+            data: options.data,
+            params: if options.params.len == 0 {
+                &[]
+            } else {
+                options.params.try_as_slice()?
+            },
+            _marker: PhantomData,
+        },
+        None => Options {
+            data: std::ptr::null(),
+            params: &[],
+            _marker: PhantomData,
+        },
+    };
+
+    // SAFETY: `input` and `output` are non-null, aligned, and point to valid values.
+    let (input, output) = unsafe { (&*input, &mut *output) };
+    let hash = body(input.try_as_slice()?, options)?;
+    output.write(hash.as_ref())?;
+    Ok(())
+}
+
+/// Hashes every input of a batch with `hasher`, writing straight into the
+/// caller's output array.
+///
+/// # Safety
+/// The arguments must follow the contract of [`BatchHashFunction`](crate::BatchHashFunction).
+pub unsafe fn batch(
+    hasher: HashFunction,
+    inputs: *const BatchInput,
+    options: *const CHashOptions,
+    outputs: *mut BatchOutput,
+    error: *mut ErrorMessage,
+) -> i32 {
+    let result = std::panic::catch_unwind(AssertUnwindSafe(|| {
+        // SAFETY: forwarded from the caller.
+        unsafe { batch_inner(hasher, inputs, options, outputs, error) }
+    }))
+    .unwrap_or_else(|payload| {
+        let e = panicked(&*payload);
+        // SAFETY: forwarded from the caller.
+        unsafe { report(error, e.message.as_deref().unwrap_or(e.code.message())) };
+        Err(e.code as i32)
+    });
+    match result {
+        Ok(()) => ErrorCode::Ok as i32,
+        Err(code) => code,
+    }
+}
+
+unsafe fn batch_inner(
+    hasher: HashFunction,
+    inputs: *const BatchInput,
+    options: *const CHashOptions,
+    outputs: *mut BatchOutput,
+    error: *mut ErrorMessage,
+) -> Result<(), i32> {
+    if inputs.is_null() || outputs.is_null() {
+        return Err(ErrorCode::NullPointer as i32);
+    }
+    if !inputs.is_aligned() || !outputs.is_aligned() {
+        return Err(ErrorCode::BadAlignment as i32);
+    }
+    // SAFETY: `inputs` and `outputs` are non-null, aligned, and point to valid values.
+    let (inputs, outputs) = unsafe { (&*inputs, &mut *outputs) };
+    outputs.len = 0;
+    let data = match inputs.data.len {
+        0 => &[],
+        _ => inputs.data.try_as_slice().map_err(|code| code as i32)?,
+    };
+    let offsets = inputs.offsets.try_as_slice().map_err(|code| code as i32)?;
+    let count = offsets.len().saturating_sub(1);
+    let width = outputs.width;
+    let size = count
+        .checked_mul(width)
+        .ok_or(ErrorCode::UnsupportedInputLength as i32)?;
+    if outputs.ptr.is_null() && size > 0 {
+        return Err(ErrorCode::NullPointer as i32);
+    }
+    let hashes = if size == 0 {
+        &mut []
+    } else {
+        // SAFETY: the caller guarantees `ptr` is valid for `width` bytes per input.
+        unsafe { std::slice::from_raw_parts_mut(outputs.ptr, size) }
+    };
+
+    for (i, range) in offsets.windows(2).enumerate() {
+        let input = data
+            .get(range[0]..range[1])
+            .ok_or(ErrorCode::InvalidInput as i32)?;
+        let input = Slice::from_ref(input);
+        let mut output = OutputBuffer::new(&mut hashes[i * width..(i + 1) * width]);
+        // SAFETY: every pointer is valid for the duration of the call, and
+        // `options` and `error` are passed on as the caller gave them.
+        let ret = unsafe { hasher(&raw const input, options, &raw mut output, error) };
+        if ret != ErrorCode::Ok as i32 {
+            if ret == ErrorCode::OutputTooSmall as i32 {
+                outputs.width = output.len;
+            }
+            return Err(ret);
+        }
+        if output.len != width {
+            outputs.width = output.len;
+            return Err(ErrorCode::OutputTooSmall as i32);
+        }
+        outputs.len = i + 1;
+    }
+    Ok(())
+}
+
+/// Declares a [`HashFunction`](crate::HashFunction) entry point from a safe body.
+///
+/// The body receives the input bytes and the hasher's [`Options`], and returns
+/// the hash bytes or a [`PluginError`]. Fixed-width hashers return an array,
+/// which is written straight into the host's buffer without allocating. A
+/// body whose hash type can't be inferred, such as one that only panics, names
+/// it after the arguments: `fn name(input, options) -> Vec<u8>`.
+///
+/// # Examples
+/// ```
+/// use atlas_common::hash_function;
+///
+/// hash_function! {
+///     /// Sums every byte of the input, wrapping on overflow.
+///     pub fn byte_sum(input, options) {
+///         let seed = options.seed_u32()?;
+///         let sum = input.iter().fold(seed, |sum, b| sum.wrapping_add(u32::from(*b)));
+///         Ok(sum.to_be_bytes())
+///     }
+/// }
+/// ```
+#[macro_export]
+macro_rules! hash_function {
+    (
+        $(#[$attr:meta])*
+        $vis:vis fn $name:ident($input:pat, $options:pat) $(-> $hash:ty)? $body:block
+    ) => {
+        $(#[$attr])*
+        ///
+        /// # Safety
+        /// The arguments must follow the contract of `atlas_common::HashFunction`.
+        #[unsafe(no_mangle)]
+        $vis unsafe extern "C" fn $name(
+            input: *const $crate::Slice<u8>,
+            options: *const $crate::CHashOptions,
+            output: *mut $crate::OutputBuffer,
+            error: *mut $crate::ErrorMessage,
+        ) -> i32 {
+            // SAFETY: the caller upholds the `HashFunction` contract.
+            unsafe {
+                $crate::plugin::call(
+                    input,
+                    options,
+                    output,
+                    error,
+                    |$input: &[u8], $options: $crate::plugin::Options<'_>|
+                        $(-> Result<$hash, $crate::PluginError>)? { $body },
+                )
+            }
+        }
+    };
+}
+
+/// Exports `hash_batch`, the plugin's [`BatchHashFunction`](crate::BatchHashFunction),
+/// which hashes a batch with any of the plugin's hashers.
+///
+/// # Examples
+/// ```
+/// atlas_common::hash_batch!();
+/// ```
+#[macro_export]
+macro_rules! hash_batch {
+    () => {
+        /// Hashes many inputs with one of this plugin's hashers.
+        ///
+        /// # Safety
+        /// The arguments must follow the contract of `atlas_common::BatchHashFunction`.
+        #[unsafe(no_mangle)]
+        pub unsafe extern "C" fn hash_batch(
+            hasher: $crate::HashFunction,
+            inputs: *const $crate::BatchInput,
+            options: *const $crate::CHashOptions,
+            outputs: *mut $crate::BatchOutput,
+            error: *mut $crate::ErrorMessage,
+        ) -> i32 {
+            // SAFETY: the caller upholds the `BatchHashFunction` contract.
+            unsafe { $crate::plugin::batch(hasher, inputs, options, outputs, error) }
+        }
+    };
+}
@@ -0,0 +1,29 @@
+//! Checks that `include/atlas_plugin.h` matches the Rust ABI types.
+
+use std::path::Path;
+
+#[test]
+fn header_is_up_to_date() {
+    let crate_dir = Path::new(env!("CARGO_MANIFEST_DIR"));
+    let config = cbindgen::Config::from_file(crate_dir.join("cbindgen.toml"))
+        .expect("Failed to read cbindgen.toml");
+    let bindings = cbindgen::Builder::new()
+        .with_crate(crate_dir)
+        .with_config(config)
+        .generate()
+        .expect("Failed to generate the C header");
+
+    let mut generated = Vec::new();
+    bindings.write(&mut generated);
+    let generated = String::from_utf8(generated).expect("The C header isn't valid UTF-8");
+
+    let header_path = crate_dir.join("include").join("atlas_plugin.h");
+    if std::env::var_os("ATLAS_UPDATE_HEADER").is_some() {
+        std::fs::write(&header_path, &generated).expect("Failed to write the C header");
+    }
+    let header = std::fs::read_to_string(&header_path).unwrap_or_default();
+    assert!(
+        header == generated,
+        "include/atlas_plugin.h is out of date, regenerate it with `ATLAS_UPDATE_HEADER=1 cargo test -p atlas-common --test header`"
+    );
+}
@@ -0,0 +1,205 @@
+//! CRCs in the Rocksoft model used by Greg Cook's catalogue of parametrised CRC
+//! algorithms, <https://reveng.sourceforge.io/crc-catalogue/>.
+//!
+//! A model is a width of 1 to 64 bits, a polynomial without its top bit, the
+//! initial register, whether input bytes and the output are reflected, and a
+//! value XOR-ed into the output.
+//!
+//! Catalogue entries are [`Table`]s built at compile time, which take a byte
+//! per step. [`Model::checksum`] goes a bit at a time, for models only known
+//! at runtime, where building a table would cost more than most inputs.
+
+/// The parameters of a CRC.
+#[derive(Clone, Copy)]
+pub struct Model {
+    pub width: u32,
+    pub poly: u64,
+    pub init: u64,
+    pub refin: bool,
+    pub refout: bool,
+    pub xorout: u64,
+}
+
+pub const CRC_8: Table = table(8, 0x07, 0, false, false, 0);
+pub const CRC_8_MAXIM_DOW: Table = table(8, 0x31, 0, true, true, 0);
+pub const CRC_16_ARC: Table = table(16, 0x8005, 0, true, true, 0);
+pub const CRC_16_IBM_3740: Table = table(16, 0x1021, 0xffff, false, false, 0);
+pub const CRC_16_KERMIT: Table = table(16, 0x1021, 0, true, true, 0);
+pub const CRC_16_MODBUS: Table = table(16, 0x8005, 0xffff, true, true, 0);
+pub const CRC_16_XMODEM: Table = table(16, 0x1021, 0, false, false, 0);
+pub const CRC_32: Table = table(32, 0x04c11db7, 0xffffffff, true, true, 0xffffffff);
+pub const CRC_32_BZIP2: Table = table(32, 0x04c11db7, 0xffffffff, false, false, 0xffffffff);
+pub const CRC_32_CKSUM: Table = table(32, 0x04c11db7, 0, false, false, 0xffffffff);
+pub const CRC_32_JAMCRC: Table = table(32, 0x04c11db7, 0xffffffff, true, true, 0);
+pub const CRC_32_MPEG_2: Table = table(32, 0x04c11db7, 0xffffffff, false, false, 0);
+pub const CRC_32C: Table = table(32, 0x1edc6f41, 0xffffffff, true, true, 0xffffffff);
+pub const CRC_64_ECMA_182: Table = table(64, 0x42f0e1eba9ea3693, 0, false, false, 0);
+pub const CRC_64_GO_ISO: Table = table(64, 0x1b, u64::MAX, true, true, u64::MAX);
+pub const CRC_64_XZ: Table = table(64, 0x42f0e1eba9ea3693, u64::MAX, true, true, u64::MAX);
+
+/// A catalogue entry, with its table built at compile time.
+const fn table(width: u32, poly: u64, init: u64, refin: bool, refout: bool, xorout: u64) -> Table {
+    Table::new(Model::new(width, poly, init, refin, refout, xorout))
+}
+
+impl Model {
+    pub const fn new(
+        width: u32,
+        poly: u64,
+        init: u64,
+        refin: bool,
+        refout: bool,
+        xorout: u64,
+    ) -> Self {
+        Self {
+            width,
+            poly,
+            init,
+            refin,
+            refout,
+            xorout,
+        }
+    }
+
+    /// The bits of a `width` wide register.
+    pub fn mask(&self) -> u64 {
+        u64::MAX >> (64 - self.width)
+    }
+
+    pub fn checksum(&self, data: &[u8]) -> u64 {
+        let top = 1 << (self.width - 1);
+        let mask = self.mask();
+        let mut crc = self.init;
+        for byte in data {
+            let byte = if self.refin {
+                byte.reverse_bits()
+            } else {
+                *byte
+            };
+            for bit in (0..8).rev() {
+                let carry = (crc & top != 0) ^ ((byte >> bit) & 1 != 0);
+                crc = (crc << 1) & mask;
+                if carry {
+                    crc ^= self.poly;
+                }
+            }
+        }
+        if self.refout {
+            crc = crc.reverse_bits() >> (64 - self.width);
+        }
+        crc ^ self.xorout
+    }
+
+    /// The checksum of `data` as big-endian bytes, as many as the width needs.
+    pub fn checksum_bytes(&self, data: &[u8]) -> Checksum {
+        Checksum::new(self.width, self.checksum(data))
+    }
+}
+
+/// A checksum as big-endian bytes, as many as its width needs, kept on the
+/// stack so hashing doesn't allocate.
+pub struct Checksum {
+    bytes: [u8; 8],
+    len: usize,
+}
+
+impl Checksum {
+    fn new(width: u32, value: u64) -> Self {
+        Self {
+            bytes: value.to_be_bytes(),
+            len: width.div_ceil(8) as usize,
+        }
+    }
+}
+
+impl AsRef<[u8]> for Checksum {
+    fn as_ref(&self) -> &[u8] {
+        &self.bytes[8 - self.len..]
+    }
+}
+
+/// A model with what each byte does to the register worked out ahead.
+///
+/// A reflected model keeps its register reflected, in the low `width` bits,
+/// and the others keep it in the high `width` bits, so both shift a whole
+/// byte out whatever the width.
+pub struct Table {
+    model: Model,
+    table: [u64; 256],
+}
+
+impl Table {
+    pub const fn new(model: Model) -> Self {
+        let mut table = [0; 256];
+        let mut i = 0;
+        while i < 256 {
+            let mut crc = i as u64;
+            let mut bit = 0;
+            if model.refin {
+                let poly = model.poly.reverse_bits() >> (64 - model.width);
+                while bit < 8 {
+                    crc = if crc & 1 != 0 {
+                        (crc >> 1) ^ poly
+                    } else {
+                        crc >> 1
+                    };
+                    bit += 1;
+                }
+            } else {
+                let poly = model.poly << (64 - model.width);
+                crc <<= 56;
+                while bit < 8 {
+                    crc = if crc >> 63 != 0 {
+                        (crc << 1) ^ poly
+                    } else {
+                        crc << 1
+                    };
+                    bit += 1;
+                }
+            }
+            table[i] = crc;
+            i += 1;
+        }
+        Self { model, table }
+    }
+
+    pub fn checksum(&self, data: &[u8]) -> u64 {
+        let Model {
+            width,
+            init,
+            refin,
+            refout,
+            xorout,
+            ..
+        } = self.model;
+        let crc = if refin {
+            let mut crc = init.reverse_bits() >> (64 - width);
+            for byte in data {
+                crc = (crc >> 8) ^ self.table[usize::from(crc as u8 ^ byte)];
+            }
+            // The register is already reflected.
+            if refout {
+                crc
+            } else {
+                crc.reverse_bits() >> (64 - width)
+            }
+        } else {
+            let mut crc = init << (64 - width);
+            for byte in data {
+                crc = (crc << 8) ^ self.table[usize::from((crc >> 56) as u8 ^ byte)];
+            }
+            let crc = crc >> (64 - width);
+            if refout {
+                crc.reverse_bits() >> (64 - width)
+            } else {
+                crc
+            }
+        };
+        crc ^ xorout
+    }
+
+    /// The checksum of `data` as big-endian bytes, as many as the width needs.
+    pub fn checksum_bytes(&self, data: &[u8]) -> Checksum {
+        Checksum::new(self.model.width, self.checksum(data))
+    }
+}
@@ -0,0 +1,183 @@
+use atlas_common::{
+    ErrorCode, HashFlags, HashParam, PluginError, RegisterFunc, RegisterParamFunc, hash_function,
+};
+
+mod crc;
+
+use crc::Model;
+
+#[unsafe(no_mangle)]
+pub extern "C" fn register_hashers(register: RegisterFunc) {
+    register(c"CRC".as_ptr(), HashFlags::empty(), crc);
+
+    register(c"CRC-8".as_ptr(), HashFlags::empty(), crc8);
+    register(
+        c"CRC-8/MAXIM-DOW".as_ptr(),
+        HashFlags::empty(),
+        crc8_maxim_dow,
+    );
+    register(c"CRC-16/ARC".as_ptr(), HashFlags::empty(), crc16_arc);
+    register(
+        c"CRC-16/IBM-3740".as_ptr(),
+        HashFlags::empty(),
+        crc16_ibm_3740,
+    );
+    register(c"CRC-16/KERMIT".as_ptr(), HashFlags::empty(), crc16_kermit);
+    register(c"CRC-16/MODBUS".as_ptr(), HashFlags::empty(), crc16_modbus);
+    register(c"CRC-16/XMODEM".as_ptr(), HashFlags::empty(), crc16_xmodem);
+    register(c"CRC-32".as_ptr(), HashFlags::empty(), crc32);
+    register(c"CRC-32/BZIP2".as_ptr(), HashFlags::empty(), crc32_bzip2);
+    register(c"CRC-32/CKSUM".as_ptr(), HashFlags::empty(), crc32_cksum);
+    register(c"CRC-32/JAMCRC".as_ptr(), HashFlags::empty(), crc32_jamcrc);
+    register(c"CRC-32/MPEG-2".as_ptr(), HashFlags::empty(), crc32_mpeg2);
+    register(c"CRC-32C".as_ptr(), HashFlags::empty(), crc32c);
+    register(
+        c"CRC-64/ECMA-182".as_ptr(),
+        HashFlags::empty(),
+        crc64_ecma_182,
+    );
+    register(c"CRC-64/GO-ISO".as_ptr(), HashFlags::empty(), crc64_go_iso);
+    register(c"CRC-64/XZ".as_ptr(), HashFlags::empty(), crc64_xz);
+}
+
+atlas_common::hash_batch!();
+
+/// The parameters of the generic `CRC` hasher, which default to CRC-32.
+#[unsafe(no_mangle)]
+pub extern "C" fn register_params(register: RegisterParamFunc) {
+    let params = [
+        HashParam::new(c"width", 1, 64, 1, 32),
+        HashParam::new(c"poly", 0, u64::MAX, 1, 0x04c11db7),
+        HashParam::new(c"init", 0, u64::MAX, 1, 0xffffffff),
+        HashParam::new(c"refin", 0, 1, 1, 1),
+        HashParam::new(c"refout", 0, 1, 1, 1),
+        HashParam::new(c"xorout", 0, u64::MAX, 1, 0xffffffff),
+    ];
+    for param in &params {
+        register(c"CRC".as_ptr(), param);
+    }
+}
+
+hash_function! {
+    /// Any CRC of up to 64 bits, described by its `width`, `poly`, `init`,
+    /// `refin`, `refout` and `xorout` parameters.
+    pub fn crc(input, options) {
+        let [width, poly, init, refin, refout, xorout] = options.params()?;
+        let model = Model::new(width as u32, poly, init, refin == 1, refout == 1, xorout);
+        for (name, value) in [("poly", poly), ("init", init), ("xorout", xorout)] {
+            if value & !model.mask() != 0 {
+                return Err(PluginError::new(
+                    ErrorCode::InvalidParameter,
+                    format!("The {name} parameter {value:#x} is wider than {width} bits"),
+                ));
+            }
+        }
+        Ok(model.checksum_bytes(input))
+    }
+}
+
+hash_function! {
+    /// Also known as CRC-8/SMBUS.
+    pub fn crc8(input, _) {
+        Ok(crc::CRC_8.checksum_bytes(input))
+    }
+}
+
+hash_function! {
+    /// The Dallas/Maxim 1-Wire CRC.
+    pub fn crc8_maxim_dow(input, _) {
+        Ok(crc::CRC_8_MAXIM_DOW.checksum_bytes(input))
+    }
+}
+
+hash_function! {
+    /// Also known as CRC-16 and CRC-IBM.
+    pub fn crc16_arc(input, _) {
+        Ok(crc::CRC_16_ARC.checksum_bytes(input))
+    }
+}
+
+hash_function! {
+    /// Also known as CRC-16/CCITT-FALSE.
+    pub fn crc16_ibm_3740(input, _) {
+        Ok(crc::CRC_16_IBM_3740.checksum_bytes(input))
+    }
+}
+
+hash_function! {
+    /// Also known as CRC-16/CCITT.
+    pub fn crc16_kermit(input, _) {
+        Ok(crc::CRC_16_KERMIT.checksum_bytes(input))
+    }
+}
+
+hash_function! {
+    pub fn crc16_modbus(input, _) {
+        Ok(crc::CRC_16_MODBUS.checksum_bytes(input))
+    }
+}
+
+hash_function! {
+    pub fn crc16_xmodem(input, _) {
+        Ok(crc::CRC_16_XMODEM.checksum_bytes(input))
+    }
+}
+
+hash_function! {
+    /// The CRC-32 of zlib, PNG and Ethernet, CRC-32/ISO-HDLC in the catalogue.
+    pub fn crc32(input, _) {
+        Ok(crc::CRC_32.checksum_bytes(input))
+    }
+}
+
+hash_function! {
+    /// The unreflected CRC-32, as used by bzip2 and Unreal's `FCrc::StrCrc`.
+    pub fn crc32_bzip2(input, _) {
+        Ok(crc::CRC_32_BZIP2.checksum_bytes(input))
+    }
+}
+
+hash_function! {
+    /// The CRC of POSIX `cksum`, without the length `cksum` appends to the input.
+    pub fn crc32_cksum(input, _) {
+        Ok(crc::CRC_32_CKSUM.checksum_bytes(input))
+    }
+}
+
+hash_function! {
+    /// CRC-32 without the final inversion.
+    pub fn crc32_jamcrc(input, _) {
+        Ok(crc::CRC_32_JAMCRC.checksum_bytes(input))
+    }
+}
+
+hash_function! {
+    pub fn crc32_mpeg2(input, _) {
+        Ok(crc::CRC_32_MPEG_2.checksum_bytes(input))
+    }
+}
+
+hash_function! {
+    /// Castagnoli's CRC, CRC-32/ISCSI in the catalogue.
+    pub fn crc32c(input, _) {
+        Ok(crc::CRC_32C.checksum_bytes(input))
+    }
+}
+
+hash_function! {
+    pub fn crc64_ecma_182(input, _) {
+        Ok(crc::CRC_64_ECMA_182.checksum_bytes(input))
+    }
+}
+
+hash_function! {
+    pub fn crc64_go_iso(input, _) {
+        Ok(crc::CRC_64_GO_ISO.checksum_bytes(input))
+    }
+}
+
+hash_function! {
+    pub fn crc64_xz(input, _) {
+        Ok(crc::CRC_64_XZ.checksum_bytes(input))
+    }
+}
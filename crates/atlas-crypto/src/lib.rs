@@ -0,0 +1,140 @@
+use atlas_common::{
+    ErrorCode, HashFlags, HashParam, PluginError, RegisterFunc, RegisterParamFunc, hash_function,
+};
+use paste::paste;
+use ripemd::Digest as _;
+
+/// The longest output `BLAKE3-XOF` produces.
+const MAX_XOF_LEN: u64 = 1 << 16;
+
+#[unsafe(no_mangle)]
+pub extern "C" fn register_hashers(register: RegisterFunc) {
+    register(c"BLAKE2b".as_ptr(), HashFlags::KEYED, blake2b);
+    register(c"BLAKE2s".as_ptr(), HashFlags::KEYED, blake2s);
+
+    register(c"BLAKE3".as_ptr(), HashFlags::empty(), blake3_hash);
+    register(c"BLAKE3-keyed".as_ptr(), HashFlags::KEYED, blake3_keyed);
+    register(
+        c"BLAKE3-derive-key".as_ptr(),
+        HashFlags::KEYED,
+        blake3_derive_key,
+    );
+    register(c"BLAKE3-XOF".as_ptr(), HashFlags::empty(), blake3_xof);
+
+    register(c"RIPEMD-128".as_ptr(), HashFlags::empty(), ripemd_128);
+    register(c"RIPEMD-160".as_ptr(), HashFlags::empty(), ripemd_160);
+    register(c"RIPEMD-256".as_ptr(), HashFlags::empty(), ripemd_256);
+    register(c"RIPEMD-320".as_ptr(), HashFlags::empty(), ripemd_320);
+}
+
+atlas_common::hash_batch!();
+
+#[unsafe(no_mangle)]
+pub extern "C" fn register_params(register: RegisterParamFunc) {
+    register(
+        c"BLAKE2b".as_ptr(),
+        &HashParam::new(c"bits", 8, 512, 8, 512),
+    );
+    register(
+        c"BLAKE2s".as_ptr(),
+        &HashParam::new(c"bits", 8, 256, 8, 256),
+    );
+    register(
+        c"BLAKE3-XOF".as_ptr(),
+        &HashParam::new(c"len", 1, MAX_XOF_LEN, 1, blake3::OUT_LEN as u64),
+    );
+}
+
+fn invalid_param(name: &str, value: u64) -> PluginError {
+    PluginError::new(
+        ErrorCode::InvalidParameter,
+        format!("{value} is not a valid value for the {name} parameter"),
+    )
+}
+
+macro_rules! blake2_impl {
+    ($variant:ident) => {
+        paste! {
+            hash_function! {
+                /// Hashes to `bits`, a whole number of bytes. The key may be up
+                /// to the digest's block size, an empty key gives the unkeyed hash.
+                pub fn $variant(input, options) {
+                    let [bits] = options.params()?;
+                    let max = [< $variant _simd >]::OUTBYTES as u64 * 8;
+                    if bits == 0 || bits > max || bits % 8 != 0 {
+                        return Err(invalid_param("bits", bits));
+                    }
+                    let key = options.key_bytes([< $variant _simd >]::KEYBYTES)?;
+                    let hash = [< $variant _simd >]::Params::new()
+                        .hash_length(bits as usize / 8)
+                        .key(key)
+                        .hash(input);
+                    Ok(hash.as_bytes().to_vec())
+                }
+            }
+        }
+    };
+}
+
+blake2_impl!(blake2b);
+blake2_impl!(blake2s);
+
+hash_function! {
+    pub fn blake3_hash(input, _) {
+        Ok(*blake3::hash(input).as_bytes())
+    }
+}
+
+hash_function! {
+    pub fn blake3_keyed(input, options) {
+        let key = options.key::<{ blake3::KEY_LEN }>()?;
+        Ok(*blake3::keyed_hash(&key, input).as_bytes())
+    }
+}
+
+hash_function! {
+    /// The context string is passed as the key.
+    pub fn blake3_derive_key(input, options) {
+        let context = std::str::from_utf8(options.key_bytes(usize::MAX)?).map_err(|e| {
+            PluginError::new(
+                ErrorCode::InvalidInput,
+                format!("The context is not valid UTF-8: {e}"),
+            )
+        })?;
+        Ok(blake3::derive_key(context, input).to_vec())
+    }
+}
+
+hash_function! {
+    /// Reads `len` bytes of output.
+    pub fn blake3_xof(input, options) {
+        let [len] = options.params()?;
+        if len == 0 || len > MAX_XOF_LEN {
+            return Err(invalid_param("len", len));
+        }
+        let mut hash = vec![0; len as usize];
+        blake3::Hasher::new()
+            .update(input)
+            .finalize_xof()
+            .fill(&mut hash);
+        Ok(hash)
+    }
+}
+
+macro_rules! ripemd_impl {
+    ($bits:expr) => {
+        paste! {
+            hash_function! {
+                pub fn [< ripemd_ $bits >](input, _) {
+                    let hash = ripemd::[< Ripemd $bits >]::digest(input);
+                    Ok(hash.to_vec())
+                }
+            }
+        }
+    };
+}
+
+ripemd_impl!(128);
+ripemd_impl!(160);
+ripemd_impl!(256);
+ripemd_impl!(320);
@@ -0,0 +1,83 @@
+use atlas_common::{
+    ErrorCode, HashFlags, HashParam, PluginError, RegisterFunc, RegisterParamFunc, hash_function,
+};
+
+mod md6;
+mod streebog;
+mod tiger;
+mod whirlpool;
+
+#[unsafe(no_mangle)]
+pub extern "C" fn register_hashers(register: RegisterFunc) {
+    register(c"Tiger".as_ptr(), HashFlags::empty(), tiger);
+    register(c"Whirlpool".as_ptr(), HashFlags::empty(), whirlpool);
+    register(c"MD6".as_ptr(), HashFlags::KEYED, md6);
+    register(c"Streebog-256".as_ptr(), HashFlags::empty(), streebog_256);
+    register(c"Streebog-512".as_ptr(), HashFlags::empty(), streebog_512);
+}
+
+atlas_common::hash_batch!();
+
+#[unsafe(no_mangle)]
+pub extern "C" fn register_params(register: RegisterParamFunc) {
+    register(c"Tiger".as_ptr(), &HashParam::new(c"variant", 1, 2, 1, 1));
+    register(
+        c"Tiger".as_ptr(),
+        &HashParam::new(c"bits", 128, 192, 32, 192),
+    );
+    register(c"MD6".as_ptr(), &HashParam::new(c"bits", 8, 512, 8, 256));
+}
+
+fn invalid_param(name: &str, value: u64) -> PluginError {
+    PluginError::new(
+        ErrorCode::InvalidParameter,
+        format!("{value} is not a valid value for the {name} parameter"),
+    )
+}
+
+hash_function! {
+    /// Tiger (`variant=1`) or Tiger2 (`variant=2`), truncated to `bits`.
+    pub fn tiger(input, options) {
+        let [variant, bits] = options.params()?;
+        let padding = match variant {
+            1 => 0x01,
+            2 => 0x80,
+            _ => return Err(invalid_param("variant", variant)),
+        };
+        if !matches!(bits, 128 | 160 | 192) {
+            return Err(invalid_param("bits", bits));
+        }
+        let hash = tiger::hash(input, padding);
+        Ok(hash[..bits as usize / 8].to_vec())
+    }
+}
+
+hash_function! {
+    pub fn whirlpool(input, _) {
+        Ok(whirlpool::hash(input).to_vec())
+    }
+}
+
+hash_function! {
+    /// The key may be up to 64 bytes, an empty key gives the unkeyed hash.
+    pub fn md6(input, options) {
+        let [bits] = options.params()?;
+        if bits == 0 || bits > 512 || bits % 8 != 0 {
+            return Err(invalid_param("bits", bits));
+        }
+        let key = options.key_bytes(64)?;
+        Ok(md6::hash(input, bits as usize, key))
+    }
+}
+
+hash_function! {
+    pub fn streebog_256(input, _) {
+        Ok(streebog::hash256(input).to_vec())
+    }
+}
+
+hash_function! {
+    pub fn streebog_512(input, _) {
+        Ok(streebog::hash512(input).to_vec())
+    }
+}
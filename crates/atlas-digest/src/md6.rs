@@ -0,0 +1,115 @@
+//! MD6 in its default, fully hierarchical mode, from the submission to the NIST
+//! SHA-3 competition at <https://groups.csail.mit.edu/cis/md6/>.
+
+/// Words in a compression input: `Q`, the key, the node ID, the control word and the block.
+const N: usize = 89;
+/// Words in a chaining value.
+const C: usize = 16;
+/// Bytes in a block.
+const BLOCK_LEN: usize = 512;
+/// The height a tree may reach before MD6 would switch to its sequential mode.
+const MAX_LEVEL: u64 = 64;
+
+/// The fractional part of `sqrt(6)`.
+const Q: [u64; 15] = [
+    0x7311c2812425cfa0,
+    0x6432286434aac8e7,
+    0xb60450e9ef68b7c1,
+    0xe8fb23908d9f06f1,
+    0xdd2e76cba691e5bf,
+    0x0cd0d63b2c30bc41,
+    0x1f8ccf6823058f8a,
+    0x54e5ed5b88e3775d,
+    0x4ad12aae0a6d6031,
+    0x3e7f16bb88222e0d,
+    0x8af8671d3fb50c2c,
+    0x995ad1178bd25c31,
+    0xc878c1dd04c4b633,
+    0x3b72066c7a1552ac,
+    0x0d6f3522631effcb,
+];
+
+const RIGHT_SHIFTS: [u32; 16] = [10, 5, 13, 10, 11, 12, 2, 7, 14, 15, 7, 13, 11, 7, 6, 12];
+const LEFT_SHIFTS: [u32; 16] = [11, 24, 9, 16, 15, 9, 27, 15, 6, 2, 29, 8, 15, 5, 31, 9];
+
+const S0: u64 = 0x0123456789abcdef;
+const S_MASK: u64 = 0x7311c2812425cfa0;
+
+fn read_words(bytes: &[u8], words: &mut [u64]) {
+    for (word, chunk) in words.iter_mut().zip(bytes.chunks_exact(8)) {
+        let mut buffer = [0u8; 8];
+        buffer.copy_from_slice(chunk);
+        *word = u64::from_be_bytes(buffer);
+    }
+}
+
+/// The compression function, returning the last 16 words of the feedback shift register.
+fn compress(input: &[u64; N], rounds: usize) -> [u64; C] {
+    let mut a = Vec::with_capacity(N + rounds * C);
+    a.extend_from_slice(input);
+    let mut s = S0;
+    for _ in 0..rounds {
+        for step in 0..C {
+            let i = a.len();
+            let mut x = s ^ a[i - N] ^ a[i - 17];
+            x ^= (a[i - 18] & a[i - 21]) ^ (a[i - 31] & a[i - 67]);
+            x ^= x >> RIGHT_SHIFTS[step];
+            a.push(x ^ (x << LEFT_SHIFTS[step]));
+        }
+        s = s.rotate_left(1) ^ (s & S_MASK);
+    }
+    let mut output = [0; C];
+    output.copy_from_slice(&a[a.len() - C..]);
+    output
+}
+
+/// The `bits`-bit hash of `data`, with `bits` a multiple of 8 from 8 to 512,
+/// and an optional key of up to 64 bytes.
+pub fn hash(data: &[u8], bits: usize, key: &[u8]) -> Vec<u8> {
+    let rounds = if key.is_empty() {
+        40 + bits / 4
+    } else {
+        (40 + bits / 4).max(80)
+    };
+    let mut input = [0u64; N];
+    input[..Q.len()].copy_from_slice(&Q);
+    let mut padded_key = [0u8; 64];
+    padded_key[..key.len()].copy_from_slice(key);
+    read_words(&padded_key, &mut input[15..23]);
+
+    // Each level compresses every 512 byte block of the one below into a
+    // 128 byte chaining value, until a single block is left.
+    let mut message = data.to_vec();
+    let mut level = 1u64;
+    loop {
+        let mut blocks: Vec<&[u8]> = message.chunks(BLOCK_LEN).collect();
+        if blocks.is_empty() {
+            // An empty message is still one (all padding) block.
+            blocks.push(&[]);
+        }
+        let is_final = blocks.len() == 1;
+        let mut next = Vec::with_capacity(blocks.len() * C * 8);
+        for (index, block) in blocks.iter().enumerate() {
+            let padding_bits = ((BLOCK_LEN - block.len()) * 8) as u64;
+            input[23] = (level << 56) | index as u64;
+            input[24] = ((rounds as u64) << 48)
+                | (MAX_LEVEL << 40)
+                | (u64::from(is_final) << 36)
+                | (padding_bits << 20)
+                | ((key.len() as u64) << 12)
+                | bits as u64;
+            let mut padded = [0u8; BLOCK_LEN];
+            padded[..block.len()].copy_from_slice(block);
+            read_words(&padded, &mut input[25..]);
+            for word in compress(&input, rounds) {
+                next.extend_from_slice(&word.to_be_bytes());
+            }
+        }
+        message = next;
+        if is_final {
+            break;
+        }
+        level += 1;
+    }
+    message.split_off(message.len() - bits / 8)
+}
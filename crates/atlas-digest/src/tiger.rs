@@ -0,0 +1,138 @@
+//! Tiger and Tiger2, from <https://www.cs.technion.ac.il/~biham/Reports/Tiger/>.
+//!
+//! Tiger2 only differs in its padding byte.
+
+use std::sync::LazyLock;
+
+const IV: [u64; 3] = [0x0123456789abcdef, 0xfedcba9876543210, 0xf096a5b4c3b2e187];
+
+/// The four S-boxes, back to back.
+type SBoxes = [u64; 1024];
+
+/// The S-boxes are generated from Tiger itself the way the reference
+/// implementation's `gen` program does, rather than pasted as 8 KiB of constants.
+static SBOXES: LazyLock<SBoxes> = LazyLock::new(generate_sboxes);
+
+fn generate_sboxes() -> SBoxes {
+    const PASSES: usize = 5;
+    let message = b"Tiger - A Fast New Hash Function, by Ross Anderson and Eli Biham";
+
+    let mut sboxes = [0u64; 1024];
+    for (i, entry) in sboxes.iter_mut().enumerate() {
+        *entry = u64::from_ne_bytes([i as u8; 8]);
+    }
+    let mut state = IV;
+    let mut abc = 2;
+    for _ in 0..PASSES {
+        for i in 0..256 {
+            for sbox in (0..1024).step_by(256) {
+                abc += 1;
+                if abc == 3 {
+                    abc = 0;
+                    compress(&sboxes, &mut state, message);
+                }
+                // Swap each byte of the entry with the same byte of the entry
+                // the matching state byte points to.
+                for (column, other) in state[abc].to_le_bytes().into_iter().enumerate() {
+                    let j = sbox + usize::from(other);
+                    let mask = 0xff << (column * 8);
+                    let (a, b) = (sboxes[sbox + i], sboxes[j]);
+                    sboxes[sbox + i] = (a & !mask) | (b & mask);
+                    sboxes[j] = (b & !mask) | (a & mask);
+                }
+            }
+        }
+    }
+    sboxes
+}
+
+fn round(sboxes: &SBoxes, a: &mut u64, b: &mut u64, c: &mut u64, x: u64, mul: u64) {
+    *c ^= x;
+    let c_bytes = c.to_le_bytes().map(usize::from);
+    *a = a.wrapping_sub(
+        sboxes[c_bytes[0]]
+            ^ sboxes[256 + c_bytes[2]]
+            ^ sboxes[512 + c_bytes[4]]
+            ^ sboxes[768 + c_bytes[6]],
+    );
+    *b = b.wrapping_add(
+        sboxes[768 + c_bytes[1]]
+            ^ sboxes[512 + c_bytes[3]]
+            ^ sboxes[256 + c_bytes[5]]
+            ^ sboxes[c_bytes[7]],
+    );
+    *b = b.wrapping_mul(mul);
+}
+
+fn pass(sboxes: &SBoxes, a: &mut u64, b: &mut u64, c: &mut u64, x: &[u64; 8], mul: u64) {
+    round(sboxes, a, b, c, x[0], mul);
+    round(sboxes, b, c, a, x[1], mul);
+    round(sboxes, c, a, b, x[2], mul);
+    round(sboxes, a, b, c, x[3], mul);
+    round(sboxes, b, c, a, x[4], mul);
+    round(sboxes, c, a, b, x[5], mul);
+    round(sboxes, a, b, c, x[6], mul);
+    round(sboxes, b, c, a, x[7], mul);
+}
+
+fn key_schedule(x: &mut [u64; 8]) {
+    x[0] = x[0].wrapping_sub(x[7] ^ 0xa5a5a5a5a5a5a5a5);
+    x[1] ^= x[0];
+    x[2] = x[2].wrapping_add(x[1]);
+    x[3] = x[3].wrapping_sub(x[2] ^ (!x[1] << 19));
+    x[4] ^= x[3];
+    x[5] = x[5].wrapping_add(x[4]);
+    x[6] = x[6].wrapping_sub(x[5] ^ (!x[4] >> 23));
+    x[7] ^= x[6];
+    x[0] = x[0].wrapping_add(x[7]);
+    x[1] = x[1].wrapping_sub(x[0] ^ (!x[7] << 19));
+    x[2] ^= x[1];
+    x[3] = x[3].wrapping_add(x[2]);
+    x[4] = x[4].wrapping_sub(x[3] ^ (!x[2] >> 23));
+    x[5] ^= x[4];
+    x[6] = x[6].wrapping_add(x[5]);
+    x[7] = x[7].wrapping_sub(x[6] ^ 0x0123456789abcdef);
+}
+
+fn compress(sboxes: &SBoxes, state: &mut [u64; 3], block: &[u8]) {
+    let mut x: [u64; 8] = std::array::from_fn(|i| {
+        let mut word = [0u8; 8];
+        word.copy_from_slice(&block[i * 8..i * 8 + 8]);
+        u64::from_le_bytes(word)
+    });
+    let [mut a, mut b, mut c] = *state;
+    pass(sboxes, &mut a, &mut b, &mut c, &x, 5);
+    key_schedule(&mut x);
+    pass(sboxes, &mut c, &mut a, &mut b, &x, 7);
+    key_schedule(&mut x);
+    pass(sboxes, &mut b, &mut c, &mut a, &x, 9);
+    state[0] ^= a;
+    state[1] = b.wrapping_sub(state[1]);
+    state[2] = c.wrapping_add(state[2]);
+}
+
+/// The 192-bit hash, with `padding` being `0x01` for Tiger and `0x80` for Tiger2.
+pub fn hash(data: &[u8], padding: u8) -> [u8; 24] {
+    let sboxes = &*SBOXES;
+    let mut state = IV;
+    let mut blocks = data.chunks_exact(64);
+    for block in &mut blocks {
+        compress(sboxes, &mut state, block);
+    }
+
+    let remainder = blocks.remainder();
+    let mut last = [0u8; 128];
+    last[..remainder.len()].copy_from_slice(remainder);
+    last[remainder.len()] = padding;
+    let len = if remainder.len() < 56 { 64 } else { 128 };
+    last[len - 8..len].copy_from_slice(&(data.len() as u64).wrapping_mul(8).to_le_bytes());
+    for block in last[..len].chunks_exact(64) {
+        compress(sboxes, &mut state, block);
+    }
+
+    let mut hash = [0u8; 24];
+    for (chunk, word) in hash.chunks_exact_mut(8).zip(state) {
+        chunk.copy_from_slice(&word.to_le_bytes());
+    }
+    hash
+}
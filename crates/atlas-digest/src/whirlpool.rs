@@ -0,0 +1,145 @@
+//! Whirlpool, the final (2003) version adopted by NESSIE and ISO/IEC 10118-3,
+//! from <https://web.archive.org/web/2017/http://www.larc.usp.br/~pbarreto/WhirlpoolPage.html>.
+
+const ROUNDS: usize = 10;
+
+/// Builds the S-box from the `E` and `R` mini-boxes, as the specification does.
+const fn sbox() -> [u8; 256] {
+    const E: [u8; 16] = [1, 11, 9, 12, 13, 6, 15, 3, 14, 8, 7, 4, 10, 2, 5, 0];
+    const R: [u8; 16] = [7, 12, 11, 13, 14, 4, 9, 15, 6, 3, 8, 10, 2, 5, 1, 0];
+    let mut e_inv = [0u8; 16];
+    let mut i = 0;
+    while i < 16 {
+        e_inv[E[i] as usize] = i as u8;
+        i += 1;
+    }
+
+    let mut sbox = [0u8; 256];
+    let mut u = 0;
+    while u < 256 {
+        let high = E[u >> 4];
+        let low = e_inv[u & 15];
+        let r = R[(high ^ low) as usize];
+        sbox[u] = (E[(high ^ r) as usize] << 4) | e_inv[(low ^ r) as usize];
+        u += 1;
+    }
+    sbox
+}
+
+/// Multiplies in GF(2^8) modulo `x^8 + x^4 + x^3 + x^2 + 1`.
+const fn gf_mul(mut a: u8, mut b: u8) -> u8 {
+    let mut product = 0;
+    while b != 0 {
+        if b & 1 != 0 {
+            product ^= a;
+        }
+        let carry = a & 0x80 != 0;
+        a <<= 1;
+        if carry {
+            a ^= 0x1d;
+        }
+        b >>= 1;
+    }
+    product
+}
+
+/// `TABLES[i][x]` is row `x` of the S-box followed by the diffusion matrix,
+/// for the byte in column `i`, with the first column in the top byte.
+const fn tables() -> [[u64; 256]; 8] {
+    const CIRCULANT: [u8; 8] = [1, 1, 4, 1, 8, 5, 2, 9];
+    let sbox = sbox();
+    let mut tables = [[0u64; 256]; 8];
+    let mut x = 0;
+    while x < 256 {
+        let mut row = 0u64;
+        let mut j = 0;
+        while j < 8 {
+            row = (row << 8) | gf_mul(sbox[x], CIRCULANT[j]) as u64;
+            j += 1;
+        }
+        let mut i = 0;
+        while i < 8 {
+            tables[i][x] = row.rotate_right(8 * i as u32);
+            i += 1;
+        }
+        x += 1;
+    }
+    tables
+}
+
+const TABLES: [[u64; 256]; 8] = tables();
+
+const fn round_constants() -> [u64; ROUNDS] {
+    let sbox = sbox();
+    let mut constants = [0u64; ROUNDS];
+    let mut r = 0;
+    while r < ROUNDS {
+        let mut j = 0;
+        while j < 8 {
+            constants[r] = (constants[r] << 8) | sbox[8 * r + j] as u64;
+            j += 1;
+        }
+        r += 1;
+    }
+    constants
+}
+
+const ROUND_CONSTANTS: [u64; ROUNDS] = round_constants();
+
+/// One round on the state's rows, each read as a big-endian word.
+fn round(state: &[u64; 8], key: &[u64; 8]) -> [u64; 8] {
+    std::array::from_fn(|i| {
+        let mut row = key[i];
+        for (j, table) in TABLES.iter().enumerate() {
+            // The cyclic permutation takes column `j` from row `i - j`.
+            let byte = (state[(i + 8 - j) % 8] >> (56 - 8 * j)) as u8;
+            row ^= table[usize::from(byte)];
+        }
+        row
+    })
+}
+
+fn compress(hash: &mut [u64; 8], block: &[u8]) {
+    let block: [u64; 8] = std::array::from_fn(|i| {
+        let mut word = [0u8; 8];
+        word.copy_from_slice(&block[i * 8..i * 8 + 8]);
+        u64::from_be_bytes(word)
+    });
+    let mut key = *hash;
+    let mut state: [u64; 8] = std::array::from_fn(|i| block[i] ^ key[i]);
+    for constant in ROUND_CONSTANTS {
+        let mut round_key = [0; 8];
+        round_key[0] = constant;
+        key = round(&key, &round_key);
+        state = round(&state, &key);
+    }
+    for i in 0..8 {
+        hash[i] ^= state[i] ^ block[i];
+    }
+}
+
+pub fn hash(data: &[u8]) -> [u8; 64] {
+    let mut hash = [0u64; 8];
+    let mut blocks = data.chunks_exact(64);
+    for block in &mut blocks {
+        compress(&mut hash, block);
+    }
+
+    // Pad with a one bit, then zeros up to a 256-bit length field.
+    let remainder = blocks.remainder();
+    let mut last = [0u8; 128];
+    last[..remainder.len()].copy_from_slice(remainder);
+    last[remainder.len()] = 0x80;
+    let len = if remainder.len() < 32 { 64 } else { 128 };
+    let bits = (data.len() as u128).wrapping_mul(8);
+    last[len - 16..len].copy_from_slice(&bits.to_be_bytes());
+    for block in last[..len].chunks_exact(64) {
+        compress(&mut hash, block);
+    }
+
+    let mut output = [0u8; 64];
+    for (chunk, word) in output.chunks_exact_mut(8).zip(hash) {
+        chunk.copy_from_slice(&word.to_be_bytes());
+    }
+    output
+}
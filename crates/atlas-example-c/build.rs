@@ -0,0 +1,39 @@
+//! Compiles `src/example.c` into a plugin library against `atlas_plugin.h`.
+
+use std::path::PathBuf;
+
+fn main() {
+    let out_dir = PathBuf::from(std::env::var_os("OUT_DIR").expect("OUT_DIR is not set"));
+    let target_os = std::env::var("CARGO_CFG_TARGET_OS").expect("CARGO_CFG_TARGET_OS is not set");
+    let library = out_dir.join(match target_os.as_str() {
+        "windows" => "atlas_example_c.dll",
+        "macos" | "ios" => "libatlas_example_c.dylib",
+        _ => "libatlas_example_c.so",
+    });
+
+    let compiler = cc::Build::new()
+        .include("../atlas-common/include")
+        .warnings(true)
+        .get_compiler();
+    let mut command = compiler.to_command();
+    if compiler.is_like_msvc() {
+        command
+            .arg("/LD")
+            .arg("src/example.c")
+            .arg(format!("/Fe{}", library.display()));
+    } else {
+        command
+            .args(["-shared", "-fPIC", "-o"])
+            .arg(&library)
+            .arg("src/example.c");
+    }
+    let status = command.status().expect("Failed to run the C compiler");
+    assert!(status.success(), "Failed to compile src/example.c");
+
+    println!("cargo::rerun-if-changed=src/example.c");
+    println!("cargo::rerun-if-changed=../atlas-common/include/atlas_plugin.h");
+    println!(
+        "cargo::rustc-env=ATLAS_EXAMPLE_C_LIBRARY={}",
+        library.display()
+    );
+}
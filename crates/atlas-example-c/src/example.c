@@ -0,0 +1,95 @@
+/*
+ * An example Atlas plugin written in C.
+ *
+ * Implements 32-bit FNV-1a, with a second registration that takes the offset
+ * basis as its seed. Copy this file as a starting point for wrapping existing
+ * C hash implementations.
+ */
+
+#include <stdint.h>
+#include <string.h>
+
+#include "atlas_plugin.h"
+
+#if defined(_WIN32)
+#define ATLAS_EXPORT __declspec(dllexport)
+#else
+#define ATLAS_EXPORT __attribute__((visibility("default")))
+#endif
+
+#define FNV_32_BASIS 0x811c9dc5u
+#define FNV_32_PRIME 0x01000193u
+
+static void write_error(ErrorMessage *error, const char *message) {
+    if (error == NULL || error->ptr == NULL) {
+        return;
+    }
+    size_t len = strlen(message);
+    if (len > error->capacity) {
+        len = error->capacity;
+    }
+    memcpy(error->ptr, message, len);
+    error->len = len;
+}
+
+/* Hashes are written big-endian, like the Rust plugins do. */
+static int32_t write_u32(OutputBuffer *output, uint32_t hash) {
+    output->len = 4;
+    if (output->capacity < 4) {
+        return ErrorCode_OutputTooSmall;
+    }
+    output->ptr[0] = (uint8_t)(hash >> 24);
+    output->ptr[1] = (uint8_t)(hash >> 16);
+    output->ptr[2] = (uint8_t)(hash >> 8);
+    output->ptr[3] = (uint8_t)hash;
+    return ErrorCode_Ok;
+}
+
+static uint32_t fnv1a_32(const uint8_t *data, size_t len, uint32_t hash) {
+    for (size_t i = 0; i < len; i++) {
+        hash = (hash ^ data[i]) * FNV_32_PRIME;
+    }
+    return hash;
+}
+
+static int32_t check_arguments(const Slice_u8 *input, OutputBuffer *output, ErrorMessage *error) {
+    if (input == NULL || output == NULL || (input->ptr == NULL && input->len != 0)) {
+        write_error(error, "The input and output must not be null");
+        return ErrorCode_NullPointer;
+    }
+    return ErrorCode_Ok;
+}
+
+static int32_t example_fnv1a_32(const Slice_u8 *input, const CHashOptions *options,
+                                OutputBuffer *output, ErrorMessage *error) {
+    (void)options;
+    int32_t ret = check_arguments(input, output, error);
+    if (ret != ErrorCode_Ok) {
+        return ret;
+    }
+    return write_u32(output, fnv1a_32(input->ptr, input->len, FNV_32_BASIS));
+}
+
+static int32_t example_fnv1a_32_basis(const Slice_u8 *input, const CHashOptions *options,
+                                      OutputBuffer *output, ErrorMessage *error) {
+    int32_t ret = check_arguments(input, output, error);
+    if (ret != ErrorCode_Ok) {
+        return ret;
+    }
+    if (options == NULL || options->data == NULL) {
+        write_error(error, "This hasher requires the offset basis as its seed");
+        return ErrorCode_MissingOption;
+    }
+    uint64_t seed = *(const uint64_t *)options->data;
+    if (seed > UINT32_MAX) {
+        write_error(error, "The offset basis must fit in 32 bits");
+        return ErrorCode_InvalidSeedWidth;
+    }
+    return write_u32(output, fnv1a_32(input->ptr, input->len, (uint32_t)seed));
+}
+
+ATLAS_EXPORT void register_hashers(RegisterFunc register_hasher) {
+    HashFlags no_flags = {0};
+    register_hasher("C Example FNV-1a_32", no_flags, example_fnv1a_32);
+    register_hasher("C Example FNV-1a_32 (basis as seed)", HashFlags_SEEDED, example_fnv1a_32_basis);
+}
@@ -0,0 +1,7 @@
+//! An example Atlas plugin written in C, see `src/example.c`.
+//!
+//! The build script compiles it into a dynamic library against
+//! `atlas_plugin.h`; this crate only exposes where that library ended up.
+
+/// The path of the compiled C plugin.
+pub const LIBRARY_PATH: &str = env!("ATLAS_EXAMPLE_C_LIBRARY");
@@ -0,0 +1,62 @@
+//! FNV of any width that is a multiple of 32 bits, up to 1024, with a custom
+//! prime and offset basis.
+//!
+//! Everything is computed in 1024 bits and masked to the width, which gives
+//! the same result as computing in the width itself.
+
+use ruint::Uint;
+
+pub type Wide = Uint<1024, 16>;
+
+/// The string the standard offset bases are the FNV-0 hash of, from
+/// <http://www.isthe.com/chongo/tech/comp/fnv/>.
+pub const CHONGO: &[u8] = b"chongo <Landon Curt Noll> /\\../\\";
+
+#[derive(Clone, Copy, PartialEq, Eq)]
+pub enum Variant {
+    Fnv0,
+    Fnv1,
+    Fnv1a,
+}
+
+/// The standard prime for `bits`, `2^e + b` for the `e` and `b` FNV chose.
+/// Only the six standard widths have one.
+pub fn standard_prime(bits: usize) -> Option<Wide> {
+    let (exponent, low) = match bits {
+        32 => (24, 0x193),
+        64 => (40, 0x1b3),
+        128 => (88, 0x13b),
+        256 => (168, 0x163),
+        512 => (344, 0x157),
+        1024 => (680, 0x18d),
+        _ => return None,
+    };
+    Some((Wide::from(1u8) << exponent) | Wide::from(low))
+}
+
+/// The standard offset basis for `bits` with `prime`, the FNV-0 hash of [`CHONGO`].
+pub fn offset_basis(bits: usize, prime: Wide) -> Wide {
+    hash(CHONGO, bits, prime, Wide::ZERO, Variant::Fnv0)
+}
+
+pub fn hash(data: &[u8], bits: usize, prime: Wide, basis: Wide, variant: Variant) -> Wide {
+    let mask = Wide::MAX >> (Wide::BITS - bits);
+    let mut hash = match variant {
+        Variant::Fnv0 => Wide::ZERO,
+        Variant::Fnv1 | Variant::Fnv1a => basis & mask,
+    };
+    for byte in data {
+        let byte = Wide::from(*byte);
+        hash = match variant {
+            Variant::Fnv0 | Variant::Fnv1 => hash.wrapping_mul(prime) ^ byte,
+            Variant::Fnv1a => (hash ^ byte).wrapping_mul(prime),
+        } & mask;
+    }
+    hash
+}
+
+/// The `bits / 8` big-endian bytes of `value`.
+pub fn to_bytes(value: Wide, bits: usize) -> Vec<u8> {
+    let bytes = value.to_be_bytes_vec();
+    bytes[bytes.len() - bits / 8..].to_vec()
+}
@@ -1,10 +1,16 @@
 #![allow(dead_code)]
-use atlas_common::{CHashOptions, HashFlags, RegisterFunc, Slice};
+use atlas_common::{
+    ErrorCode, HashFlags, HashParam, PluginError, RegisterFunc, RegisterParamFunc, hash_function,
+};
 use num_traits::ToBytes;
 use paste::paste;
 use ruint::{Uint, uint};
 use std::ops::BitXor as _;
 
+mod custom;
+
+use custom::{Variant, Wide};
+
 #[unsafe(no_mangle)]
 pub extern "C" fn register_hashers(register: RegisterFunc) {
     register(c"FNV-0_32".as_ptr(), HashFlags::empty(), fnv0_32);
@@ -27,6 +33,62 @@ pub extern "C" fn register_hashers(register: RegisterFunc) {
     register(c"FNV-1a_256".as_ptr(), HashFlags::empty(), fnv1a_256);
     register(c"FNV-1a_512".as_ptr(), HashFlags::empty(), fnv1a_512);
     register(c"FNV-1a_1024".as_ptr(), HashFlags::empty(), fnv1a_1024);
+
+    register(c"FNV".as_ptr(), HashFlags::KEYED, fnv);
+}
+
+atlas_common::hash_batch!();
+
+#[unsafe(no_mangle)]
+pub extern "C" fn register_params(register: RegisterParamFunc) {
+    register(c"FNV".as_ptr(), &HashParam::new(c"bits", 32, 1024, 32, 32));
+    register(c"FNV".as_ptr(), &HashParam::new(c"variant", 0, 2, 1, 2));
+}
+
+hash_function! {
+    /// FNV-0 (`variant=0`), FNV-1 (`variant=1`) or FNV-1a (`variant=2`) of any
+    /// width that is a multiple of 32 `bits`.
+    ///
+    /// The key is the big-endian prime, optionally followed by the big-endian
+    /// offset basis, each `bits / 8` bytes. Without a basis, it is computed from
+    /// the prime the way the standard ones are. Without a key, the standard
+    /// prime is used, which only exists for the standard widths.
+    pub fn fnv(input, options) {
+        let [bits, variant] = options.params()?;
+        let bits = bits as usize;
+        let variant = match variant {
+            0 => Variant::Fnv0,
+            1 => Variant::Fnv1,
+            _ => Variant::Fnv1a,
+        };
+        let len = bits / 8;
+        let key = options.key_bytes(2 * len)?;
+        let (prime, basis) = match key.len() {
+            0 => {
+                let prime = custom::standard_prime(bits).ok_or_else(|| {
+                    PluginError::new(
+                        ErrorCode::MissingOption,
+                        format!("There is no standard {bits}-bit FNV prime, the key must hold one"),
+                    )
+                })?;
+                (prime, None)
+            }
+            n if n == len => (Wide::from_be_slice(key), None),
+            n if n == 2 * len => (
+                Wide::from_be_slice(&key[..len]),
+                Some(Wide::from_be_slice(&key[len..])),
+            ),
+            n => {
+                return Err(PluginError::new(
+                    ErrorCode::InvalidKeyLength,
+                    format!("The key must be {len} or {} bytes, got {n}", 2 * len),
+                ));
+            }
+        };
+        let basis = basis.unwrap_or_else(|| custom::offset_basis(bits, prime));
+        let hash = custom::hash(input, bits, prime, basis, variant);
+        Ok(custom::to_bytes(hash, bits))
+    }
 }
 
 macro_rules! fnv_impl {
@@ -37,94 +99,40 @@ macro_rules! fnv_impl {
         prime: $prime:expr,
     ) => {
         paste! {
-            #[unsafe(no_mangle)]
-            pub unsafe extern "C" fn [< fnv0 _ $bits >](data: *mut Slice<u8>, options: *const CHashOptions) -> i32 {
-                if data.is_null() {
-                    return -1;
-                }
-                // SAFETY:
-                // This is safe because we just made sure the pointers aren't null.
-                unsafe {
-                    let input = &mut *data;
-                    let Some(input_data) = input.as_mut_slice() else {
-                        return -2;
-                    };
-
-                    if !input.ptr.is_null() {
-                        let _: Option<Box<[u8]>> = input.into_boxed_slice();
-                    }
-
+            hash_function! {
+                pub fn [< fnv0 _ $bits >](input, _) {
                     let prime = $prime;
                     let mut hash: $primitive = $primitive::from(0u8);
-                    for byte in input_data {
+                    for byte in input {
                         hash = hash.wrapping_mul(prime).bitxor($primitive::from(*byte));
                     }
 
-                    let hash_bytes = ToBytes::to_be_bytes(&hash);
-                    let hash_boxed = hash_bytes.as_slice().to_vec().into_boxed_slice();
-                    *data = Slice::from_boxed_slice(hash_boxed);
+                    Ok(ToBytes::to_be_bytes(&hash))
                 }
-                0
             }
 
-            #[unsafe(no_mangle)]
-            pub unsafe extern "C" fn [< fnv1 _ $bits >](data: *mut Slice<u8>, options: *const CHashOptions) -> i32 {
-                if data.is_null() {
-                    return -1;
-                }
-                // SAFETY:
-                // This is safe because we just made sure the pointers aren't null.
-                unsafe {
-                    let input = &mut *data;
-                    let Some(input_data) = input.as_mut_slice() else {
-                        return -2;
-                    };
-
-                    if !input.ptr.is_null() {
-                        let _: Option<Box<[u8]>> = input.into_boxed_slice();
-                    }
-
+            hash_function! {
+                pub fn [< fnv1 _ $bits >](input, _) {
                     let prime = $prime;
                     let mut hash: $primitive = $base;
-                    for byte in input_data {
+                    for byte in input {
                         hash = hash.wrapping_mul(prime).bitxor($primitive::from(*byte));
                     }
 
-                    let hash_bytes = ToBytes::to_be_bytes(&hash);
-                    let hash_boxed = hash_bytes.as_slice().to_vec().into_boxed_slice();
-                    *data = Slice::from_boxed_slice(hash_boxed);
+                    Ok(ToBytes::to_be_bytes(&hash))
                 }
-                0
             }
 
-            #[unsafe(no_mangle)]
-            pub unsafe extern "C" fn [< fnv1a _ $bits >](data: *mut Slice<u8>, options: *const CHashOptions) -> i32 {
-                if data.is_null() {
-                    return -1;
-                }
-                // SAFETY:
-                // This is safe because we just made sure the pointers aren't null.
-                unsafe {
-                    let input = &mut *data;
-                    let Some(input_data) = input.as_mut_slice() else {
-                        return -2;
-                    };
-
-                    if !input.ptr.is_null() {
-                        let _: Option<Box<[u8]>> = input.into_boxed_slice();
-                    }
-
+            hash_function! {
+                pub fn [< fnv1a _ $bits >](input, _) {
                     let prime = $prime;
                     let mut hash: $primitive = $base;
-                    for byte in input_data {
+                    for byte in input {
                         hash = hash.bitxor($primitive::from(*byte)).wrapping_mul(prime);
                     }
 
-                    let hash_bytes = ToBytes::to_be_bytes(&hash);
-                    let hash_boxed = hash_bytes.as_slice().to_vec().into_boxed_slice();
-                    *data = Slice::from_boxed_slice(hash_boxed);
+                    Ok(ToBytes::to_be_bytes(&hash))
                 }
-                0
             }
         }
     };
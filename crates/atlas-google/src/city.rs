@@ -0,0 +1,483 @@
+//! Google's `CityHash` v1.1, from <https://github.com/google/cityhash>.
+//!
+//! Rotations are to the right, as in the reference.
+
+/// Some primes between 2^63 and 2^64 for various uses.
+pub const K0: u64 = 0xc3a5c85c97cb3127;
+pub const K1: u64 = 0xb492b66fbe98f273;
+pub const K2: u64 = 0x9ae16a3b2f90404f;
+/// Magic numbers for 32-bit hashing, copied from Murmur3.
+pub const C1: u32 = 0xcc9e2d51;
+pub const C2: u32 = 0x1b873593;
+/// The multiplier of `Hash128to64`.
+const K_MUL: u64 = 0x9ddfea08eb382d69;
+
+pub fn fetch32(bytes: &[u8]) -> u32 {
+    let mut word = [0u8; 4];
+    word.copy_from_slice(&bytes[..4]);
+    u32::from_le_bytes(word)
+}
+
+pub fn fetch64(bytes: &[u8]) -> u64 {
+    let mut word = [0u8; 8];
+    word.copy_from_slice(&bytes[..8]);
+    u64::from_le_bytes(word)
+}
+
+/// A 32-bit to 32-bit integer hash, copied from Murmur3.
+pub fn fmix(mut h: u32) -> u32 {
+    h ^= h >> 16;
+    h = h.wrapping_mul(0x85ebca6b);
+    h ^= h >> 13;
+    h = h.wrapping_mul(0xc2b2ae35);
+    h ^ (h >> 16)
+}
+
+/// The first half of [`mur`], scrambling a word before it is combined.
+pub fn scramble(a: u32) -> u32 {
+    a.wrapping_mul(C1).rotate_right(17).wrapping_mul(C2)
+}
+
+/// The second half of [`mur`], combining a scrambled word into `h`.
+pub fn combine(h: u32, a: u32) -> u32 {
+    (h ^ a)
+        .rotate_right(19)
+        .wrapping_mul(5)
+        .wrapping_add(0xe6546b64)
+}
+
+/// Murmur3's helper for combining two 32-bit values.
+pub fn mur(a: u32, h: u32) -> u32 {
+    combine(h, scramble(a))
+}
+
+/// The seed is only used by `FarmHash`, `CityHash` passes `0`.
+pub fn hash32_len_0_to_4(s: &[u8], seed: u32) -> u32 {
+    let (mut b, mut c) = (seed, 9u32);
+    for byte in s {
+        // The reference adds the bytes as signed chars.
+        b = b.wrapping_mul(C1).wrapping_add(*byte as i8 as u32);
+        c ^= b;
+    }
+    fmix(mur(b, mur(s.len() as u32, c)))
+}
+
+/// The seed is only used by `FarmHash`, `CityHash` passes `0`.
+pub fn hash32_len_5_to_12(s: &[u8], seed: u32) -> u32 {
+    let len = s.len() as u32;
+    let a = len.wrapping_add(fetch32(s));
+    let b = len.wrapping_mul(5).wrapping_add(fetch32(&s[s.len() - 4..]));
+    let c = 9u32.wrapping_add(fetch32(&s[(s.len() >> 1) & 4..]));
+    let d = len.wrapping_mul(5).wrapping_add(seed);
+    fmix(seed ^ mur(c, mur(b, mur(a, d))))
+}
+
+fn hash32_len_13_to_24(s: &[u8]) -> u32 {
+    let len = s.len();
+    let a = fetch32(&s[(len >> 1) - 4..]);
+    let b = fetch32(&s[4..]);
+    let c = fetch32(&s[len - 8..]);
+    let d = fetch32(&s[len >> 1..]);
+    let e = fetch32(s);
+    let f = fetch32(&s[len - 4..]);
+    let h = len as u32;
+    fmix(mur(f, mur(e, mur(d, mur(c, mur(b, mur(a, h)))))))
+}
+
+/// The mixing shared by the end of every 32-bit hash longer than 24 bytes.
+pub fn finish32(h: u32, g: u32, f: u32) -> u32 {
+    let g = g
+        .rotate_right(11)
+        .wrapping_mul(C1)
+        .rotate_right(17)
+        .wrapping_mul(C1);
+    let f = f
+        .rotate_right(11)
+        .wrapping_mul(C1)
+        .rotate_right(17)
+        .wrapping_mul(C1);
+    let h = combine(h.wrapping_add(g), 0)
+        .rotate_right(17)
+        .wrapping_mul(C1);
+    combine(h.wrapping_add(f), 0)
+        .rotate_right(17)
+        .wrapping_mul(C1)
+}
+
+/// `CityHash32`.
+pub fn hash32(s: &[u8]) -> u32 {
+    let len = s.len();
+    match len {
+        0..=4 => return hash32_len_0_to_4(s, 0),
+        5..=12 => return hash32_len_5_to_12(s, 0),
+        13..=24 => return hash32_len_13_to_24(s),
+        _ => {}
+    }
+
+    let tail = |offset: usize| scramble(fetch32(&s[len - offset..]));
+    let mut h = combine(combine(len as u32, tail(4)), tail(16));
+    let mut g = combine(combine(C1.wrapping_mul(len as u32), tail(8)), tail(12));
+    let mut f = C1
+        .wrapping_mul(len as u32)
+        .wrapping_add(tail(20))
+        .rotate_right(19)
+        .wrapping_mul(5)
+        .wrapping_add(0xe6546b64);
+
+    for block in s.chunks_exact(20).take((len - 1) / 20) {
+        let a0 = scramble(fetch32(block));
+        let a1 = fetch32(&block[4..]);
+        let a2 = scramble(fetch32(&block[8..]));
+        let a3 = scramble(fetch32(&block[12..]));
+        let a4 = fetch32(&block[16..]);
+        h = (h ^ a0)
+            .rotate_right(18)
+            .wrapping_mul(5)
+            .wrapping_add(0xe6546b64);
+        f = f.wrapping_add(a1).rotate_right(19).wrapping_mul(C1);
+        g = g
+            .wrapping_add(a2)
+            .rotate_right(18)
+            .wrapping_mul(5)
+            .wrapping_add(0xe6546b64);
+        h = combine(h, a3.wrapping_add(a1));
+        g = (g ^ a4).swap_bytes().wrapping_mul(5);
+        h = h.wrapping_add(a4.wrapping_mul(5)).swap_bytes();
+        f = f.wrapping_add(a0);
+        (f, h, g) = (g, f, h);
+    }
+    finish32(h, g, f)
+}
+
+pub fn shift_mix(val: u64) -> u64 {
+    val ^ (val >> 47)
+}
+
+/// Murmur-inspired hashing of two words.
+pub fn hash_len_16_mul(u: u64, v: u64, mul: u64) -> u64 {
+    let a = shift_mix((u ^ v).wrapping_mul(mul));
+    let b = shift_mix((v ^ a).wrapping_mul(mul));
+    b.wrapping_mul(mul)
+}
+
+/// `Hash128to64`, with `u` as the low word.
+pub fn hash_len_16(u: u64, v: u64) -> u64 {
+    hash_len_16_mul(u, v, K_MUL)
+}
+
+pub fn hash_len_0_to_16(s: &[u8]) -> u64 {
+    let len = s.len() as u64;
+    let mul = K2.wrapping_add(len * 2);
+    if s.len() >= 8 {
+        let a = fetch64(s).wrapping_add(K2);
+        let b = fetch64(&s[s.len() - 8..]);
+        let c = b.rotate_right(37).wrapping_mul(mul).wrapping_add(a);
+        let d = a.rotate_right(25).wrapping_add(b).wrapping_mul(mul);
+        return hash_len_16_mul(c, d, mul);
+    }
+    if s.len() >= 4 {
+        let a = u64::from(fetch32(s));
+        let b = u64::from(fetch32(&s[s.len() - 4..]));
+        return hash_len_16_mul(len.wrapping_add(a << 3), b, mul);
+    }
+    if s.is_empty() {
+        return K2;
+    }
+    let a = u32::from(s[0]);
+    let b = u32::from(s[s.len() >> 1]);
+    let c = u32::from(s[s.len() - 1]);
+    let y = u64::from(a + (b << 8));
+    let z = u64::from(len as u32 + (c << 2));
+    shift_mix(y.wrapping_mul(K2) ^ z.wrapping_mul(K0)).wrapping_mul(K2)
+}
+
+pub fn hash_len_17_to_32(s: &[u8]) -> u64 {
+    let len = s.len();
+    let mul = K2.wrapping_add(len as u64 * 2);
+    let a = fetch64(s).wrapping_mul(K1);
+    let b = fetch64(&s[8..]);
+    let c = fetch64(&s[len - 8..]).wrapping_mul(mul);
+    let d = fetch64(&s[len - 16..]).wrapping_mul(K2);
+    hash_len_16_mul(
+        a.wrapping_add(b)
+            .rotate_right(43)
+            .wrapping_add(c.rotate_right(30))
+            .wrapping_add(d),
+        a.wrapping_add(b.wrapping_add(K2).rotate_right(18))
+            .wrapping_add(c),
+        mul,
+    )
+}
+
+fn hash_len_33_to_64(s: &[u8]) -> u64 {
+    let len = s.len();
+    let mul = K2.wrapping_add(len as u64 * 2);
+    let a = fetch64(s).wrapping_mul(K2);
+    let b = fetch64(&s[8..]);
+    let c = fetch64(&s[len - 24..]);
+    let d = fetch64(&s[len - 32..]);
+    let e = fetch64(&s[16..]).wrapping_mul(K2);
+    let f = fetch64(&s[24..]).wrapping_mul(9);
+    let g = fetch64(&s[len - 8..]);
+    let h = fetch64(&s[len - 16..]).wrapping_mul(mul);
+    let u = a
+        .wrapping_add(g)
+        .rotate_right(43)
+        .wrapping_add(b.rotate_right(30).wrapping_add(c).wrapping_mul(9));
+    let v = (a.wrapping_add(g) ^ d).wrapping_add(f).wrapping_add(1);
+    let w = u
+        .wrapping_add(v)
+        .wrapping_mul(mul)
+        .swap_bytes()
+        .wrapping_add(h);
+    let x = e.wrapping_add(f).rotate_right(42).wrapping_add(c);
+    let y = v
+        .wrapping_add(w)
+        .wrapping_mul(mul)
+        .swap_bytes()
+        .wrapping_add(g)
+        .wrapping_mul(mul);
+    let z = e.wrapping_add(f).wrapping_add(c);
+    let a = x
+        .wrapping_add(z)
+        .wrapping_mul(mul)
+        .wrapping_add(y)
+        .swap_bytes()
+        .wrapping_add(b);
+    let b = shift_mix(
+        z.wrapping_add(a)
+            .wrapping_mul(mul)
+            .wrapping_add(d)
+            .wrapping_add(h),
+    )
+    .wrapping_mul(mul);
+    b.wrapping_add(x)
+}
+
+/// A 16-byte hash of `s[0..32]`, `a` and `b`. Quick and dirty.
+pub fn weak_hash_len_32_with_seeds(s: &[u8], a: u64, b: u64) -> (u64, u64) {
+    let (w, x, y, z) = (
+        fetch64(s),
+        fetch64(&s[8..]),
+        fetch64(&s[16..]),
+        fetch64(&s[24..]),
+    );
+    let a = a.wrapping_add(w);
+    let b = b.wrapping_add(a).wrapping_add(z).rotate_right(21);
+    let c = a;
+    let a = a.wrapping_add(x).wrapping_add(y);
+    let b = b.wrapping_add(a.rotate_right(44));
+    (a.wrapping_add(z), b.wrapping_add(c))
+}
+
+/// The 56 bytes of state kept by the long paths of the 64- and 128-bit hashes.
+pub struct State {
+    pub x: u64,
+    pub y: u64,
+    pub z: u64,
+    pub v: (u64, u64),
+    pub w: (u64, u64),
+}
+
+impl State {
+    /// Mixes in a 64-byte block.
+    pub fn round(&mut self, block: &[u8]) {
+        self.x = self
+            .x
+            .wrapping_add(self.y)
+            .wrapping_add(self.v.0)
+            .wrapping_add(fetch64(&block[8..]))
+            .rotate_right(37)
+            .wrapping_mul(K1);
+        self.y = self
+            .y
+            .wrapping_add(self.v.1)
+            .wrapping_add(fetch64(&block[48..]))
+            .rotate_right(42)
+            .wrapping_mul(K1);
+        self.x ^= self.w.1;
+        self.y = self
+            .y
+            .wrapping_add(self.v.0)
+            .wrapping_add(fetch64(&block[40..]));
+        self.z = self
+            .z
+            .wrapping_add(self.w.0)
+            .rotate_right(33)
+            .wrapping_mul(K1);
+        self.v = weak_hash_len_32_with_seeds(
+            block,
+            self.v.1.wrapping_mul(K1),
+            self.x.wrapping_add(self.w.0),
+        );
+        self.w = weak_hash_len_32_with_seeds(
+            &block[32..],
+            self.z.wrapping_add(self.w.1),
+            self.y.wrapping_add(fetch64(&block[16..])),
+        );
+        std::mem::swap(&mut self.z, &mut self.x);
+    }
+}
+
+/// `CityHash64`.
+pub fn hash64(s: &[u8]) -> u64 {
+    let len = s.len();
+    match len {
+        0..=16 => return hash_len_0_to_16(s),
+        17..=32 => return hash_len_17_to_32(s),
+        33..=64 => return hash_len_33_to_64(s),
+        _ => {}
+    }
+
+    // For strings over 64 bytes the end is hashed first, then the loop runs
+    // over every whole 64-byte block before it.
+    let x = fetch64(&s[len - 40..]);
+    let y = fetch64(&s[len - 16..]).wrapping_add(fetch64(&s[len - 56..]));
+    let z = hash_len_16(
+        fetch64(&s[len - 48..]).wrapping_add(len as u64),
+        fetch64(&s[len - 24..]),
+    );
+    let mut state = State {
+        x: x.wrapping_mul(K1).wrapping_add(fetch64(s)),
+        y,
+        z,
+        v: weak_hash_len_32_with_seeds(&s[len - 64..], len as u64, z),
+        w: weak_hash_len_32_with_seeds(&s[len - 32..], y.wrapping_add(K1), x),
+    };
+    for block in s.chunks_exact(64).take((len - 1) / 64) {
+        state.round(block);
+    }
+
+    let State { x, y, z, v, w } = state;
+    hash_len_16(
+        hash_len_16(v.0, w.0)
+            .wrapping_add(shift_mix(y).wrapping_mul(K1))
+            .wrapping_add(z),
+        hash_len_16(v.1, w.1).wrapping_add(x),
+    )
+}
+
+/// `CityHash64WithSeeds`.
+pub fn hash64_with_seeds(s: &[u8], seed0: u64, seed1: u64) -> u64 {
+    hash_len_16(hash64(s).wrapping_sub(seed0), seed1)
+}
+
+/// `CityHash64WithSeed`.
+pub fn hash64_with_seed(s: &[u8], seed: u64) -> u64 {
+    hash64_with_seeds(s, K2, seed)
+}
+
+/// A 128-bit hash for strings shorter than 128 bytes, based on City and
+/// Murmur.
+fn city_murmur(s: &[u8], (mut a, mut b): (u64, u64)) -> (u64, u64) {
+    let len = s.len();
+    let (mut c, mut d);
+    if len <= 16 {
+        a = shift_mix(a.wrapping_mul(K1)).wrapping_mul(K1);
+        c = b.wrapping_mul(K1).wrapping_add(hash_len_0_to_16(s));
+        d = shift_mix(a.wrapping_add(if len >= 8 { fetch64(s) } else { c }));
+    } else {
+        c = hash_len_16(fetch64(&s[len - 8..]).wrapping_add(K1), a);
+        d = hash_len_16(
+            b.wrapping_add(len as u64),
+            c.wrapping_add(fetch64(&s[len - 16..])),
+        );
+        a = a.wrapping_add(d);
+        for block in s.chunks_exact(16).take((len - 1) / 16) {
+            a ^= shift_mix(fetch64(block).wrapping_mul(K1)).wrapping_mul(K1);
+            a = a.wrapping_mul(K1);
+            b ^= a;
+            c ^= shift_mix(fetch64(&block[8..]).wrapping_mul(K1)).wrapping_mul(K1);
+            c = c.wrapping_mul(K1);
+            d ^= c;
+        }
+    }
+    let a = hash_len_16(a, c);
+    let b = hash_len_16(d, b);
+    (a ^ b, hash_len_16(b, a))
+}
+
+/// `CityHash128WithSeed`, with the seed and result as `(low, high)`.
+pub fn hash128_with_seed(s: &[u8], (x, y): (u64, u64)) -> (u64, u64) {
+    let len = s.len();
+    if len < 128 {
+        return city_murmur(s, (x, y));
+    }
+
+    let z = (len as u64).wrapping_mul(K1);
+    let v0 = (y ^ K1)
+        .rotate_right(49)
+        .wrapping_mul(K1)
+        .wrapping_add(fetch64(s));
+    let v1 = v0
+        .rotate_right(42)
+        .wrapping_mul(K1)
+        .wrapping_add(fetch64(&s[8..]));
+    let mut state = State {
+        x,
+        y,
+        z,
+        v: (v0, v1),
+        w: (
+            y.wrapping_add(z)
+                .rotate_right(35)
+                .wrapping_mul(K1)
+                .wrapping_add(x),
+            x.wrapping_add(fetch64(&s[88..]))
+                .rotate_right(53)
+                .wrapping_mul(K1),
+        ),
+    };
+    let mut blocks = s.chunks_exact(128);
+    for block in &mut blocks {
+        state.round(&block[..64]);
+        state.round(&block[64..]);
+    }
+
+    let State {
+        mut x,
+        mut y,
+        mut z,
+        mut v,
+        mut w,
+    } = state;
+    x = x.wrapping_add(v.0.wrapping_add(z).rotate_right(49).wrapping_mul(K0));
+    y = y.wrapping_mul(K0).wrapping_add(w.1.rotate_right(37));
+    z = z.wrapping_mul(K0).wrapping_add(w.0.rotate_right(27));
+    w.0 = w.0.wrapping_mul(9);
+    v.0 = v.0.wrapping_mul(K0);
+
+    // Hash up to 4 chunks of 32 bytes from the end, reaching back into the
+    // blocks already mixed in when the rest isn't a multiple of 32.
+    let rest = blocks.remainder().len();
+    for start in (1..=rest.div_ceil(32)).map(|chunk| len - chunk * 32) {
+        y = x
+            .wrapping_add(y)
+            .rotate_right(42)
+            .wrapping_mul(K0)
+            .wrapping_add(v.1);
+        w.0 = w.0.wrapping_add(fetch64(&s[start + 16..]));
+        x = x.wrapping_mul(K0).wrapping_add(w.0);
+        z = z.wrapping_add(w.1).wrapping_add(fetch64(&s[start..]));
+        w.1 = w.1.wrapping_add(v.0);
+        v = weak_hash_len_32_with_seeds(&s[start..], v.0.wrapping_add(z), v.1);
+        v.0 = v.0.wrapping_mul(K0);
+    }
+
+    let x = hash_len_16(x, v.0);
+    let y = hash_len_16(y.wrapping_add(z), w.0);
+    (
+        hash_len_16(x.wrapping_add(v.1), w.1).wrapping_add(y),
+        hash_len_16(x.wrapping_add(w.1), y.wrapping_add(v.1)),
+    )
+}
+
+/// `CityHash128`, with the result as `(low, high)`.
+pub fn hash128(s: &[u8]) -> (u64, u64) {
+    if s.len() >= 16 {
+        let seed = (fetch64(s), fetch64(&s[8..]).wrapping_add(K0));
+        hash128_with_seed(&s[16..], seed)
+    } else {
+        hash128_with_seed(s, (K0, K1))
+    }
+}
@@ -0,0 +1,186 @@
+//! The `FarmHash` fingerprints and `farmhashcc`'s seeded 32-bit hash, from
+//! <https://github.com/google/farmhash>.
+//!
+//! `Fingerprint128` is `CityHash128`, so it lives in [`city`](crate::city).
+
+use crate::city::{
+    C1, K0, K1, K2, State, combine, fetch32, fetch64, finish32, fmix, hash_len_0_to_16,
+    hash_len_16_mul, hash_len_17_to_32, hash32_len_0_to_4, hash32_len_5_to_12, mur, scramble,
+    shift_mix, weak_hash_len_32_with_seeds,
+};
+
+/// `farmhashmk`'s `Hash32Len13to24`.
+fn hash32_len_13_to_24(s: &[u8], seed: u32) -> u32 {
+    let len = s.len();
+    let a = fetch32(&s[(len >> 1) - 4..]);
+    let b = fetch32(&s[4..]);
+    let c = fetch32(&s[len - 8..]);
+    let d = fetch32(&s[len >> 1..]);
+    let e = fetch32(s);
+    let f = fetch32(&s[len - 4..]);
+    let mut h = d
+        .wrapping_mul(C1)
+        .wrapping_add(len as u32)
+        .wrapping_add(seed);
+    let mut a = a.rotate_right(12).wrapping_add(f);
+    h = mur(c, h).wrapping_add(a);
+    a = a.rotate_right(3).wrapping_add(c);
+    h = mur(e, h).wrapping_add(a);
+    a = a.wrapping_add(f).rotate_right(12).wrapping_add(d);
+    h = mur(b ^ seed, h).wrapping_add(a);
+    fmix(h)
+}
+
+/// `Fingerprint32`, which is `farmhashmk::Hash32`.
+pub fn fingerprint32(s: &[u8]) -> u32 {
+    let len = s.len();
+    match len {
+        0..=4 => return hash32_len_0_to_4(s, 0),
+        5..=12 => return hash32_len_5_to_12(s, 0),
+        13..=24 => return hash32_len_13_to_24(s, 0),
+        _ => {}
+    }
+
+    let tail = |offset: usize| scramble(fetch32(&s[len - offset..]));
+    let mut h = combine(combine(len as u32, tail(4)), tail(16));
+    let mut g = combine(combine(C1.wrapping_mul(len as u32), tail(8)), tail(12));
+    let mut f = C1
+        .wrapping_mul(len as u32)
+        .wrapping_add(tail(20))
+        .rotate_right(19)
+        .wrapping_add(113);
+
+    for block in s.chunks_exact(20).take((len - 1) / 20) {
+        let a = fetch32(block);
+        let b = fetch32(&block[4..]);
+        let c = fetch32(&block[8..]);
+        let d = fetch32(&block[12..]);
+        let e = fetch32(&block[16..]);
+        h = h.wrapping_add(a);
+        g = g.wrapping_add(b);
+        f = f.wrapping_add(c);
+        h = mur(d, h).wrapping_add(e);
+        g = mur(c, g).wrapping_add(a);
+        f = mur(b.wrapping_add(e.wrapping_mul(C1)), f).wrapping_add(d);
+        f = f.wrapping_add(g);
+        g = g.wrapping_add(f);
+    }
+    finish32(h, g, f)
+}
+
+/// `farmhashcc::Hash32WithSeed`, a seeded hash similar to `CityHash32`.
+pub fn hash32_with_seed(s: &[u8], seed: u32) -> u32 {
+    let len = s.len();
+    match len {
+        0..=4 => hash32_len_0_to_4(s, seed),
+        5..=12 => hash32_len_5_to_12(s, seed),
+        13..=24 => hash32_len_13_to_24(s, seed.wrapping_mul(C1)),
+        _ => {
+            let h = hash32_len_13_to_24(&s[..24], seed ^ len as u32);
+            mur(crate::city::hash32(&s[24..]).wrapping_add(seed), h)
+        }
+    }
+}
+
+/// `farmhashna`'s `HashLen33to64`.
+fn hash_len_33_to_64(s: &[u8]) -> u64 {
+    let len = s.len();
+    let mul = K2.wrapping_add(len as u64 * 2);
+    let a = fetch64(s).wrapping_mul(K2);
+    let b = fetch64(&s[8..]);
+    let c = fetch64(&s[len - 8..]).wrapping_mul(mul);
+    let d = fetch64(&s[len - 16..]).wrapping_mul(K2);
+    let y = a
+        .wrapping_add(b)
+        .rotate_right(43)
+        .wrapping_add(c.rotate_right(30))
+        .wrapping_add(d);
+    let z = hash_len_16_mul(
+        y,
+        a.wrapping_add(b.wrapping_add(K2).rotate_right(18))
+            .wrapping_add(c),
+        mul,
+    );
+    let e = fetch64(&s[16..]).wrapping_mul(mul);
+    let f = fetch64(&s[24..]);
+    let g = y.wrapping_add(fetch64(&s[len - 32..])).wrapping_mul(mul);
+    let h = z.wrapping_add(fetch64(&s[len - 24..])).wrapping_mul(mul);
+    hash_len_16_mul(
+        e.wrapping_add(f)
+            .rotate_right(43)
+            .wrapping_add(g.rotate_right(30))
+            .wrapping_add(h),
+        e.wrapping_add(f.wrapping_add(a).rotate_right(18))
+            .wrapping_add(g),
+        mul,
+    )
+}
+
+/// `Fingerprint64`, which is `farmhashna::Hash64`.
+pub fn fingerprint64(s: &[u8]) -> u64 {
+    const SEED: u64 = 81;
+
+    let len = s.len();
+    match len {
+        0..=16 => return hash_len_0_to_16(s),
+        17..=32 => return hash_len_17_to_32(s),
+        33..=64 => return hash_len_33_to_64(s),
+        _ => {}
+    }
+
+    let y = SEED.wrapping_mul(K1).wrapping_add(113);
+    let mut state = State {
+        x: SEED.wrapping_mul(K2).wrapping_add(fetch64(s)),
+        y,
+        z: shift_mix(y.wrapping_mul(K2).wrapping_add(113)).wrapping_mul(K2),
+        v: (0, 0),
+        w: (0, 0),
+    };
+    // Leave 1 to 64 bytes for the last round, which rereads the last 64.
+    for block in s.chunks_exact(64).take((len - 1) / 64) {
+        state.round(block);
+    }
+
+    let State {
+        mut x,
+        mut y,
+        mut z,
+        mut v,
+        mut w,
+    } = state;
+    let mul = K1.wrapping_add((z & 0xff) << 1);
+    let last = &s[len - 64..];
+    w.0 = w.0.wrapping_add(((len - 1) & 63) as u64);
+    v.0 = v.0.wrapping_add(w.0);
+    w.0 = w.0.wrapping_add(v.0);
+    x = x
+        .wrapping_add(y)
+        .wrapping_add(v.0)
+        .wrapping_add(fetch64(&last[8..]))
+        .rotate_right(37)
+        .wrapping_mul(mul);
+    y = y
+        .wrapping_add(v.1)
+        .wrapping_add(fetch64(&last[48..]))
+        .rotate_right(42)
+        .wrapping_mul(mul);
+    x ^= w.1.wrapping_mul(9);
+    y = y
+        .wrapping_add(v.0.wrapping_mul(9))
+        .wrapping_add(fetch64(&last[40..]));
+    z = z.wrapping_add(w.0).rotate_right(33).wrapping_mul(mul);
+    v = weak_hash_len_32_with_seeds(last, v.1.wrapping_mul(mul), x.wrapping_add(w.0));
+    w = weak_hash_len_32_with_seeds(
+        &last[32..],
+        z.wrapping_add(w.1),
+        y.wrapping_add(fetch64(&last[16..])),
+    );
+    std::mem::swap(&mut z, &mut x);
+    hash_len_16_mul(
+        hash_len_16_mul(v.0, w.0, mul)
+            .wrapping_add(shift_mix(y).wrapping_mul(K0))
+            .wrapping_add(z),
+        hash_len_16_mul(v.1, w.1, mul).wrapping_add(x),
+        mul,
+    )
+}
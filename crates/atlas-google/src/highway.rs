@@ -0,0 +1,198 @@
+//! Google's `HighwayHash`, ported from the portable C implementation at
+//! <https://github.com/google/highwayhash/blob/master/c/highwayhash.c>.
+
+struct State {
+    v0: [u64; 4],
+    v1: [u64; 4],
+    mul0: [u64; 4],
+    mul1: [u64; 4],
+}
+
+fn read_u64(bytes: &[u8]) -> u64 {
+    let mut word = [0u8; 8];
+    word.copy_from_slice(&bytes[..8]);
+    u64::from_le_bytes(word)
+}
+
+fn zipper_merge_and_add(v1: u64, v0: u64, add1: &mut u64, add0: &mut u64) {
+    *add0 = add0.wrapping_add(
+        (((v0 & 0xff000000) | (v1 & 0xff00000000)) >> 24)
+            | (((v0 & 0xff0000000000) | (v1 & 0xff000000000000)) >> 16)
+            | (v0 & 0xff0000)
+            | ((v0 & 0xff00) << 32)
+            | ((v1 & 0xff00000000000000) >> 8)
+            | (v0 << 56),
+    );
+    *add1 = add1.wrapping_add(
+        (((v1 & 0xff000000) | (v0 & 0xff00000000)) >> 24)
+            | (v1 & 0xff0000)
+            | ((v1 & 0xff0000000000) >> 16)
+            | ((v1 & 0xff00) << 24)
+            | ((v0 & 0xff000000000000) >> 8)
+            | ((v1 & 0xff) << 48)
+            | (v0 & 0xff00000000000000),
+    );
+}
+
+/// Swaps the 32-bit halves of a lane.
+fn swap_halves(lane: u64) -> u64 {
+    lane.rotate_left(32)
+}
+
+impl State {
+    fn new(key: [u64; 4]) -> Self {
+        let mul0 = [
+            0xdbe6d5d5fe4cce2f,
+            0xa4093822299f31d0,
+            0x13198a2e03707344,
+            0x243f6a8885a308d3,
+        ];
+        let mul1 = [
+            0x3bd39e10cb0ef593,
+            0xc0acf169b5f18a8c,
+            0xbe5466cf34e90c6c,
+            0x452821e638d01377,
+        ];
+        Self {
+            v0: std::array::from_fn(|i| mul0[i] ^ key[i]),
+            v1: std::array::from_fn(|i| mul1[i] ^ swap_halves(key[i])),
+            mul0,
+            mul1,
+        }
+    }
+
+    fn update(&mut self, lanes: [u64; 4]) {
+        for (i, lane) in lanes.into_iter().enumerate() {
+            self.v1[i] = self.v1[i].wrapping_add(self.mul0[i].wrapping_add(lane));
+            self.mul0[i] ^= (self.v1[i] & 0xffffffff).wrapping_mul(self.v0[i] >> 32);
+            self.v0[i] = self.v0[i].wrapping_add(self.mul1[i]);
+            self.mul1[i] ^= (self.v0[i] & 0xffffffff).wrapping_mul(self.v1[i] >> 32);
+        }
+        let [a0, a1, a2, a3] = &mut self.v0;
+        let [b0, b1, b2, b3] = &mut self.v1;
+        zipper_merge_and_add(*b1, *b0, a1, a0);
+        zipper_merge_and_add(*b3, *b2, a3, a2);
+        zipper_merge_and_add(*a1, *a0, b1, b0);
+        zipper_merge_and_add(*a3, *a2, b3, b2);
+    }
+
+    fn update_packet(&mut self, packet: &[u8]) {
+        self.update(std::array::from_fn(|i| read_u64(&packet[i * 8..])));
+    }
+
+    /// Mixes in the last 1 to 31 bytes.
+    fn update_remainder(&mut self, bytes: &[u8]) {
+        let size = bytes.len();
+        for lane in &mut self.v0 {
+            *lane = lane.wrapping_add(((size as u64) << 32) + size as u64);
+        }
+        // Rotate each 32-bit half of the lanes by the size.
+        for lane in &mut self.v1 {
+            let low = (*lane as u32).rotate_left(size as u32);
+            let high = ((*lane >> 32) as u32).rotate_left(size as u32);
+            *lane = u64::from(low) | (u64::from(high) << 32);
+        }
+
+        let mut packet = [0u8; 32];
+        let whole_words = size & !3;
+        packet[..whole_words].copy_from_slice(&bytes[..whole_words]);
+        if size & 16 != 0 {
+            packet[28..].copy_from_slice(&bytes[size - 4..]);
+        } else {
+            let remainder = &bytes[whole_words..];
+            if let Some(last) = remainder.last() {
+                packet[16] = remainder[0];
+                packet[17] = remainder[remainder.len() >> 1];
+                packet[18] = *last;
+            }
+        }
+        self.update_packet(&packet);
+    }
+
+    fn permute_and_update(&mut self) {
+        let v0 = self.v0;
+        self.update([
+            swap_halves(v0[2]),
+            swap_halves(v0[3]),
+            swap_halves(v0[0]),
+            swap_halves(v0[1]),
+        ]);
+    }
+
+    fn finalize64(mut self) -> u64 {
+        for _ in 0..4 {
+            self.permute_and_update();
+        }
+        self.v0[0]
+            .wrapping_add(self.v1[0])
+            .wrapping_add(self.mul0[0])
+            .wrapping_add(self.mul1[0])
+    }
+
+    fn finalize128(mut self) -> [u64; 2] {
+        for _ in 0..6 {
+            self.permute_and_update();
+        }
+        std::array::from_fn(|i| {
+            self.v0[i]
+                .wrapping_add(self.mul0[i])
+                .wrapping_add(self.v1[i + 2])
+                .wrapping_add(self.mul1[i + 2])
+        })
+    }
+
+    fn finalize256(mut self) -> [u64; 4] {
+        // More rounds than the shorter hashes, which barely adds to the cost
+        // of the long messages 256-bit hashes are mostly used for.
+        for _ in 0..10 {
+            self.permute_and_update();
+        }
+        let sum = |i: usize| {
+            (
+                self.v0[i].wrapping_add(self.mul0[i]),
+                self.v1[i].wrapping_add(self.mul1[i]),
+            )
+        };
+        let (a0, a2) = sum(0);
+        let (a1, a3) = sum(1);
+        let (m1, m0) = modular_reduction(a3, a2, a1, a0);
+        let (a0, a2) = sum(2);
+        let (a1, a3) = sum(3);
+        let (m3, m2) = modular_reduction(a3, a2, a1, a0);
+        [m0, m1, m2, m3]
+    }
+}
+
+/// Reduces a 256-bit value modulo `x^128 + x^2 + x`, returning `(m1, m0)`.
+fn modular_reduction(a3_unmasked: u64, a2: u64, a1: u64, a0: u64) -> (u64, u64) {
+    let a3 = a3_unmasked & 0x3fffffffffffffff;
+    let m1 = a1 ^ ((a3 << 1) | (a2 >> 63)) ^ ((a3 << 2) | (a2 >> 62));
+    let m0 = a0 ^ (a2 << 1) ^ (a2 << 2);
+    (m1, m0)
+}
+
+fn process_all(data: &[u8], key: [u64; 4]) -> State {
+    let mut state = State::new(key);
+    let mut packets = data.chunks_exact(32);
+    for packet in &mut packets {
+        state.update_packet(packet);
+    }
+    if !packets.remainder().is_empty() {
+        state.update_remainder(packets.remainder());
+    }
+    state
+}
+
+pub fn hash64(data: &[u8], key: [u64; 4]) -> u64 {
+    process_all(data, key).finalize64()
+}
+
+/// The hash as `[low, high]`.
+pub fn hash128(data: &[u8], key: [u64; 4]) -> [u64; 2] {
+    process_all(data, key).finalize128()
+}
+
+/// The hash as four words, least significant first.
+pub fn hash256(data: &[u8], key: [u64; 4]) -> [u64; 4] {
+    process_all(data, key).finalize256()
+}
@@ -0,0 +1,167 @@
+use atlas_common::{HashFlags, RegisterFunc, hash_function};
+
+mod city;
+mod farm;
+mod highway;
+
+#[unsafe(no_mangle)]
+pub extern "C" fn register_hashers(register: RegisterFunc) {
+    register(c"CityHash32".as_ptr(), HashFlags::empty(), city_hash32);
+    register(
+        c"CityHash32WithSeed".as_ptr(),
+        HashFlags::SEEDED,
+        city_hash32_with_seed,
+    );
+    register(c"CityHash64".as_ptr(), HashFlags::empty(), city_hash64);
+    register(
+        c"CityHash64WithSeed".as_ptr(),
+        HashFlags::SEEDED,
+        city_hash64_with_seed,
+    );
+    register(
+        c"CityHash64WithSeeds".as_ptr(),
+        HashFlags::KEYED,
+        city_hash64_with_seeds,
+    );
+    register(c"CityHash128".as_ptr(), HashFlags::empty(), city_hash128);
+    register(
+        c"CityHash128WithSeed".as_ptr(),
+        HashFlags::KEYED,
+        city_hash128_with_seed,
+    );
+    register(
+        c"FarmHash_Fingerprint32".as_ptr(),
+        HashFlags::empty(),
+        farm_fingerprint32,
+    );
+    register(
+        c"FarmHash_Fingerprint64".as_ptr(),
+        HashFlags::empty(),
+        farm_fingerprint64,
+    );
+    register(
+        c"FarmHash_Fingerprint128".as_ptr(),
+        HashFlags::empty(),
+        city_hash128,
+    );
+    register(c"HighwayHash64".as_ptr(), HashFlags::KEYED, highway_hash64);
+    register(
+        c"HighwayHash128".as_ptr(),
+        HashFlags::KEYED,
+        highway_hash128,
+    );
+    register(
+        c"HighwayHash256".as_ptr(),
+        HashFlags::KEYED,
+        highway_hash256,
+    );
+}
+
+atlas_common::hash_batch!();
+
+/// Reads a key as little-endian 64-bit words.
+fn key_words<const N: usize>(key: &[u8]) -> [u64; N] {
+    std::array::from_fn(|i| {
+        let mut word = [0u8; 8];
+        word.copy_from_slice(&key[i * 8..i * 8 + 8]);
+        u64::from_le_bytes(word)
+    })
+}
+
+hash_function! {
+    pub fn city_hash32(input, _) {
+        let hash = city::hash32(input);
+        Ok(hash.to_be_bytes())
+    }
+}
+
+hash_function! {
+    /// `CityHash` has no seeded 32-bit hash, this is the one `FarmHash` added
+    /// next to its copy of `CityHash32` (`farmhashcc::Hash32WithSeed`).
+    pub fn city_hash32_with_seed(input, options) {
+        let hash = farm::hash32_with_seed(input, options.seed_u32()?);
+        Ok(hash.to_be_bytes())
+    }
+}
+
+hash_function! {
+    pub fn city_hash64(input, _) {
+        let hash = city::hash64(input);
+        Ok(hash.to_be_bytes())
+    }
+}
+
+hash_function! {
+    pub fn city_hash64_with_seed(input, options) {
+        let hash = city::hash64_with_seed(input, options.seed()?);
+        Ok(hash.to_be_bytes())
+    }
+}
+
+hash_function! {
+    /// The two seeds are a 16-byte key, read as little-endian `seed0:seed1`.
+    pub fn city_hash64_with_seeds(input, options) {
+        let [seed0, seed1] = key_words(&options.key::<16>()?);
+        let hash = city::hash64_with_seeds(input, seed0, seed1);
+        Ok(hash.to_be_bytes())
+    }
+}
+
+hash_function! {
+    /// Also registered as `FarmHash_Fingerprint128`, which is the same hash.
+    pub fn city_hash128(input, _) {
+        let (low, high) = city::hash128(input);
+        let hash = u128::from(low) | (u128::from(high) << 64);
+        Ok(hash.to_be_bytes())
+    }
+}
+
+hash_function! {
+    /// The 128-bit seed is a 16-byte key, read as little-endian `low:high`.
+    pub fn city_hash128_with_seed(input, options) {
+        let seed = key_words::<2>(&options.key::<16>()?);
+        let (low, high) = city::hash128_with_seed(input, seed.into());
+        let hash = u128::from(low) | (u128::from(high) << 64);
+        Ok(hash.to_be_bytes())
+    }
+}
+
+hash_function! {
+    pub fn farm_fingerprint32(input, _) {
+        let hash = farm::fingerprint32(input);
+        Ok(hash.to_be_bytes())
+    }
+}
+
+hash_function! {
+    pub fn farm_fingerprint64(input, _) {
+        let hash = farm::fingerprint64(input);
+        Ok(hash.to_be_bytes())
+    }
+}
+
+hash_function! {
+    /// The 256-bit key is read as four little-endian words.
+    pub fn highway_hash64(input, options) {
+        let hash = highway::hash64(input, key_words(&options.key::<32>()?));
+        Ok(hash.to_be_bytes())
+    }
+}
+
+hash_function! {
+    /// The 256-bit key is read as four little-endian words.
+    pub fn highway_hash128(input, options) {
+        let [low, high] = highway::hash128(input, key_words(&options.key::<32>()?));
+        let hash = u128::from(low) | (u128::from(high) << 64);
+        Ok(hash.to_be_bytes())
+    }
+}
+
+hash_function! {
+    /// The 256-bit key is read as four little-endian words, and the hash is
+    /// written most significant word first.
+    pub fn highway_hash256(input, options) {
+        let words = highway::hash256(input, key_words(&options.key::<32>()?));
+        Ok(words.iter().rev().flat_map(|word| word.to_be_bytes()).collect::<Vec<_>>())
+    }
+}
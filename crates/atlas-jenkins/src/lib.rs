@@ -0,0 +1,95 @@
+use atlas_common::{HashFlags, RegisterFunc, hash_function};
+
+mod lookup2;
+mod lookup3;
+mod spooky;
+
+#[unsafe(no_mangle)]
+pub extern "C" fn register_hashers(register: RegisterFunc) {
+    register(c"Jenkins_OAAT".as_ptr(), HashFlags::empty(), one_at_a_time);
+    register(c"Lookup2".as_ptr(), HashFlags::SEEDED, lookup2);
+    register(
+        c"Lookup3_hashlittle".as_ptr(),
+        HashFlags::SEEDED,
+        lookup3_hashlittle,
+    );
+    register(
+        c"Lookup3_hashbig".as_ptr(),
+        HashFlags::SEEDED,
+        lookup3_hashbig,
+    );
+    register(
+        c"Lookup3_hashlittle2".as_ptr(),
+        HashFlags::SEEDED,
+        lookup3_hashlittle2,
+    );
+    register(c"SpookyV2_64".as_ptr(), HashFlags::SEEDED, spooky_v2_64);
+    register(c"SpookyV2_128".as_ptr(), HashFlags::SEEDED, spooky_v2_128);
+}
+
+atlas_common::hash_batch!();
+
+hash_function! {
+    pub fn one_at_a_time(input, _) {
+        let mut hash = 0u32;
+        for byte in input {
+            hash = hash.wrapping_add(u32::from(*byte));
+            hash = hash.wrapping_add(hash << 10);
+            hash ^= hash >> 6;
+        }
+        hash = hash.wrapping_add(hash << 3);
+        hash ^= hash >> 11;
+        hash = hash.wrapping_add(hash << 15);
+        Ok(hash.to_be_bytes())
+    }
+}
+
+hash_function! {
+    pub fn lookup2(input, options) {
+        let hash = lookup2::hash(input, options.seed_u32()?);
+        Ok(hash.to_be_bytes())
+    }
+}
+
+hash_function! {
+    pub fn lookup3_hashlittle(input, options) {
+        let hash = lookup3::hashlittle(input, options.seed_u32()?);
+        Ok(hash.to_be_bytes())
+    }
+}
+
+hash_function! {
+    pub fn lookup3_hashbig(input, options) {
+        let hash = lookup3::hashbig(input, options.seed_u32()?);
+        Ok(hash.to_be_bytes())
+    }
+}
+
+hash_function! {
+    /// The low half of the seed is the primary initval (`*pc`), the high half
+    /// the secondary one (`*pb`). The hash is `pb:pc`, as in lookup3's `hashlittle2`
+    /// 64-bit usage.
+    pub fn lookup3_hashlittle2(input, options) {
+        let seed = options.seed()?;
+        let (pc, pb) = lookup3::hashlittle2(input, seed as u32, (seed >> 32) as u32);
+        let hash = u64::from(pc) | (u64::from(pb) << 32);
+        Ok(hash.to_be_bytes())
+    }
+}
+
+hash_function! {
+    pub fn spooky_v2_64(input, options) {
+        let hash = spooky::hash64(input, options.seed()?);
+        Ok(hash.to_be_bytes())
+    }
+}
+
+hash_function! {
+    /// Both seeds are set to the seed, and the hash is `hash2:hash1`.
+    pub fn spooky_v2_128(input, options) {
+        let seed = options.seed()?;
+        let (hash1, hash2) = spooky::hash128(input, seed, seed);
+        let hash = u128::from(hash1) | (u128::from(hash2) << 64);
+        Ok(hash.to_be_bytes())
+    }
+}
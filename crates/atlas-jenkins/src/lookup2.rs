@@ -0,0 +1,44 @@
+//! Bob Jenkins' lookup2 `hash()`, from <http://burtleburtle.net/bob/c/lookup2.c>.
+
+/// The golden ratio, an arbitrary value.
+const GOLDEN_RATIO: u32 = 0x9e3779b9;
+
+fn mix(a: &mut u32, b: &mut u32, c: &mut u32) {
+    *a = a.wrapping_sub(*b).wrapping_sub(*c) ^ (*c >> 13);
+    *b = b.wrapping_sub(*c).wrapping_sub(*a) ^ (*a << 8);
+    *c = c.wrapping_sub(*a).wrapping_sub(*b) ^ (*b >> 13);
+    *a = a.wrapping_sub(*b).wrapping_sub(*c) ^ (*c >> 12);
+    *b = b.wrapping_sub(*c).wrapping_sub(*a) ^ (*a << 16);
+    *c = c.wrapping_sub(*a).wrapping_sub(*b) ^ (*b >> 5);
+    *a = a.wrapping_sub(*b).wrapping_sub(*c) ^ (*c >> 3);
+    *b = b.wrapping_sub(*c).wrapping_sub(*a) ^ (*a << 10);
+    *c = c.wrapping_sub(*a).wrapping_sub(*b) ^ (*b >> 15);
+}
+
+fn word(bytes: &[u8]) -> u32 {
+    let mut word = [0u8; 4];
+    word[..bytes.len()].copy_from_slice(bytes);
+    u32::from_le_bytes(word)
+}
+
+/// Hashes `key`, starting from `initval`.
+pub fn hash(key: &[u8], initval: u32) -> u32 {
+    let (mut a, mut b, mut c) = (GOLDEN_RATIO, GOLDEN_RATIO, initval);
+
+    let mut blocks = key.chunks_exact(12);
+    for block in &mut blocks {
+        a = a.wrapping_add(word(&block[0..4]));
+        b = b.wrapping_add(word(&block[4..8]));
+        c = c.wrapping_add(word(&block[8..12]));
+        mix(&mut a, &mut b, &mut c);
+    }
+
+    // The last 11 bytes. The low byte of `c` is reserved for the length.
+    let rest = blocks.remainder();
+    c = c.wrapping_add(key.len() as u32);
+    a = a.wrapping_add(word(&rest[..rest.len().min(4)]));
+    b = b.wrapping_add(word(rest.get(4..rest.len().min(8)).unwrap_or_default()));
+    c = c.wrapping_add(word(rest.get(8..).unwrap_or_default()) << 8);
+    mix(&mut a, &mut b, &mut c);
+    c
+}
@@ -0,0 +1,90 @@
+//! Bob Jenkins' lookup3 (`hashlittle`, `hashbig` and `hashlittle2`), from
+//! <http://burtleburtle.net/bob/c/lookup3.c>.
+
+fn mix(a: &mut u32, b: &mut u32, c: &mut u32) {
+    *a = a.wrapping_sub(*c);
+    *a ^= c.rotate_left(4);
+    *c = c.wrapping_add(*b);
+    *b = b.wrapping_sub(*a);
+    *b ^= a.rotate_left(6);
+    *a = a.wrapping_add(*c);
+    *c = c.wrapping_sub(*b);
+    *c ^= b.rotate_left(8);
+    *b = b.wrapping_add(*a);
+    *a = a.wrapping_sub(*c);
+    *a ^= c.rotate_left(16);
+    *c = c.wrapping_add(*b);
+    *b = b.wrapping_sub(*a);
+    *b ^= a.rotate_left(19);
+    *a = a.wrapping_add(*c);
+    *c = c.wrapping_sub(*b);
+    *c ^= b.rotate_left(4);
+    *b = b.wrapping_add(*a);
+}
+
+fn finalize(a: &mut u32, b: &mut u32, c: &mut u32) {
+    *c ^= *b;
+    *c = c.wrapping_sub(b.rotate_left(14));
+    *a ^= *c;
+    *a = a.wrapping_sub(c.rotate_left(11));
+    *b ^= *a;
+    *b = b.wrapping_sub(a.rotate_left(25));
+    *c ^= *b;
+    *c = c.wrapping_sub(b.rotate_left(16));
+    *a ^= *c;
+    *a = a.wrapping_sub(c.rotate_left(4));
+    *b ^= *a;
+    *b = b.wrapping_sub(a.rotate_left(14));
+    *c ^= *b;
+    *c = c.wrapping_sub(b.rotate_left(24));
+}
+
+/// The shared body of every variant, returning `(c, b)`.
+///
+/// The variants only differ in how they read 32-bit words out of the key;
+/// the last block is zero padded, which matches the reference's masking.
+fn lookup3(key: &[u8], pc: u32, pb: u32, read: fn([u8; 4]) -> u32) -> (u32, u32) {
+    let init = 0xdeadbeef_u32
+        .wrapping_add(key.len() as u32)
+        .wrapping_add(pc);
+    let (mut a, mut b, mut c) = (init, init, init.wrapping_add(pb));
+    if key.is_empty() {
+        return (c, b);
+    }
+
+    let mut block = [0u8; 12];
+    for (i, chunk) in key.chunks(12).enumerate() {
+        block = [0; 12];
+        block[..chunk.len()].copy_from_slice(chunk);
+        if (i + 1) * 12 >= key.len() {
+            break;
+        }
+        add_block(&mut a, &mut b, &mut c, &block, read);
+        mix(&mut a, &mut b, &mut c);
+    }
+    add_block(&mut a, &mut b, &mut c, &block, read);
+    finalize(&mut a, &mut b, &mut c);
+    (c, b)
+}
+
+fn add_block(a: &mut u32, b: &mut u32, c: &mut u32, block: &[u8; 12], read: fn([u8; 4]) -> u32) {
+    let word = |i: usize| read([block[i], block[i + 1], block[i + 2], block[i + 3]]);
+    *a = a.wrapping_add(word(0));
+    *b = b.wrapping_add(word(4));
+    *c = c.wrapping_add(word(8));
+}
+
+/// `hashlittle`, reading the key as little-endian words.
+pub fn hashlittle(key: &[u8], initval: u32) -> u32 {
+    lookup3(key, initval, 0, u32::from_le_bytes).0
+}
+
+/// `hashbig`, reading the key as big-endian words.
+pub fn hashbig(key: &[u8], initval: u32) -> u32 {
+    lookup3(key, initval, 0, u32::from_be_bytes).0
+}
+
+/// `hashlittle2`, returning the primary and secondary hashes `(pc, pb)`.
+pub fn hashlittle2(key: &[u8], pc: u32, pb: u32) -> (u32, u32) {
+    lookup3(key, pc, pb, u32::from_le_bytes)
+}
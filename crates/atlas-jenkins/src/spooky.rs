@@ -0,0 +1,136 @@
+//! Bob Jenkins' `SpookyHash` V2, from <http://burtleburtle.net/bob/hash/spooky.html>.
+
+/// The number of 64-bit words in the long hash's state.
+const NUM_VARS: usize = 12;
+/// The number of bytes mixed in at a time by the long hash.
+const BLOCK_SIZE: usize = NUM_VARS * 8;
+/// Messages shorter than this use the short hash.
+const BUF_SIZE: usize = 2 * BLOCK_SIZE;
+/// An arbitrary odd constant that isn't too regular.
+const CONST: u64 = 0xdeadbeefdeadbeef;
+
+const MIX_ROTATIONS: [u32; NUM_VARS] = [11, 32, 43, 31, 17, 28, 39, 57, 55, 54, 22, 46];
+const END_ROTATIONS: [u32; NUM_VARS] = [44, 15, 34, 21, 38, 33, 10, 13, 38, 53, 42, 54];
+
+fn read_u64(bytes: &[u8]) -> u64 {
+    let mut word = [0u8; 8];
+    word[..bytes.len()].copy_from_slice(bytes);
+    u64::from_le_bytes(word)
+}
+
+fn short_mix(h: &mut [u64; 4]) {
+    for (i, rotation) in [50, 52, 30, 41, 54, 48, 38, 37, 62, 34, 5, 36]
+        .into_iter()
+        .enumerate()
+    {
+        // h2 = rot(h2); h2 += h3; h0 ^= h2, shifted by one word each step.
+        let (a, b, c) = ((i + 2) % 4, (i + 3) % 4, i % 4);
+        h[a] = h[a].rotate_left(rotation).wrapping_add(h[b]);
+        h[c] ^= h[a];
+    }
+}
+
+fn short_end(h: &mut [u64; 4]) {
+    for (i, rotation) in [15, 52, 26, 51, 28, 9, 47, 54, 32, 25, 63]
+        .into_iter()
+        .enumerate()
+    {
+        // h3 ^= h2; h2 = rot(h2); h3 += h2, shifted by one word each step.
+        let (a, b) = ((i + 3) % 4, (i + 2) % 4);
+        h[a] ^= h[b];
+        h[b] = h[b].rotate_left(rotation);
+        h[a] = h[a].wrapping_add(h[b]);
+    }
+}
+
+/// The hash for messages shorter than [`BUF_SIZE`].
+fn short(message: &[u8], seed1: u64, seed2: u64) -> (u64, u64) {
+    let mut h = [seed1, seed2, CONST, CONST];
+
+    let mut rest = message;
+    if message.len() > 15 {
+        let mut blocks = message.chunks_exact(32);
+        for block in &mut blocks {
+            h[2] = h[2].wrapping_add(read_u64(&block[0..8]));
+            h[3] = h[3].wrapping_add(read_u64(&block[8..16]));
+            short_mix(&mut h);
+            h[0] = h[0].wrapping_add(read_u64(&block[16..24]));
+            h[1] = h[1].wrapping_add(read_u64(&block[24..32]));
+        }
+        rest = blocks.remainder();
+        if rest.len() >= 16 {
+            h[2] = h[2].wrapping_add(read_u64(&rest[0..8]));
+            h[3] = h[3].wrapping_add(read_u64(&rest[8..16]));
+            short_mix(&mut h);
+            rest = &rest[16..];
+        }
+    }
+
+    // The last 0..15 bytes, and the length.
+    h[3] = h[3].wrapping_add((message.len() as u64) << 56);
+    if rest.is_empty() {
+        h[2] = h[2].wrapping_add(CONST);
+        h[3] = h[3].wrapping_add(CONST);
+    } else {
+        h[2] = h[2].wrapping_add(read_u64(&rest[..rest.len().min(8)]));
+        h[3] = h[3].wrapping_add(read_u64(rest.get(8..).unwrap_or_default()));
+    }
+    short_end(&mut h);
+    (h[0], h[1])
+}
+
+fn mix(data: &[u8], s: &mut [u64; NUM_VARS]) {
+    for (i, rotation) in MIX_ROTATIONS.into_iter().enumerate() {
+        s[i] = s[i].wrapping_add(read_u64(&data[i * 8..i * 8 + 8]));
+        s[(i + 2) % NUM_VARS] ^= s[(i + 10) % NUM_VARS];
+        s[(i + 11) % NUM_VARS] ^= s[i];
+        s[i] = s[i].rotate_left(rotation);
+        s[(i + 11) % NUM_VARS] = s[(i + 11) % NUM_VARS].wrapping_add(s[(i + 1) % NUM_VARS]);
+    }
+}
+
+fn end_partial(h: &mut [u64; NUM_VARS]) {
+    for (i, rotation) in END_ROTATIONS.into_iter().enumerate() {
+        let (a, b, c) = ((i + 11) % NUM_VARS, (i + 1) % NUM_VARS, (i + 2) % NUM_VARS);
+        h[a] = h[a].wrapping_add(h[b]);
+        h[c] ^= h[a];
+        h[b] = h[b].rotate_left(rotation);
+    }
+}
+
+fn end(data: &[u8], h: &mut [u64; NUM_VARS]) {
+    for (i, h) in h.iter_mut().enumerate() {
+        *h = h.wrapping_add(read_u64(&data[i * 8..i * 8 + 8]));
+    }
+    end_partial(h);
+    end_partial(h);
+    end_partial(h);
+}
+
+/// `SpookyHash::Hash128`, returning `(hash1, hash2)`.
+pub fn hash128(message: &[u8], seed1: u64, seed2: u64) -> (u64, u64) {
+    if message.len() < BUF_SIZE {
+        return short(message, seed1, seed2);
+    }
+
+    let mut h = [
+        seed1, seed2, CONST, seed1, seed2, CONST, seed1, seed2, CONST, seed1, seed2, CONST,
+    ];
+    let mut blocks = message.chunks_exact(BLOCK_SIZE);
+    for block in &mut blocks {
+        mix(block, &mut h);
+    }
+
+    // The last partial block is zero padded, with its length in the last byte.
+    let rest = blocks.remainder();
+    let mut last = [0u8; BLOCK_SIZE];
+    last[..rest.len()].copy_from_slice(rest);
+    last[BLOCK_SIZE - 1] = rest.len() as u8;
+    end(&last, &mut h);
+    (h[0], h[1])
+}
+
+/// `SpookyHash::Hash64`, which is [`hash128`] seeded twice with `seed`.
+pub fn hash64(message: &[u8], seed: u64) -> u64 {
+    hash128(message, seed, seed).0
+}
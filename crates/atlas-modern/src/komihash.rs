@@ -0,0 +1,119 @@
+//! Aleksey Vaneev's komihash, version 5, from <https://github.com/avaneev/komihash>.
+
+fn multiply(a: u64, b: u64) -> (u64, u64) {
+    let product = u128::from(a) * u128::from(b);
+    (product as u64, (product >> 64) as u64)
+}
+
+fn read8(bytes: &[u8]) -> u64 {
+    let mut word = [0u8; 8];
+    word.copy_from_slice(&bytes[..8]);
+    u64::from_le_bytes(word)
+}
+
+/// Up to 7 bytes as a little-endian word, with a 1 bit after them.
+fn pad(bytes: &[u8]) -> u64 {
+    bytes
+        .iter()
+        .enumerate()
+        .fold(1 << (8 * bytes.len()), |word, (i, byte)| {
+            word | (u64::from(*byte) << (8 * i))
+        })
+}
+
+/// The two words every step updates, `Seed1` and `Seed5` in the reference.
+struct State {
+    seed1: u64,
+    seed5: u64,
+}
+
+impl State {
+    fn mix(&mut self, a: u64, b: u64) {
+        let (low, high) = multiply(a, b);
+        self.seed5 = self.seed5.wrapping_add(high);
+        self.seed1 = low ^ self.seed5;
+    }
+
+    fn round(&mut self) {
+        self.mix(self.seed1, self.seed5);
+    }
+
+    fn hash16(&mut self, bytes: &[u8]) {
+        self.mix(self.seed1 ^ read8(bytes), self.seed5 ^ read8(&bytes[8..]));
+    }
+
+    /// Mixes in the last 0 to 15 bytes, padded, and finishes.
+    fn finish(mut self, tail: &[u8]) -> u64 {
+        let (r1, r2) = if tail.len() > 7 {
+            (self.seed1 ^ read8(tail), self.seed5 ^ pad(&tail[8..]))
+        } else {
+            (self.seed1 ^ pad(tail), self.seed5)
+        };
+        self.mix(r1, r2);
+        self.round();
+        self.seed1
+    }
+
+    /// Finishes an empty input, which has no padding bit.
+    fn finish_empty(mut self) -> u64 {
+        self.round();
+        self.round();
+        self.seed1
+    }
+}
+
+pub fn hash(data: &[u8], seed: u64) -> u64 {
+    let mut state = State {
+        seed1: 0x243F6A8885A308D3 ^ (seed & 0x5555555555555555),
+        seed5: 0x452821E638D01377 ^ (seed & 0xAAAAAAAAAAAAAAAA),
+    };
+    state.round();
+    if data.is_empty() {
+        return state.finish_empty();
+    }
+
+    let mut p = data;
+    if p.len() > 63 {
+        let (seed1, seed5) = (state.seed1, state.seed5);
+        let mut low = [
+            seed1,
+            0x13198A2E03707344 ^ seed1,
+            0xA4093822299F31D0 ^ seed1,
+            0x082EFA98EC4E6C89 ^ seed1,
+        ];
+        let mut high = [
+            seed5,
+            0xBE5466CF34E90C6C ^ seed5,
+            0xC0AC29B7C97C50DD ^ seed5,
+            0x3F84D5B5B5470917 ^ seed5,
+        ];
+        while p.len() > 63 {
+            for lane in 0..4 {
+                let (l, h) = multiply(
+                    low[lane] ^ read8(&p[8 * lane..]),
+                    high[lane] ^ read8(&p[32 + 8 * lane..]),
+                );
+                low[lane] = l;
+                high[lane] = high[lane].wrapping_add(h);
+            }
+            // Each lane takes the high word of the one before it.
+            for lane in 0..4 {
+                low[(lane + 1) % 4] ^= high[lane];
+            }
+            p = &p[64..];
+        }
+        state.seed1 = low[0] ^ low[1] ^ low[2] ^ low[3];
+        state.seed5 = high[0] ^ high[1] ^ high[2] ^ high[3];
+    }
+
+    if p.len() > 31 {
+        state.hash16(p);
+        state.hash16(&p[16..]);
+        p = &p[32..];
+    }
+    if p.len() > 15 {
+        state.hash16(p);
+        p = &p[16..];
+    }
+    state.finish(p)
+}
@@ -0,0 +1,221 @@
+use atlas_common::{HashFlags, HashParam, RegisterFunc, RegisterParamFunc, hash_function};
+use rapidhash::v3::RapidSecrets;
+
+mod komihash;
+mod metro;
+mod t1ha;
+mod wyhash;
+
+#[unsafe(no_mangle)]
+pub extern "C" fn register_hashers(register: RegisterFunc) {
+    register(c"wyhash_final3".as_ptr(), HashFlags::SEEDED, wyhash_final3);
+    register(
+        c"wyhash_final3_with_secret".as_ptr(),
+        HashFlags::SECRET,
+        wyhash_final3_with_secret,
+    );
+    register(c"wyhash_final4".as_ptr(), HashFlags::SEEDED, wyhash_final4);
+    register(
+        c"wyhash_final4_with_secret".as_ptr(),
+        HashFlags::SECRET,
+        wyhash_final4_with_secret,
+    );
+
+    register(c"komihash".as_ptr(), HashFlags::SEEDED, komihash);
+
+    register(c"rapidhash".as_ptr(), HashFlags::SEEDED, rapidhash);
+    register(
+        c"rapidhashMicro".as_ptr(),
+        HashFlags::SEEDED,
+        rapidhash_micro,
+    );
+    register(c"rapidhashNano".as_ptr(), HashFlags::SEEDED, rapidhash_nano);
+
+    register(c"t1ha0_32le".as_ptr(), HashFlags::SEEDED, t1ha0_32le);
+    register(c"t1ha0_32be".as_ptr(), HashFlags::SEEDED, t1ha0_32be);
+    register(c"t1ha1_le".as_ptr(), HashFlags::SEEDED, t1ha1_le);
+    register(c"t1ha1_be".as_ptr(), HashFlags::SEEDED, t1ha1_be);
+    register(c"t1ha2_atonce".as_ptr(), HashFlags::SEEDED, t1ha2_atonce);
+    register(
+        c"t1ha2_atonce128".as_ptr(),
+        HashFlags::SEEDED,
+        t1ha2_atonce128,
+    );
+
+    register(c"MetroHash64_1".as_ptr(), HashFlags::SEEDED, metro_hash64_1);
+    register(c"MetroHash64_2".as_ptr(), HashFlags::SEEDED, metro_hash64_2);
+    register(
+        c"MetroHash128_1".as_ptr(),
+        HashFlags::SEEDED,
+        metro_hash128_1,
+    );
+    register(
+        c"MetroHash128_2".as_ptr(),
+        HashFlags::SEEDED,
+        metro_hash128_2,
+    );
+}
+
+atlas_common::hash_batch!();
+
+#[unsafe(no_mangle)]
+pub extern "C" fn register_params(register: RegisterParamFunc) {
+    for name in [c"wyhash_final3_with_secret", c"wyhash_final4_with_secret"] {
+        register(
+            name.as_ptr(),
+            &HashParam::new(c"seed_value", 0, u64::MAX, 1, 0),
+        );
+    }
+}
+
+hash_function! {
+    pub fn wyhash_final3(input, options) {
+        let hash = wyhash::hash_final3(input, options.seed()?, &wyhash::SECRET_FINAL3);
+        Ok(hash.to_be_bytes())
+    }
+}
+
+hash_function! {
+    /// The secret replaces `_wyp`, as 4 little-endian words. Since only one of
+    /// the seed and secret can be given, the seed is the `seed_value` parameter.
+    pub fn wyhash_final3_with_secret(input, options) {
+        let [seed] = options.params()?;
+        let hash = wyhash::hash_final3(input, seed, &wyhash_secret(options.secret(32)?));
+        Ok(hash.to_be_bytes())
+    }
+}
+
+hash_function! {
+    pub fn wyhash_final4(input, options) {
+        let hash = wyhash::hash_final4(input, options.seed()?, &wyhash::SECRET_FINAL4);
+        Ok(hash.to_be_bytes())
+    }
+}
+
+hash_function! {
+    /// Like `wyhash_final3_with_secret`, with the secret replacing final4's `_wyp`.
+    pub fn wyhash_final4_with_secret(input, options) {
+        let [seed] = options.params()?;
+        let hash = wyhash::hash_final4(input, seed, &wyhash_secret(options.secret(32)?));
+        Ok(hash.to_be_bytes())
+    }
+}
+
+/// The first 32 bytes of a secret as 4 little-endian words.
+fn wyhash_secret(secret: &[u8]) -> [u64; 4] {
+    let mut words = [0u64; 4];
+    for (word, bytes) in words.iter_mut().zip(secret.chunks_exact(8)) {
+        let mut buf = [0u8; 8];
+        buf.copy_from_slice(bytes);
+        *word = u64::from_le_bytes(buf);
+    }
+    words
+}
+
+hash_function! {
+    pub fn komihash(input, options) {
+        let hash = komihash::hash(input, options.seed()?);
+        Ok(hash.to_be_bytes())
+    }
+}
+
+hash_function! {
+    /// rapidhash V3, seeded the way the C++ `rapidhash_withSeed` is.
+    pub fn rapidhash(input, options) {
+        let secrets = RapidSecrets::seed_cpp(options.seed()?);
+        let hash = rapidhash::v3::rapidhash_v3_seeded(input, &secrets);
+        Ok(hash.to_be_bytes())
+    }
+}
+
+hash_function! {
+    pub fn rapidhash_micro(input, options) {
+        let secrets = RapidSecrets::seed_cpp(options.seed()?);
+        let hash = rapidhash::v3::rapidhash_v3_micro_inline::<true, false>(input, &secrets);
+        Ok(hash.to_be_bytes())
+    }
+}
+
+hash_function! {
+    pub fn rapidhash_nano(input, options) {
+        let secrets = RapidSecrets::seed_cpp(options.seed()?);
+        let hash = rapidhash::v3::rapidhash_v3_nano_inline::<true, false>(input, &secrets);
+        Ok(hash.to_be_bytes())
+    }
+}
+
+hash_function! {
+    pub fn t1ha0_32le(input, options) {
+        let hash = t1ha::t1ha0_32(input, options.seed()?, t1ha::Endian::Little);
+        Ok(hash.to_be_bytes())
+    }
+}
+
+hash_function! {
+    pub fn t1ha0_32be(input, options) {
+        let hash = t1ha::t1ha0_32(input, options.seed()?, t1ha::Endian::Big);
+        Ok(hash.to_be_bytes())
+    }
+}
+
+hash_function! {
+    pub fn t1ha1_le(input, options) {
+        let hash = t1ha::t1ha1(input, options.seed()?, t1ha::Endian::Little);
+        Ok(hash.to_be_bytes())
+    }
+}
+
+hash_function! {
+    pub fn t1ha1_be(input, options) {
+        let hash = t1ha::t1ha1(input, options.seed()?, t1ha::Endian::Big);
+        Ok(hash.to_be_bytes())
+    }
+}
+
+hash_function! {
+    pub fn t1ha2_atonce(input, options) {
+        let hash = t1ha::t1ha2(input, options.seed()?);
+        Ok(hash.to_be_bytes())
+    }
+}
+
+hash_function! {
+    /// The returned value is the high half, `extra_result` the low half.
+    pub fn t1ha2_atonce128(input, options) {
+        let (high, low) = t1ha::t1ha2_128(input, options.seed()?);
+        let hash = u128::from(low) | (u128::from(high) << 64);
+        Ok(hash.to_be_bytes())
+    }
+}
+
+hash_function! {
+    pub fn metro_hash64_1(input, options) {
+        let hash = metro::hash64(input, options.seed_u32()?, 1);
+        Ok(hash.to_be_bytes())
+    }
+}
+
+hash_function! {
+    pub fn metro_hash64_2(input, options) {
+        let hash = metro::hash64(input, options.seed_u32()?, 2);
+        Ok(hash.to_be_bytes())
+    }
+}
+
+hash_function! {
+    /// The reference writes out two words, read here as little-endian `low:high`.
+    pub fn metro_hash128_1(input, options) {
+        let [low, high] = metro::hash128(input, options.seed_u32()?, 1);
+        let hash = u128::from(low) | (u128::from(high) << 64);
+        Ok(hash.to_be_bytes())
+    }
+}
+
+hash_function! {
+    /// The reference writes out two words, read here as little-endian `low:high`.
+    pub fn metro_hash128_2(input, options) {
+        let [low, high] = metro::hash128(input, options.seed_u32()?, 2);
+        let hash = u128::from(low) | (u128::from(high) << 64);
+        Ok(hash.to_be_bytes())
+    }
+}
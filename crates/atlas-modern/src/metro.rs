@@ -0,0 +1,212 @@
+//! J. Andrew Rogers' original `MetroHash` release, from <https://github.com/jandrewrogers/MetroHash>.
+//!
+//! Each width came in two variants, `_1` and `_2`, with different constants
+//! and rotations.
+
+/// The constants of the `_1` variants.
+const K1: [u64; 4] = [0xC83A91E1, 0x8648DBDB, 0x7BDEC03B, 0x2F5870A5];
+/// The constants of the `_2` variants.
+const K2: [u64; 4] = [0xD6D018F5, 0xA2AA033B, 0x62992FC1, 0x30BC5B29];
+
+fn read(bytes: &[u8], len: usize) -> u64 {
+    let mut word = [0u8; 8];
+    word[..len].copy_from_slice(&bytes[..len]);
+    u64::from_le_bytes(word)
+}
+
+/// Takes the next `len` bytes off the front of `rest` if there are that many.
+fn take(rest: &mut &[u8], len: usize) -> Option<u64> {
+    if rest.len() < len {
+        return None;
+    }
+    let word = read(rest, len);
+    *rest = &rest[len..];
+    Some(word)
+}
+
+/// The bulk loop shared by every variant, over whole 32-byte blocks.
+fn bulk<'a>(v: &mut [u64; 4], data: &'a [u8], k: &[u64; 4]) -> &'a [u8] {
+    let mut rest = data;
+    while rest.len() >= 32 {
+        for i in 0..4 {
+            let word = read(&rest[8 * i..], 8);
+            v[i] = v[i]
+                .wrapping_add(word.wrapping_mul(k[i]))
+                .rotate_right(29)
+                .wrapping_add(v[(i + 2) % 4]);
+        }
+        rest = &rest[32..];
+    }
+    rest
+}
+
+/// Folds the four lanes together after the bulk loop, the last one rotated by `rot[1]`.
+fn fold(v: &mut [u64; 4], k: &[u64; 4], rot: [u32; 2]) {
+    let [k0, k1, ..] = *k;
+    v[2] ^= v[0]
+        .wrapping_add(v[3])
+        .wrapping_mul(k0)
+        .wrapping_add(v[1])
+        .rotate_right(rot[0])
+        .wrapping_mul(k1);
+    v[3] ^= v[1]
+        .wrapping_add(v[2])
+        .wrapping_mul(k1)
+        .wrapping_add(v[0])
+        .rotate_right(rot[0])
+        .wrapping_mul(k0);
+    v[0] ^= v[0]
+        .wrapping_add(v[2])
+        .wrapping_mul(k0)
+        .wrapping_add(v[3])
+        .rotate_right(rot[0])
+        .wrapping_mul(k1);
+    v[1] ^= v[1]
+        .wrapping_add(v[3])
+        .wrapping_mul(k1)
+        .wrapping_add(v[2])
+        .rotate_right(rot[1])
+        .wrapping_mul(k0);
+}
+
+/// `metrohash64_1` and `metrohash64_2`.
+pub fn hash64(data: &[u8], seed: u32, variant: u8) -> u64 {
+    let (k, rot) = match variant {
+        1 => (&K1, [33, 33, 35, 33, 15, 13, 25, 33, 33]),
+        _ => (&K2, [30, 29, 34, 36, 15, 15, 23, 28, 29]),
+    };
+    let [k0, k1, k2, k3] = *k;
+    let mut hash = u64::from(seed)
+        .wrapping_add(k2)
+        .wrapping_mul(k0)
+        .wrapping_add(data.len() as u64);
+
+    let mut rest = data;
+    if data.len() >= 32 {
+        let mut v = [hash; 4];
+        rest = bulk(&mut v, data, k);
+        fold(&mut v, k, [rot[0]; 2]);
+        hash = hash.wrapping_add(v[0] ^ v[1]);
+    }
+
+    if rest.len() >= 16 {
+        // The two variants weight the 16-byte tail differently.
+        let (m0, m1, m2, m3) = match variant {
+            1 => (k0, k1, k1, k2),
+            _ => (k2, k3, k2, k3),
+        };
+        let (w0, w1) = (read(rest, 8), read(&rest[8..], 8));
+        rest = &rest[16..];
+        let mut v0 = hash
+            .wrapping_add(w0.wrapping_mul(m0))
+            .rotate_right(rot[1])
+            .wrapping_mul(m1);
+        let mut v1 = hash
+            .wrapping_add(w1.wrapping_mul(m2))
+            .rotate_right(rot[1])
+            .wrapping_mul(m3);
+        v0 ^= v0.wrapping_mul(k0).rotate_right(rot[2]).wrapping_add(v1);
+        v1 ^= v1.wrapping_mul(k3).rotate_right(rot[2]).wrapping_add(v0);
+        hash = hash.wrapping_add(v1);
+    }
+
+    for (len, rot) in [(8, rot[3]), (4, rot[4]), (2, rot[5]), (1, rot[6])] {
+        if let Some(word) = take(&mut rest, len) {
+            hash = hash.wrapping_add(word.wrapping_mul(k3));
+            hash ^= hash.rotate_right(rot).wrapping_mul(k1);
+        }
+    }
+
+    hash ^= hash.rotate_right(rot[7]);
+    hash = hash.wrapping_mul(k0);
+    hash ^ hash.rotate_right(rot[8])
+}
+
+/// `metrohash128_1` and `metrohash128_2`, as the two words the reference
+/// writes out in order.
+pub fn hash128(data: &[u8], seed: u32, variant: u8) -> [u64; 2] {
+    let (k, rot) = match variant {
+        1 => (
+            &K1,
+            [26, 30, 33, 17, 33, 20, 33, 18, 33, 24, 33, 24, 13, 37],
+        ),
+        _ => (
+            &K2,
+            [33, 33, 29, 29, 29, 29, 29, 25, 29, 30, 29, 18, 33, 33],
+        ),
+    };
+    let [k0, k1, k2, k3] = *k;
+    let seed = u64::from(seed);
+    let len = data.len() as u64;
+    let mut v = [
+        seed.wrapping_sub(k0).wrapping_mul(k3).wrapping_add(len),
+        seed.wrapping_add(k1).wrapping_mul(k2).wrapping_add(len),
+        seed.wrapping_add(k0).wrapping_mul(k2).wrapping_add(len),
+        seed.wrapping_sub(k1).wrapping_mul(k3).wrapping_add(len),
+    ];
+
+    let mut rest = data;
+    if data.len() >= 32 {
+        rest = bulk(&mut v, data, k);
+        fold(&mut v, k, [rot[0], rot[1]]);
+    }
+
+    let [mut v0, mut v1, ..] = v;
+    if rest.len() >= 16 {
+        let (w0, w1) = (read(rest, 8), read(&rest[8..], 8));
+        rest = &rest[16..];
+        v0 = v0
+            .wrapping_add(w0.wrapping_mul(k2))
+            .rotate_right(rot[2])
+            .wrapping_mul(k3);
+        v1 = v1
+            .wrapping_add(w1.wrapping_mul(k2))
+            .rotate_right(rot[2])
+            .wrapping_mul(k3);
+        v0 ^= v0
+            .wrapping_mul(k2)
+            .wrapping_add(v1)
+            .rotate_right(rot[3])
+            .wrapping_mul(k1);
+        v1 ^= v1
+            .wrapping_mul(k3)
+            .wrapping_add(v0)
+            .rotate_right(rot[3])
+            .wrapping_mul(k0);
+    }
+
+    // The shorter tails alternate between the two halves.
+    for (step, len) in [8, 4, 2, 1].into_iter().enumerate() {
+        let Some(word) = take(&mut rest, len) else {
+            continue;
+        };
+        let (inner, outer) = (rot[4 + 2 * step], rot[5 + 2 * step]);
+        if step % 2 == 0 {
+            v0 = v0
+                .wrapping_add(word.wrapping_mul(k2))
+                .rotate_right(inner)
+                .wrapping_mul(k3);
+            v0 ^= v0
+                .wrapping_mul(k2)
+                .wrapping_add(v1)
+                .rotate_right(outer)
+                .wrapping_mul(k1);
+        } else {
+            v1 = v1
+                .wrapping_add(word.wrapping_mul(k2))
+                .rotate_right(inner)
+                .wrapping_mul(k3);
+            v1 ^= v1
+                .wrapping_mul(k3)
+                .wrapping_add(v0)
+                .rotate_right(outer)
+                .wrapping_mul(k0);
+        }
+    }
+
+    v0 = v0.wrapping_add(v0.wrapping_mul(k0).wrapping_add(v1).rotate_right(rot[12]));
+    v1 = v1.wrapping_add(v1.wrapping_mul(k1).wrapping_add(v0).rotate_right(rot[13]));
+    v0 = v0.wrapping_add(v0.wrapping_mul(k2).wrapping_add(v1).rotate_right(rot[12]));
+    v1 = v1.wrapping_add(v1.wrapping_mul(k3).wrapping_add(v0).rotate_right(rot[13]));
+    [v0, v1]
+}
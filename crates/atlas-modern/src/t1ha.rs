@@ -0,0 +1,317 @@
+//! Leonid Yuriev's t1ha ("Fast Positive Hash") v2, from <https://github.com/erthink/t1ha>.
+//!
+//! Only the portable functions are here, `t1ha0` itself picks an AES-NI
+//! variant at runtime on x86 and so differs between machines.
+
+const PRIME_0: u64 = 0xEC99BF0D8372CAAB;
+const PRIME_1: u64 = 0x82434FE90EDCEF39;
+const PRIME_2: u64 = 0xD4F06DB99D67BE4B;
+const PRIME_3: u64 = 0xBD9CACC22C6E9571;
+const PRIME_4: u64 = 0x9C06FAF4D023E3AB;
+const PRIME_5: u64 = 0xC060724A8424F345;
+const PRIME_6: u64 = 0xCB5AF53AE3AAAC31;
+
+const PRIME32_0: u32 = 0x92D78269;
+const PRIME32_1: u32 = 0xCA9B4735;
+const PRIME32_2: u32 = 0xA4ABA1C3;
+const PRIME32_3: u32 = 0xF6499843;
+const PRIME32_4: u32 = 0x86F0FD61;
+const PRIME32_5: u32 = 0xCA2DA6FB;
+const PRIME32_6: u32 = 0xC4BB3575;
+
+#[derive(Clone, Copy)]
+pub enum Endian {
+    Little,
+    Big,
+}
+
+impl Endian {
+    fn fetch32(self, bytes: &[u8]) -> u32 {
+        let mut word = [0u8; 4];
+        word.copy_from_slice(&bytes[..4]);
+        match self {
+            Self::Little => u32::from_le_bytes(word),
+            Self::Big => u32::from_be_bytes(word),
+        }
+    }
+
+    fn fetch64(self, bytes: &[u8]) -> u64 {
+        let mut word = [0u8; 8];
+        word.copy_from_slice(&bytes[..8]);
+        match self {
+            Self::Little => u64::from_le_bytes(word),
+            Self::Big => u64::from_be_bytes(word),
+        }
+    }
+
+    /// Reads the last word of the input, which may be short, as a number of
+    /// its own length.
+    fn tail(self, bytes: &[u8]) -> u64 {
+        match self {
+            Self::Little => bytes
+                .iter()
+                .rev()
+                .fold(0, |acc, &byte| (acc << 8) | u64::from(byte)),
+            Self::Big => bytes
+                .iter()
+                .fold(0, |acc, &byte| (acc << 8) | u64::from(byte)),
+        }
+    }
+}
+
+fn mul_64x64_128(a: u64, b: u64) -> (u64, u64) {
+    let product = u128::from(a) * u128::from(b);
+    (product as u64, (product >> 64) as u64)
+}
+
+fn mux64(v: u64, prime: u64) -> u64 {
+    let (low, high) = mul_64x64_128(v, prime);
+    low ^ high
+}
+
+fn mixup64(a: &mut u64, b: &mut u64, v: u64, prime: u64) {
+    let (low, high) = mul_64x64_128(b.wrapping_add(v), prime);
+    *a ^= low;
+    *b = b.wrapping_add(high);
+}
+
+fn mixup32(a: &mut u32, b: &mut u32, v: u32, prime: u32) {
+    let product = u64::from(b.wrapping_add(v)) * u64::from(prime);
+    *a ^= product as u32;
+    *b = b.wrapping_add((product >> 32) as u32);
+}
+
+fn final32(a: u32, b: u32) -> u64 {
+    let mut l = u64::from(b ^ a.rotate_right(13)) | (u64::from(a) << 32);
+    l = l.wrapping_mul(PRIME_0);
+    l ^= l >> 41;
+    l = l.wrapping_mul(PRIME_4);
+    l ^= l >> 47;
+    l.wrapping_mul(PRIME_6)
+}
+
+fn final64(a: u64, b: u64) -> u64 {
+    let x = a.wrapping_add(b.rotate_right(41)).wrapping_mul(PRIME_0);
+    let y = a.rotate_right(23).wrapping_add(b).wrapping_mul(PRIME_6);
+    mux64(x ^ y, PRIME_5)
+}
+
+/// Splits what is left after the bulk loop into whole words and a final,
+/// possibly partial, word of 1 to `N` bytes.
+fn split_tail<const N: usize>(rest: &[u8]) -> (std::slice::ChunksExact<'_, u8>, Option<&[u8]>) {
+    let Some(last_start) = rest.len().checked_sub(1).map(|last| last / N * N) else {
+        return (rest.chunks_exact(N), None);
+    };
+    let (words, last) = rest.split_at(last_start);
+    (words.chunks_exact(N), Some(last))
+}
+
+/// `t1ha0_32le` and `t1ha0_32be`, the portable 32-bit flavour of `t1ha0`.
+pub fn t1ha0_32(data: &[u8], seed: u64, endian: Endian) -> u64 {
+    let len = data.len() as u64;
+    let mut a = (len as u32).rotate_right(17).wrapping_add(seed as u32);
+    let mut b = (len as u32) ^ (seed >> 32) as u32;
+
+    let mut rest = data;
+    if data.len() > 16 {
+        let mut c = !a;
+        let mut d = b.rotate_right(5);
+        while rest.len() > 15 {
+            let (block, tail) = rest.split_at(16);
+            rest = tail;
+            let w0 = endian.fetch32(&block[0..]);
+            let w1 = endian.fetch32(&block[4..]);
+            let w2 = endian.fetch32(&block[8..]);
+            let w3 = endian.fetch32(&block[12..]);
+
+            let d13 = w1.wrapping_add(w3.wrapping_add(d).rotate_right(17));
+            let c02 = w0 ^ w2.wrapping_add(c).rotate_right(11);
+            d ^= a.wrapping_add(w0).rotate_right(3);
+            c ^= b.wrapping_add(w1).rotate_right(7);
+            b = PRIME32_1.wrapping_mul(c02.wrapping_add(w3));
+            a = PRIME32_0.wrapping_mul(d13 ^ w2);
+        }
+        c = c.wrapping_add(a);
+        d = d.wrapping_add(b);
+        a ^= PRIME32_6.wrapping_mul(c.rotate_right(16).wrapping_add(d));
+        b ^= PRIME32_5.wrapping_mul(c.wrapping_add(d.rotate_right(16)));
+    }
+
+    // The tail mixes into alternating halves, ending with `b, a` on the last word.
+    let (words, last) = split_tail::<4>(rest);
+    let primes = [PRIME32_4, PRIME32_3, PRIME32_2];
+    let skipped = 3 - words.len();
+    for (i, word) in words.enumerate() {
+        let step = skipped + i;
+        let v = endian.fetch32(word);
+        if step % 2 == 0 {
+            mixup32(&mut a, &mut b, v, primes[step]);
+        } else {
+            mixup32(&mut b, &mut a, v, primes[step]);
+        }
+    }
+    if let Some(last) = last {
+        mixup32(&mut b, &mut a, endian.tail(last) as u32, PRIME32_1);
+    }
+    final32(a, b)
+}
+
+fn mix64(v: u64, prime: u64) -> u64 {
+    let v = v.wrapping_mul(prime);
+    v ^ v.rotate_right(41)
+}
+
+/// `t1ha1_le` and `t1ha1_be`.
+pub fn t1ha1(data: &[u8], seed: u64, endian: Endian) -> u64 {
+    let len = data.len() as u64;
+    let mut a = seed;
+    let mut b = len;
+
+    let mut rest = data;
+    if data.len() > 32 {
+        let mut c = len.rotate_right(17).wrapping_add(seed);
+        let mut d = len ^ seed.rotate_right(17);
+        while rest.len() > 31 {
+            let (block, tail) = rest.split_at(32);
+            rest = tail;
+            let w0 = endian.fetch64(&block[0..]);
+            let w1 = endian.fetch64(&block[8..]);
+            let w2 = endian.fetch64(&block[16..]);
+            let w3 = endian.fetch64(&block[24..]);
+
+            let d02 = w0 ^ w2.wrapping_add(d).rotate_right(17);
+            let c13 = w1 ^ w3.wrapping_add(c).rotate_right(17);
+            d = d.wrapping_sub(b ^ w1.rotate_right(31));
+            c = c.wrapping_add(a ^ w0.rotate_right(41));
+            b ^= PRIME_0.wrapping_mul(c13.wrapping_add(w2));
+            a ^= PRIME_1.wrapping_mul(d02.wrapping_add(w3));
+        }
+        a ^= PRIME_6.wrapping_mul(c.rotate_right(17).wrapping_add(d));
+        b ^= PRIME_5.wrapping_mul(c.wrapping_add(d.rotate_right(17)));
+    }
+
+    let (words, last) = split_tail::<8>(rest);
+    let primes = [PRIME_4, PRIME_3, PRIME_2];
+    let skipped = 3 - words.len();
+    for (i, word) in words.enumerate() {
+        let step = skipped + i;
+        let v = mux64(endian.fetch64(word), primes[step]);
+        if step % 2 == 0 {
+            b = b.wrapping_add(v);
+        } else {
+            a = a.wrapping_add(v);
+        }
+    }
+    if let Some(last) = last {
+        a = a.wrapping_add(mux64(endian.tail(last), PRIME_1));
+    }
+    mux64(a.wrapping_add(b).rotate_right(17), PRIME_4).wrapping_add(mix64(a ^ b, PRIME_0))
+}
+
+struct State {
+    a: u64,
+    b: u64,
+    c: u64,
+    d: u64,
+}
+
+impl State {
+    fn new(seed: u64, len: u64) -> Self {
+        Self {
+            a: seed,
+            b: len,
+            c: len.rotate_right(23).wrapping_add(!seed),
+            d: (!len).wrapping_add(seed.rotate_right(19)),
+        }
+    }
+
+    /// Runs the bulk loop over all whole 32-byte blocks while more than 32
+    /// bytes remain, returning the rest.
+    fn update<'a>(&mut self, data: &'a [u8]) -> &'a [u8] {
+        if data.len() <= 32 {
+            return data;
+        }
+        let mut rest = data;
+        while rest.len() > 31 {
+            let (block, tail) = rest.split_at(32);
+            rest = tail;
+            let w0 = Endian::Little.fetch64(&block[0..]);
+            let w1 = Endian::Little.fetch64(&block[8..]);
+            let w2 = Endian::Little.fetch64(&block[16..]);
+            let w3 = Endian::Little.fetch64(&block[24..]);
+
+            let d02 = w0.wrapping_add(w2.wrapping_add(self.d).rotate_right(56));
+            let c13 = w1.wrapping_add(w3.wrapping_add(self.c).rotate_right(19));
+            self.d ^= self.b.wrapping_add(w1.rotate_right(38));
+            self.c ^= self.a.wrapping_add(w0.rotate_right(57));
+            self.b ^= PRIME_6.wrapping_mul(c13.wrapping_add(w2));
+            self.a ^= PRIME_5.wrapping_mul(d02.wrapping_add(w3));
+        }
+        rest
+    }
+
+    fn squash(&mut self) {
+        self.a ^= PRIME_6.wrapping_mul(self.c.wrapping_add(self.d.rotate_right(23)));
+        self.b ^= PRIME_5.wrapping_mul(self.c.rotate_right(19).wrapping_add(self.d));
+    }
+}
+
+/// `t1ha2_atonce`.
+pub fn t1ha2(data: &[u8], seed: u64) -> u64 {
+    let mut state = State::new(seed, data.len() as u64);
+    let rest = state.update(data);
+    if rest.len() != data.len() {
+        state.squash();
+    }
+
+    let (words, last) = split_tail::<8>(rest);
+    let primes = [PRIME_4, PRIME_3, PRIME_2];
+    let skipped = 3 - words.len();
+    let State { mut a, mut b, .. } = state;
+    for (i, word) in words.enumerate() {
+        let step = skipped + i;
+        let v = Endian::Little.fetch64(word);
+        if step % 2 == 0 {
+            mixup64(&mut a, &mut b, v, primes[step]);
+        } else {
+            mixup64(&mut b, &mut a, v, primes[step]);
+        }
+    }
+    if let Some(last) = last {
+        mixup64(&mut b, &mut a, Endian::Little.tail(last), PRIME_1);
+    }
+    final64(a, b)
+}
+
+/// `t1ha2_atonce128`, as the returned `high` half and the `extra_result` low half.
+pub fn t1ha2_128(data: &[u8], seed: u64) -> (u64, u64) {
+    let mut state = State::new(seed, data.len() as u64);
+    let rest = state.update(data);
+
+    let (words, last) = split_tail::<8>(rest);
+    let primes = [PRIME_4, PRIME_3, PRIME_2];
+    let skipped = 3 - words.len();
+    let State {
+        mut a,
+        mut b,
+        mut c,
+        mut d,
+    } = state;
+    for (i, word) in words.enumerate() {
+        let v = Endian::Little.fetch64(word);
+        match skipped + i {
+            0 => mixup64(&mut a, &mut d, v, primes[0]),
+            1 => mixup64(&mut b, &mut a, v, primes[1]),
+            _ => mixup64(&mut c, &mut b, v, primes[2]),
+        }
+    }
+    if let Some(last) = last {
+        mixup64(&mut d, &mut c, Endian::Little.tail(last), PRIME_1);
+    }
+
+    mixup64(&mut a, &mut b, c.rotate_right(41) ^ d, PRIME_0);
+    mixup64(&mut b, &mut c, d.rotate_right(23) ^ a, PRIME_6);
+    mixup64(&mut c, &mut d, a.rotate_right(19) ^ b, PRIME_5);
+    mixup64(&mut d, &mut a, b.rotate_right(31) ^ c, PRIME_4);
+    (a ^ b, c.wrapping_add(d))
+}
@@ -0,0 +1,96 @@
+//! Wang Yi's wyhash, versions `final3` and `final4`, from
+//! <https://github.com/wangyi-fudan/wyhash>.
+//!
+//! This is the default build, with `WYHASH_CONDOM` set to 1 and the 32-bit
+//! multiply off.
+
+/// The default secret of `final3`, `_wyp`.
+pub const SECRET_FINAL3: [u64; 4] = [
+    0xa0761d6478bd642f,
+    0xe7037ed1a0b428db,
+    0x8ebc6af09c88c6e3,
+    0x589965cc75374cc3,
+];
+
+/// The default secret of `final4`, `_wyp`.
+pub const SECRET_FINAL4: [u64; 4] = [
+    0x2d358dccaa6c78a5,
+    0x8bb84b93962eacc9,
+    0x4b33a62ed433d4a3,
+    0x4d5a2da51de1aa47,
+];
+
+fn multiply(a: u64, b: u64) -> (u64, u64) {
+    let product = u128::from(a) * u128::from(b);
+    (product as u64, (product >> 64) as u64)
+}
+
+fn mix(a: u64, b: u64) -> u64 {
+    let (low, high) = multiply(a, b);
+    low ^ high
+}
+
+fn read8(bytes: &[u8]) -> u64 {
+    let mut word = [0u8; 8];
+    word.copy_from_slice(&bytes[..8]);
+    u64::from_le_bytes(word)
+}
+
+fn read4(bytes: &[u8]) -> u64 {
+    let mut word = [0u8; 4];
+    word.copy_from_slice(&bytes[..4]);
+    u64::from(u32::from_le_bytes(word))
+}
+
+/// Packs 1 to 3 bytes as the first, middle and last byte.
+fn read3(bytes: &[u8]) -> u64 {
+    let k = bytes.len();
+    (u64::from(bytes[0]) << 16) | (u64::from(bytes[k >> 1]) << 8) | u64::from(bytes[k - 1])
+}
+
+/// Reads `data` into the two words both versions finish with, mixing all
+/// but the last 16 bytes into `seed`. The three-lane loop runs on blocks of
+/// 48 while at least `wide` bytes are left.
+fn absorb(data: &[u8], seed: &mut u64, secret: &[u64; 4], wide: usize) -> (u64, u64) {
+    let len = data.len();
+    if len <= 16 {
+        if len >= 4 {
+            let offset = (len >> 3) << 2;
+            let a = (read4(data) << 32) | read4(&data[offset..]);
+            let b = (read4(&data[len - 4..]) << 32) | read4(&data[len - 4 - offset..]);
+            return (a, b);
+        }
+        return if len > 0 { (read3(data), 0) } else { (0, 0) };
+    }
+
+    let mut p = data;
+    if p.len() >= wide {
+        let (mut see1, mut see2) = (*seed, *seed);
+        while p.len() >= wide {
+            *seed = mix(read8(p) ^ secret[1], read8(&p[8..]) ^ *seed);
+            see1 = mix(read8(&p[16..]) ^ secret[2], read8(&p[24..]) ^ see1);
+            see2 = mix(read8(&p[32..]) ^ secret[3], read8(&p[40..]) ^ see2);
+            p = &p[48..];
+        }
+        *seed ^= see1 ^ see2;
+    }
+    while p.len() > 16 {
+        *seed = mix(read8(p) ^ secret[1], read8(&p[8..]) ^ *seed);
+        p = &p[16..];
+    }
+    // The last 16 bytes of the input, which may overlap what was mixed.
+    (read8(&data[len - 16..]), read8(&data[len - 8..]))
+}
+
+pub fn hash_final3(data: &[u8], seed: u64, secret: &[u64; 4]) -> u64 {
+    let mut seed = seed ^ secret[0];
+    let (a, b) = absorb(data, &mut seed, secret, 49);
+    mix(secret[1] ^ data.len() as u64, mix(a ^ secret[1], b ^ seed))
+}
+
+pub fn hash_final4(data: &[u8], seed: u64, secret: &[u64; 4]) -> u64 {
+    let mut seed = seed ^ mix(seed ^ secret[0], secret[1]);
+    let (a, b) = absorb(data, &mut seed, secret, 48);
+    let (a, b) = multiply(a ^ secret[1], b ^ seed);
+    mix(a ^ secret[0] ^ data.len() as u64, b ^ secret[1])
+}
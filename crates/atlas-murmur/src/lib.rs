@@ -1,4 +1,5 @@
-use atlas_common::{CHashOptions, HashFlags, RegisterFunc, Slice};
+use atlas_common::{ErrorCode, HashFlags, PluginError, RegisterFunc, hash_function};
+use std::io::Cursor;
 
 #[unsafe(no_mangle)]
 pub extern "C" fn register_hashers(register: RegisterFunc) {
@@ -19,247 +20,63 @@ pub extern "C" fn register_hashers(register: RegisterFunc) {
     );
 }
 
-#[unsafe(no_mangle)]
-pub unsafe extern "C" fn murmur2_32(data: *mut Slice<u8>, options: *const CHashOptions) -> i32 {
-    if data.is_null() || options.is_null() {
-        return -1;
-    }
-
-    unsafe {
-        let hash_options = &*options;
-        let seed = if !hash_options.data.is_null() {
-            &*hash_options.data.cast::<u64>()
-        } else {
-            &0
-        };
+atlas_common::hash_batch!();
 
-        let input = &mut *data;
-        let Some(input_data) = input.as_mut_slice() else {
-            return -2;
-        };
-
-        if !input.ptr.is_null() {
-            let _: Option<Box<[u8]>> = input.into_boxed_slice();
-        }
-        let hash = murmur2::murmur2(input_data, *seed as u32);
-        let hash_boxed = hash.to_be_bytes().to_vec().into_boxed_slice();
-        *data = Slice::from_boxed_slice(hash_boxed);
+hash_function! {
+    pub fn murmur2_32(input, options) {
+        let hash = murmur2::murmur2(input, options.seed_u32()?);
+        Ok(hash.to_be_bytes())
     }
-    0
 }
 
-#[unsafe(no_mangle)]
-pub unsafe extern "C" fn murmur2a_32(data: *mut Slice<u8>, options: *const CHashOptions) -> i32 {
-    if data.is_null() || options.is_null() {
-        return -1;
-    }
-
-    unsafe {
-        let hash_options = &*options;
-        let seed = if !hash_options.data.is_null() {
-            &*hash_options.data.cast::<u64>()
-        } else {
-            &0
-        };
-
-        let input = &mut *data;
-        let Some(input_data) = input.as_mut_slice() else {
-            return -2;
-        };
-
-        if !input.ptr.is_null() {
-            let _: Option<Box<[u8]>> = input.into_boxed_slice();
-        }
-        let hash = murmur2::murmur2a(input_data, *seed as u32);
-        let hash_boxed = hash.to_be_bytes().to_vec().into_boxed_slice();
-        *data = Slice::from_boxed_slice(hash_boxed);
+hash_function! {
+    pub fn murmur2a_32(input, options) {
+        let hash = murmur2::murmur2a(input, options.seed_u32()?);
+        Ok(hash.to_be_bytes())
     }
-    0
 }
 
-#[unsafe(no_mangle)]
-pub unsafe extern "C" fn murmur2a_64(data: *mut Slice<u8>, options: *const CHashOptions) -> i32 {
-    if data.is_null() || options.is_null() {
-        return -1;
-    }
-
-    unsafe {
-        let hash_options = &*options;
-        let seed = if !hash_options.data.is_null() {
-            &*hash_options.data.cast::<u64>()
-        } else {
-            &0
-        };
-
-        let input = &mut *data;
-        let Some(input_data) = input.as_mut_slice() else {
-            return -2;
-        };
-
-        if !input.ptr.is_null() {
-            let _: Option<Box<[u8]>> = input.into_boxed_slice();
-        }
-        let hash = murmur2::murmur64a(input_data, *seed);
-        let hash_boxed = hash.to_be_bytes().to_vec().into_boxed_slice();
-        *data = Slice::from_boxed_slice(hash_boxed);
+hash_function! {
+    pub fn murmur2a_64(input, options) {
+        let hash = murmur2::murmur64a(input, options.seed()?);
+        Ok(hash.to_be_bytes())
     }
-    0
 }
 
-#[unsafe(no_mangle)]
-pub unsafe extern "C" fn murmur2b_64(data: *mut Slice<u8>, options: *const CHashOptions) -> i32 {
-    if data.is_null() || options.is_null() {
-        return -1;
-    }
-
-    unsafe {
-        let hash_options = &*options;
-        let seed = if !hash_options.data.is_null() {
-            &*hash_options.data.cast::<u64>()
-        } else {
-            &0
-        };
-
-        let input = &mut *data;
-        let Some(input_data) = input.as_mut_slice() else {
-            return -2;
-        };
-
-        if !input.ptr.is_null() {
-            let _: Option<Box<[u8]>> = input.into_boxed_slice();
-        }
-        let hash = murmur2::murmur64b(input_data, *seed);
-        let hash_boxed = hash.to_be_bytes().to_vec().into_boxed_slice();
-        *data = Slice::from_boxed_slice(hash_boxed);
+hash_function! {
+    pub fn murmur2b_64(input, options) {
+        let hash = murmur2::murmur64b(input, options.seed()?);
+        Ok(hash.to_be_bytes())
     }
-    0
 }
 
-#[unsafe(no_mangle)]
-pub unsafe extern "C" fn murmur3_32(data: *mut Slice<u8>, options: *const CHashOptions) -> i32 {
-    if data.is_null() || options.is_null() {
-        return -1;
+hash_function! {
+    pub fn murmur3_32(input, options) {
+        let hash = murmur3::murmur3_32(&mut Cursor::new(input), options.seed_u32()?)
+            .map_err(|e| internal_error("murmur3_32", &e))?;
+        Ok(hash.to_be_bytes())
     }
-
-    unsafe {
-        let hash_options = &*options;
-        let seed = if !hash_options.data.is_null() {
-            &*hash_options.data.cast::<u64>()
-        } else {
-            &0
-        };
-
-        let input = &mut *data;
-        let Some(input_data) = input.as_mut_slice() else {
-            return -2;
-        };
-
-        if !input.ptr.is_null() {
-            let _: Option<Box<[u8]>> = input.into_boxed_slice();
-        }
-        let hash_result = murmur3::murmur3_32(&mut std::io::Cursor::new(input_data), *seed as u32);
-        match hash_result {
-            Ok(hash) => {
-                let hash_boxed = hash.to_be_bytes().to_vec().into_boxed_slice();
-                *data = Slice::from_boxed_slice(hash_boxed);
-            }
-            Err(e) => {
-                eprintln!("murmur3::murmur3_32 returned an error: {e}");
-                return -3;
-            }
-        }
-    }
-    0
 }
 
-#[unsafe(no_mangle)]
-pub unsafe extern "C" fn murmur3_x64_128(
-    data: *mut Slice<u8>,
-    options: *const CHashOptions,
-) -> i32 {
-    if data.is_null() || options.is_null() {
-        return -1;
+hash_function! {
+    pub fn murmur3_x64_128(input, options) {
+        let hash = murmur3::murmur3_x64_128(&mut Cursor::new(input), options.seed_u32()?)
+            .map_err(|e| internal_error("murmur3_x64_128", &e))?;
+        Ok(hash.to_be_bytes())
     }
-
-    unsafe {
-        let hash_options = &*options;
-        let seed = if !hash_options.data.is_null() {
-            &*hash_options.data.cast::<u64>()
-        } else {
-            &0
-        };
-
-        let input = &mut *data;
-        let Some(input_data) = input.as_mut_slice() else {
-            return -2;
-        };
-
-        if !input.ptr.is_null() {
-            let _: Option<Box<[u8]>> = input.into_boxed_slice();
-        }
-        let hash_result =
-            murmur3::murmur3_x64_128(&mut std::io::Cursor::new(input_data), *seed as u32);
-        // if hash_result.is_err() {
-        //     return -1;
-        // } else {
-        //     let hash_boxed = hash_result
-        //         .unwrap()
-        //         .to_be_bytes()
-        //         .to_vec()
-        //         .into_boxed_slice();
-        //     *data = Slice::from_boxed_slice(hash_boxed);
-        // };
-        match hash_result {
-            Ok(hash) => {
-                let hash_boxed = hash.to_be_bytes().to_vec().into_boxed_slice();
-                *data = Slice::from_boxed_slice(hash_boxed);
-            }
-            Err(e) => {
-                eprintln!("murmur3::murmur3_x64_128 returned an error: {e}");
-                return -3;
-            }
-        }
-    }
-    0
 }
 
-#[unsafe(no_mangle)]
-pub unsafe extern "C" fn murmur3_x86_128(
-    data: *mut Slice<u8>,
-    options: *const CHashOptions,
-) -> i32 {
-    if data.is_null() || options.is_null() {
-        return -1;
+hash_function! {
+    pub fn murmur3_x86_128(input, options) {
+        let hash = murmur3::murmur3_x86_128(&mut Cursor::new(input), options.seed_u32()?)
+            .map_err(|e| internal_error("murmur3_x86_128", &e))?;
+        Ok(hash.to_be_bytes())
     }
+}
 
-    unsafe {
-        let hash_options = &*options;
-        let seed = if !hash_options.data.is_null() {
-            &*hash_options.data.cast::<u64>()
-        } else {
-            &0
-        };
-
-        let input = &mut *data;
-        let Some(input_data) = input.as_mut_slice() else {
-            return -2;
-        };
-
-        if !input.ptr.is_null() {
-            let _: Option<Box<[u8]>> = input.into_boxed_slice();
-        }
-        let hash_result =
-            murmur3::murmur3_x86_128(&mut std::io::Cursor::new(input_data), *seed as u32);
-        match hash_result {
-            Ok(hash) => {
-                let hash_boxed = hash.to_be_bytes().to_vec().into_boxed_slice();
-                *data = Slice::from_boxed_slice(hash_boxed);
-            }
-            Err(e) => {
-                eprintln!("murmur3::murmur3_x86_128 returned an error: {e}");
-                return -3;
-            }
-        }
-    }
-    0
+fn internal_error(function: &str, e: &std::io::Error) -> PluginError {
+    PluginError::new(
+        ErrorCode::Internal,
+        format!("murmur3::{function} returned an error: {e}"),
+    )
 }
@@ -0,0 +1,67 @@
+//! A plugin whose hashers deliberately panic or stretch the ABI, used to test
+//! that Atlas survives misbehaving plugins. It is not copied into the `plugins` folder.
+
+use atlas_common::{
+    CHashOptions, ErrorCode, ErrorMessage, HashFlags, OutputBuffer, RegisterFunc, Slice,
+    hash_function,
+};
+
+#[unsafe(no_mangle)]
+pub extern "C" fn register_hashers(register: RegisterFunc) {
+    register(c"Panic".as_ptr(), HashFlags::empty(), explicit_panic);
+    register(
+        c"Panic (out of bounds)".as_ptr(),
+        HashFlags::empty(),
+        out_of_bounds,
+    );
+    register(c"Oversized".as_ptr(), HashFlags::empty(), oversized);
+    register(c"Greedy".as_ptr(), HashFlags::empty(), greedy);
+    register(c"Panic (unwinding)".as_ptr(), HashFlags::empty(), unwinding);
+}
+
+atlas_common::hash_batch!();
+
+hash_function! {
+    pub fn explicit_panic(input, _) -> Vec<u8> {
+        panic!("Asked to hash {} bytes", input.len());
+    }
+}
+
+hash_function! {
+    pub fn out_of_bounds(input, _) {
+        let table = [0u8; 4];
+        Ok(vec![table[input.len() + table.len()]])
+    }
+}
+
+hash_function! {
+    /// Repeats the input until it is larger than any output buffer the host starts with.
+    pub fn oversized(input, _) {
+        Ok(input.iter().copied().cycle().take(4096).collect::<Vec<_>>())
+    }
+}
+
+/// Asks for more output than any host could allocate, however large the buffer.
+unsafe extern "C" fn greedy(
+    _input: *const Slice<u8>,
+    _options: *const CHashOptions,
+    output: *mut OutputBuffer,
+    _error: *mut ErrorMessage,
+) -> i32 {
+    // SAFETY: the host passes a valid output buffer for the duration of the call
+    if let Some(output) = unsafe { output.as_mut() } {
+        output.len = usize::MAX;
+    }
+    ErrorCode::OutputTooSmall as i32
+}
+
+/// Panics without `hash_function!` to catch it, which aborts the process as
+/// the panic can't unwind out of an `extern "C"` function.
+unsafe extern "C" fn unwinding(
+    _input: *const Slice<u8>,
+    _options: *const CHashOptions,
+    _output: *mut OutputBuffer,
+    _error: *mut ErrorMessage,
+) -> i32 {
+    panic!("Unwinding into the host");
+}
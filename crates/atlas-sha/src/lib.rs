@@ -1,6 +1,6 @@
 #![allow(dead_code)]
 
-use atlas_common::{CHashOptions, HashFlags, RegisterFunc, Slice};
+use atlas_common::{HashFlags, RegisterFunc, hash_function};
 use paste::paste;
 use sha2::Digest as _;
 
@@ -19,6 +19,8 @@ pub extern "C" fn register_hashers(register: RegisterFunc) {
     register(c"SHA3-512".as_ptr(), HashFlags::empty(), sha3_512);
 }
 
+atlas_common::hash_batch!();
+
 macro_rules! sha_impl {
     (
         sha_version: $v:expr,
@@ -26,26 +28,11 @@ macro_rules! sha_impl {
         hasher: $hasher:ty,
     ) => {
         paste! {
-            #[unsafe(no_mangle)]
-            pub unsafe extern "C" fn [< sha$v _ $bits >](data: *mut Slice<u8>, options: *const CHashOptions) -> i32 {
-                if data.is_null() {
-                    return -1;
+            hash_function! {
+                pub fn [< sha$v _ $bits >](input, _) {
+                    let hash = $hasher::digest(input);
+                    Ok(hash.to_vec())
                 }
-
-                unsafe {
-                    let input = &mut *data;
-                    let Some(input_data) = input.as_mut_slice() else {
-                        return -2;
-                    };
-
-                    if !input.ptr.is_null() {
-                        let _: Option<Box<[u8]>> = input.into_boxed_slice();
-                    }
-                    let hash = $hasher::digest(input_data);
-                    let hash_boxed = hash.to_vec().into_boxed_slice();
-                    *data = Slice::from_boxed_slice(hash_boxed);
-                }
-                0
             }
         }
     };
@@ -107,24 +94,9 @@ sha_impl! {
     hasher: sha3::Sha3_512,
 }
 
-#[unsafe(no_mangle)]
-pub unsafe extern "C" fn sha1(data: *mut Slice<u8>, options: *const CHashOptions) -> i32 {
-    if data.is_null() {
-        return -1;
-    }
-
-    unsafe {
-        let input = &mut *data;
-        let Some(input_data) = input.as_mut_slice() else {
-            return -2;
-        };
-
-        if !input.ptr.is_null() {
-            let _: Option<Box<[u8]>> = input.into_boxed_slice();
-        }
-        let hash = sha1::Sha1::digest(input_data);
-        let hash_boxed = hash.to_vec().into_boxed_slice();
-        *data = Slice::from_boxed_slice(hash_boxed);
+hash_function! {
+    pub fn sha1(input, _) {
+        let hash = sha1::Sha1::digest(input);
+        Ok(hash.to_vec())
     }
-    0
 }
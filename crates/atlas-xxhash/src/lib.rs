@@ -1,4 +1,7 @@
-use atlas_common::{CHashOptions, HashFlags, RegisterFunc, Slice};
+use atlas_common::{HashFlags, RegisterFunc, hash_function};
+
+/// The smallest secret XXH3 accepts.
+const SECRET_SIZE_MIN: usize = 136;
 
 #[unsafe(no_mangle)]
 pub extern "C" fn register_hashers(register: RegisterFunc) {
@@ -28,245 +31,62 @@ pub extern "C" fn register_hashers(register: RegisterFunc) {
     // );
 }
 
-#[unsafe(no_mangle)]
-pub unsafe extern "C" fn xxh32(data: *mut Slice<u8>, options: *const CHashOptions) -> i32 {
-    if data.is_null() || options.is_null() {
-        return -1;
-    }
-
-    unsafe {
-        let hash_options = &*options;
-        let seed = if !hash_options.data.is_null() {
-            &*hash_options.data.cast::<u64>()
-        } else {
-            &0
-        };
-
-        let input = &mut *data;
-        let Some(input_data) = input.as_mut_slice() else {
-            return -2;
-        };
+atlas_common::hash_batch!();
 
-        if !input.ptr.is_null() {
-            let _: Option<Box<[u8]>> = input.into_boxed_slice();
-        }
-        let hash = xxhash_rust::xxh32::xxh32(input_data, *seed as u32);
-        let hash_boxed = hash.to_be_bytes().to_vec().into_boxed_slice();
-        *data = Slice::from_boxed_slice(hash_boxed);
+hash_function! {
+    pub fn xxh32(input, options) {
+        let hash = xxhash_rust::xxh32::xxh32(input, options.seed_u32()?);
+        Ok(hash.to_be_bytes())
     }
-    0
 }
 
-#[unsafe(no_mangle)]
-pub unsafe extern "C" fn xxh64(data: *mut Slice<u8>, options: *const CHashOptions) -> i32 {
-    if data.is_null() || options.is_null() {
-        return -1;
+hash_function! {
+    pub fn xxh64(input, options) {
+        let hash = xxhash_rust::xxh64::xxh64(input, options.seed()?);
+        Ok(hash.to_be_bytes())
     }
-
-    unsafe {
-        let hash_options = &*options;
-        let seed = if !hash_options.data.is_null() {
-            &*hash_options.data.cast::<u64>()
-        } else {
-            &0
-        };
-
-        let input = &mut *data;
-        let Some(input_data) = input.as_mut_slice() else {
-            return -2;
-        };
-
-        if !input.ptr.is_null() {
-            let _: Option<Box<[u8]>> = input.into_boxed_slice();
-        }
-        let hash = xxhash_rust::xxh64::xxh64(input_data, *seed);
-        let hash_boxed = hash.to_be_bytes().to_vec().into_boxed_slice();
-        *data = Slice::from_boxed_slice(hash_boxed);
-    }
-    0
 }
 
-#[unsafe(no_mangle)]
-pub unsafe extern "C" fn xxh3_64(data: *mut Slice<u8>, options: *const CHashOptions) -> i32 {
-    if data.is_null() {
-        return -1;
+hash_function! {
+    pub fn xxh3_64(input, _) {
+        let hash = xxhash_rust::xxh3::xxh3_64(input);
+        Ok(hash.to_be_bytes())
     }
-
-    unsafe {
-        let input = &mut *data;
-        let Some(input_data) = input.as_mut_slice() else {
-            return -2;
-        };
-
-        if !input.ptr.is_null() {
-            let _: Option<Box<[u8]>> = input.into_boxed_slice();
-        }
-        let hash = xxhash_rust::xxh3::xxh3_64(input_data);
-        let hash_boxed = hash.to_be_bytes().to_vec().into_boxed_slice();
-        *data = Slice::from_boxed_slice(hash_boxed);
-    }
-    0
 }
 
-#[unsafe(no_mangle)]
-pub unsafe extern "C" fn xxh3_64_seeded(data: *mut Slice<u8>, options: *const CHashOptions) -> i32 {
-    if data.is_null() || options.is_null() {
-        return -1;
-    }
-
-    unsafe {
-        let hash_options = &*options;
-        let seed = if !hash_options.data.is_null() {
-            &*hash_options.data.cast::<u64>()
-        } else {
-            &0
-        };
-
-        let input = &mut *data;
-        let Some(input_data) = input.as_mut_slice() else {
-            return -2;
-        };
-
-        if !input.ptr.is_null() {
-            let _: Option<Box<[u8]>> = input.into_boxed_slice();
-        }
-        let hash = xxhash_rust::xxh3::xxh3_64_with_seed(input_data, *seed);
-        let hash_boxed = hash.to_be_bytes().to_vec().into_boxed_slice();
-        *data = Slice::from_boxed_slice(hash_boxed);
+hash_function! {
+    pub fn xxh3_64_seeded(input, options) {
+        let hash = xxhash_rust::xxh3::xxh3_64_with_seed(input, options.seed()?);
+        Ok(hash.to_be_bytes())
     }
-    0
 }
 
-#[unsafe(no_mangle)]
-pub unsafe extern "C" fn xxh3_64_with_secret(
-    data: *mut Slice<u8>,
-    options: *const CHashOptions,
-) -> i32 {
-    if data.is_null() || options.is_null() {
-        return -1;
+hash_function! {
+    pub fn xxh3_64_with_secret(input, options) {
+        let secret = options.secret(SECRET_SIZE_MIN)?;
+        let hash = xxhash_rust::xxh3::xxh3_64_with_secret(input, secret);
+        Ok(hash.to_be_bytes())
     }
-
-    unsafe {
-        let hash_options = &*options;
-        let secret = if !hash_options.data.is_null() {
-            &*hash_options.data.cast::<Slice<u8>>()
-            // Box::from_raw(hash_options.data as *mut Slice<u8>)
-        } else {
-            &Slice::from_vec(vec![0u8])
-        };
-
-        let input = &mut *data;
-        let Some(input_data) = input.as_mut_slice() else {
-            return -2;
-        };
-
-        if !input.ptr.is_null() {
-            let _: Option<Box<[u8]>> = input.into_boxed_slice();
-        }
-
-        let Some(secret_data) = secret.as_mut_slice() else {
-            return -2;
-        };
-        if !secret.ptr.is_null() {
-            let _: Option<Box<[u8]>> = secret.into_boxed_slice();
-        }
-
-        let hash = xxhash_rust::xxh3::xxh3_64_with_secret(input_data, secret_data);
-        let hash_boxed = hash.to_be_bytes().to_vec().into_boxed_slice();
-        *data = Slice::from_boxed_slice(hash_boxed);
-    }
-    0
 }
 
-#[unsafe(no_mangle)]
-pub unsafe extern "C" fn xxh3_128(data: *mut Slice<u8>, options: *const CHashOptions) -> i32 {
-    if data.is_null() {
-        return -1;
+hash_function! {
+    pub fn xxh3_128(input, _) {
+        let hash = xxhash_rust::xxh3::xxh3_128(input);
+        Ok(hash.to_be_bytes())
     }
-
-    unsafe {
-        let input = &mut *data;
-        let Some(input_data) = input.as_mut_slice() else {
-            return -2;
-        };
-
-        if !input.ptr.is_null() {
-            let _: Option<Box<[u8]>> = input.into_boxed_slice();
-        }
-        let hash = xxhash_rust::xxh3::xxh3_128(input_data);
-        let hash_boxed = hash.to_be_bytes().to_vec().into_boxed_slice();
-        *data = Slice::from_boxed_slice(hash_boxed);
-    }
-    0
 }
 
-#[unsafe(no_mangle)]
-pub unsafe extern "C" fn xxh3_128_seeded(
-    data: *mut Slice<u8>,
-    options: *const CHashOptions,
-) -> i32 {
-    if data.is_null() || options.is_null() {
-        return -1;
-    }
-
-    unsafe {
-        let hash_options = &*options;
-        let seed = if !hash_options.data.is_null() {
-            &*hash_options.data.cast::<u64>()
-        } else {
-            &0
-        };
-
-        let input = &mut *data;
-        let Some(input_data) = input.as_mut_slice() else {
-            return -2;
-        };
-
-        if !input.ptr.is_null() {
-            let _: Option<Box<[u8]>> = input.into_boxed_slice();
-        }
-        let hash = xxhash_rust::xxh3::xxh3_128_with_seed(input_data, *seed);
-        let hash_boxed = hash.to_be_bytes().to_vec().into_boxed_slice();
-        *data = Slice::from_boxed_slice(hash_boxed);
+hash_function! {
+    pub fn xxh3_128_seeded(input, options) {
+        let hash = xxhash_rust::xxh3::xxh3_128_with_seed(input, options.seed()?);
+        Ok(hash.to_be_bytes())
     }
-    0
 }
 
-#[unsafe(no_mangle)]
-pub unsafe extern "C" fn xxh3_128_with_secret(
-    data: *mut Slice<u8>,
-    options: *const CHashOptions,
-) -> i32 {
-    if data.is_null() || options.is_null() {
-        return -1;
-    }
-
-    unsafe {
-        let hash_options = &*options;
-        let secret = if !hash_options.data.is_null() {
-            &*hash_options.data.cast::<Slice<u8>>()
-        } else {
-            &Slice::from_vec(vec![0u8])
-        };
-
-        let input = &mut *data;
-        let Some(input_data) = input.as_mut_slice() else {
-            return -2;
-        };
-        if !input.ptr.is_null() {
-            let _: Option<Box<[u8]>> = input.into_boxed_slice();
-        }
-
-        let Some(secret_data) = secret.as_mut_slice() else {
-            return -2;
-        };
-        if !secret.ptr.is_null() {
-            let _: Option<Box<[u8]>> = secret.into_boxed_slice();
-        }
-
-        let hash = xxhash_rust::xxh3::xxh3_128_with_secret(input_data, secret_data);
-        let hash_boxed = hash.to_be_bytes().to_vec().into_boxed_slice();
-        *data = Slice::from_boxed_slice(hash_boxed);
+hash_function! {
+    pub fn xxh3_128_with_secret(input, options) {
+        let secret = options.secret(SECRET_SIZE_MIN)?;
+        let hash = xxhash_rust::xxh3::xxh3_128_with_secret(input, secret);
+        Ok(hash.to_be_bytes())
     }
-    0
 }
@@ -1,82 +1,848 @@
-use egui::{TextBuffer as _, mutex::RwLock};
-use lazy_static::lazy_static;
-use libloading::Library;
-use log::{error, info};
 use std::collections::BTreeMap;
+use std::path::Path;
+use std::sync::Arc;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::mpsc::{self, Receiver};
+use std::thread::JoinHandle;
 
-use atlas_common::{CHashOptions, HashFlags, HashFunction, HashResult, RegisterFunc, Slice};
+use log::error;
 
-pub type ExternalRegistration = extern "C" fn(register: RegisterFunc);
+use crate::collide::{self, Collider};
+use crate::forge::{ForgeError, Forger};
+use crate::invert;
+use crate::search::engine::{Checkpoint, Engine, Event, Job, Outcome, Progress};
+use crate::search::{BruteForce, Combinator, Dictionary, Keyspace, Markov, Mask, Stream, Targets};
+use crate::{ByteOrder, Case, Encoding, HashFlags, HashOptions, Preset, Presets, Registry};
 
-lazy_static! {
-    static ref HASHERS: RwLock<BTreeMap<String, (HashFlags, HashFunction)>> =
-        RwLock::new(BTreeMap::new());
-    static ref LIBRARIES: RwLock<Vec<Library>> = RwLock::new(Vec::new());
-}
+/// Names like known ones are searched for a byte at a time, given the two before.
+const MARKOV_ORDER: usize = 3;
+/// How unlikely, in bits of surprise, the last names like known ones searched are.
+const MARKOV_MAX_COST: u32 = 48;
 
 pub struct AtlasApp {
+    /// Shared with searches running in the background.
+    registry: Arc<Registry>,
     label: String,
     hasher: String,
     seed: String,
     seed_int: u64,
-    // secret: String,
+    /// Parameter values by hasher and parameter name.
+    params: BTreeMap<(String, String), u64>,
+    presets: Presets,
+    /// The preset last applied, or "None".
+    preset: String,
+    /// The name the current settings are saved as.
+    preset_name: String,
+    case: Case,
+    encoding: Encoding,
+    byte_order: ByteOrder,
+    /// The hash to find a preimage of, in hex.
+    reverse_hash: String,
+    /// The CRC to forge the input to, in hex.
+    forge_target: String,
+    /// Where in the input the forged bytes go.
+    forge_offset: usize,
+    /// The last bytes forged, kept until what they were forged from changes.
+    forge_insert: Option<(InsertKey, Result<Vec<u8>, ForgeError>)>,
+    /// The length of the strings to reverse a CRC to.
+    crc_reverse_len: usize,
+    crc_reverse_charset: String,
+    /// The strings found by the last reverse, or the error it stopped with.
+    crc_reverse_found: Vec<String>,
+    /// The reverse running in the background, if any.
+    crc_reverse: Option<Reversing>,
+    /// The hashes to search for, in hex, separated by whitespace.
+    search_targets: String,
+    /// Files of more hashes to search for, separated by `;`.
+    search_target_files: String,
+    search_prefix: String,
+    search_suffix: String,
+    search_charset: String,
+    search_max_len: usize,
+    /// A word list to search instead of brute-forcing, if set.
+    search_dictionary: String,
+    /// A mask like `npc_?l?l?d` to search instead of brute-forcing, if set.
+    search_mask: String,
+    /// Names to train a [`Markov`] model on and search names like, if set.
+    search_markov: String,
+    /// A template like `npc_{noun}_{num:2}` to search instead of brute-forcing, if set.
+    search_template: String,
+    /// Files of tokens for the template, separated by `;`, each named by its stem.
+    search_token_files: String,
+    /// The search running in the background, if any.
+    search_engine: Option<Engine>,
+    search_progress: Progress,
+    /// The inputs found by the last search, then how it ended.
+    search_found: Vec<String>,
+    /// How the last search encoded its inputs, to show them as text.
+    search_encoding: Encoding,
+    /// How many low bits of the hash to collide.
+    collide_bits: u32,
+    collide_charset: String,
+    /// How many collisions to chain into a multicollision.
+    collide_count: u32,
+    /// The inputs found by the last collision search, or the error it stopped with.
+    collide_found: Vec<String>,
+    /// The collision search running in the background, if any.
+    colliding: Option<Colliding>,
+    /// The key for keyed hashers, as hex.
+    key: String,
+    /// The secret for hashers that take one, as hex.
+    secret: String,
+}
+
+/// What forged bytes depend on.
+#[derive(PartialEq, Eq)]
+struct InsertKey {
+    hasher: String,
+    options: HashOptions,
+    input: Vec<u8>,
+    offset: usize,
+    target: Vec<u8>,
+}
+
+/// Strings with a CRC being found on another thread, which can take a while.
+struct Reversing {
+    cancelled: Arc<AtomicBool>,
+    found: Receiver<String>,
+    thread: JoinHandle<()>,
+}
+
+impl Reversing {
+    fn start(
+        registry: Arc<Registry>,
+        hasher: String,
+        options: HashOptions,
+        len: usize,
+        charset: Vec<u8>,
+        target: Vec<u8>,
+    ) -> Self {
+        let cancelled = Arc::new(AtomicBool::new(false));
+        let (sender, found) = mpsc::channel();
+        let thread = {
+            let cancelled = Arc::clone(&cancelled);
+            std::thread::spawn(move || {
+                let forger = Forger::new(&registry, &hasher, &options);
+                let inputs = match forger.reverse(len, &charset, &target) {
+                    Ok(inputs) => inputs.cancelled_by(cancelled),
+                    Err(e) => {
+                        sender.send(format!("ERROR: {e}.")).ok();
+                        return;
+                    }
+                };
+                for input in inputs.take(16) {
+                    let line = match input {
+                        Ok(input) => String::from_utf8_lossy(&input).into_owned(),
+                        Err(e) => format!("ERROR: {e}."),
+                    };
+                    if sender.send(line).is_err() {
+                        return;
+                    }
+                }
+            })
+        };
+        Self {
+            cancelled,
+            found,
+            thread,
+        }
+    }
+
+    fn cancel(&self) {
+        self.cancelled.store(true, Ordering::Relaxed);
+    }
+}
+
+impl Drop for Reversing {
+    fn drop(&mut self) {
+        self.cancel();
+    }
+}
+
+/// Inputs that collide being found on another thread, which can take a while.
+struct Colliding {
+    cancelled: Arc<AtomicBool>,
+    found: Receiver<String>,
+    thread: JoinHandle<()>,
+}
+
+impl Colliding {
+    /// Finds one collision of the low `bits`, or a multicollision of `count`
+    /// chained collisions if set.
+    fn start(
+        registry: Arc<Registry>,
+        hasher: String,
+        options: HashOptions,
+        bits: u32,
+        charset: Vec<u8>,
+        count: Option<u32>,
+    ) -> Self {
+        let cancelled = Arc::new(AtomicBool::new(false));
+        let (sender, found) = mpsc::channel();
+        let thread = {
+            let cancelled = Arc::clone(&cancelled);
+            std::thread::spawn(move || {
+                let found = match count {
+                    Some(count) => collide::multicollision(
+                        &registry,
+                        &hasher,
+                        &options,
+                        bits,
+                        &charset,
+                        count,
+                        Some(&cancelled),
+                    )
+                    .map(|found| (found.inputs().collect::<Vec<_>>(), found.hash)),
+                    None => Collider::new(&registry, &hasher, &options, bits, &charset)
+                        .and_then(|collider| collider.cancelled_by(cancelled).find())
+                        .map(|found| (found.inputs.to_vec(), found.hash)),
+                };
+                let lines = match found {
+                    Ok((inputs, hash)) => inputs
+                        .iter()
+                        .map(|input| {
+                            format!(
+                                "{} -> 0x{hash:0width$X}",
+                                String::from_utf8_lossy(input),
+                                width = bits.div_ceil(4) as usize
+                            )
+                        })
+                        .collect(),
+                    Err(e) => vec![format!("ERROR: {e}.")],
+                };
+                for line in lines {
+                    if sender.send(line).is_err() {
+                        return;
+                    }
+                }
+            })
+        };
+        Self {
+            cancelled,
+            found,
+            thread,
+        }
+    }
+
+    fn cancel(&self) {
+        self.cancelled.store(true, Ordering::Relaxed);
+    }
+}
+
+impl Drop for Colliding {
+    fn drop(&mut self) {
+        self.cancel();
+    }
 }
 
 impl Default for AtlasApp {
     fn default() -> Self {
         Self {
+            registry: Arc::new(Registry::new()),
             label: String::new(),
             hasher: String::from("None"),
             seed: String::new(),
             seed_int: 0,
-            // secret: String::new(),
+            params: BTreeMap::new(),
+            presets: Presets::new(),
+            preset: String::from("None"),
+            preset_name: String::new(),
+            case: Case::default(),
+            encoding: Encoding::default(),
+            byte_order: ByteOrder::default(),
+            reverse_hash: String::new(),
+            forge_target: String::new(),
+            forge_offset: 0,
+            forge_insert: None,
+            crc_reverse_len: 6,
+            crc_reverse_charset: String::from("abcdefghijklmnopqrstuvwxyz0123456789_"),
+            crc_reverse_found: Vec::new(),
+            crc_reverse: None,
+            search_targets: String::new(),
+            search_target_files: String::new(),
+            search_prefix: String::new(),
+            search_suffix: String::new(),
+            search_charset: String::from("abcdefghijklmnopqrstuvwxyz0123456789_"),
+            search_max_len: 4,
+            search_dictionary: String::new(),
+            search_mask: String::new(),
+            search_markov: String::new(),
+            search_template: String::new(),
+            search_token_files: String::new(),
+            search_engine: None,
+            search_progress: Progress::default(),
+            search_found: Vec::new(),
+            search_encoding: Encoding::default(),
+            collide_bits: 24,
+            collide_charset: String::from("abcdefghijklmnopqrstuvwxyz0123456789_"),
+            collide_count: 3,
+            collide_found: Vec::new(),
+            colliding: None,
+            key: String::new(),
+            secret: String::new(),
         }
     }
 }
 
-#[unsafe(no_mangle)]
-pub extern "C" fn register(name: *const i8, flags: HashFlags, hasher: HashFunction) {
-    let name_str = unsafe { std::ffi::CStr::from_ptr(name) };
-    let name_str = name_str.to_string_lossy().to_string();
-    HASHERS.write().insert(name_str.clone(), (flags, hasher));
-    info!("Registered plugin {name_str}");
-}
-
-fn register_plugins() -> anyhow::Result<()> {
-    HASHERS.write().clear();
-    LIBRARIES.write().clear();
-    let current_exe = std::env::current_exe()?;
-    let Some(exe_dir) = current_exe.parent() else {
-        return Err(anyhow::anyhow!("Failed to find parent of current exe"));
-    };
-    let plugin_dir = exe_dir.join("plugins");
-    for entry in std::fs::read_dir(plugin_dir)? {
-        let entry = entry?;
-        if entry
-            .path()
-            .extension()
-            .is_some_and(|x| matches!(x.to_string_lossy().as_str(), "dll" | "so" | "dylib"))
-        {
-            let lib = unsafe { libloading::Library::new(entry.path())? };
-            let fn_register = unsafe {
-                lib.get::<ExternalRegistration>(b"register_hashers")?
-                    .into_raw()
-            };
-
-            fn_register(register);
-            LIBRARIES.write().push(lib);
-        }
-    }
-
-    Ok(())
-}
-
 impl AtlasApp {
     pub fn new(_cc: &eframe::CreationContext<'_>) -> Self {
-        if let Err(e) = register_plugins() {
+        let mut app = Self::default();
+        app.register_plugins();
+        app.load_presets();
+        app
+    }
+
+    fn load_presets(&mut self) {
+        match Presets::default_path().and_then(Presets::load) {
+            Ok(presets) => self.presets = presets,
+            Err(e) => error!("Failed to load presets: {e}"),
+        }
+    }
+
+    fn save_presets(&self) {
+        if let Err(e) = Presets::default_path().and_then(|path| self.presets.save(path)) {
+            error!("Failed to save presets: {e}");
+        }
+    }
+
+    /// Copies a preset's settings into the editor.
+    fn apply_preset(&mut self, preset: Preset) {
+        self.seed = preset.seed.map(|seed| seed.to_string()).unwrap_or_default();
+        self.key = preset.key.map(hex::encode_upper).unwrap_or_default();
+        self.secret = preset.secret.map(hex::encode_upper).unwrap_or_default();
+        for (name, value) in preset.params {
+            self.params.insert((preset.hasher.clone(), name), value);
+        }
+        self.hasher = preset.hasher;
+        self.case = preset.case;
+        self.encoding = preset.encoding;
+        self.byte_order = preset.byte_order;
+        self.preset_name.clone_from(&preset.name);
+        self.preset = preset.name;
+    }
+
+    /// The editor's settings as a preset named `name`.
+    fn current_preset(&self, name: &str) -> Preset {
+        let params = self
+            .params
+            .iter()
+            .filter(|((hasher, _), _)| *hasher == self.hasher)
+            .map(|((_, param), value)| (param.clone(), *value))
+            .collect();
+        let flags = self
+            .registry
+            .get(&self.hasher)
+            .map_or(HashFlags::empty(), |hasher| hasher.flags());
+        Preset {
+            seed: flags.contains(HashFlags::SEEDED).then_some(self.seed_int),
+            key: flags
+                .contains(HashFlags::KEYED)
+                .then(|| hex_bytes(&self.key))
+                .flatten(),
+            secret: flags
+                .contains(HashFlags::SECRET)
+                .then(|| hex_bytes(&self.secret))
+                .flatten(),
+            params,
+            case: self.case,
+            encoding: self.encoding,
+            byte_order: self.byte_order,
+            ..Preset::new(name, self.hasher.clone())
+        }
+    }
+
+    /// Finds the input of `invertible`'s block length that hashes to a given hash.
+    fn reverse_ui(&mut self, ui: &mut egui::Ui, invertible: &invert::Invertible) {
+        ui.label(format!(
+            "Reverse a hash of a {}-byte input (hex):",
+            invertible.input_len
+        ));
+        ui.text_edit_singleline(&mut self.reverse_hash);
+        let hash = self.reverse_hash.trim();
+        if hash.is_empty() {
+            return;
+        }
+        let hash = hash.strip_prefix("0x").unwrap_or(hash);
+        let Ok(hash) = hex::decode(hash) else {
+            ui.label("ERROR: The hash is not valid hex.");
+            return;
+        };
+        match invertible.preimage(&hash, self.seed_int) {
+            Ok(input) => {
+                let hex = hex::encode_upper(&input);
+                if ui.label(format!("Input: 0x{hex}")).clicked() {
+                    ui.ctx().copy_text(hex);
+                }
+                let mut le = [0u8; 16];
+                le[..input.len()].copy_from_slice(&input);
+                ui.label(format!(
+                    "As a little-endian integer: {}",
+                    u128::from_le_bytes(le)
+                ));
+                if input
+                    .iter()
+                    .all(|byte| byte.is_ascii_graphic() || *byte == b' ')
+                {
+                    ui.label(format!("As text: {}", String::from_utf8_lossy(&input)));
+                }
+            }
+            Err(e) => {
+                ui.label(format!("ERROR: {e}."));
+            }
+        }
+    }
+
+    /// Forges the input to a target CRC, and finds short strings with it.
+    fn forge_ui(&mut self, ui: &mut egui::Ui) {
+        let preset = self.current_preset("");
+        let target = self.forge_target.trim();
+        let target = hex::decode(target.strip_prefix("0x").unwrap_or(target));
+
+        ui.horizontal(|ui| {
+            ui.label("Target CRC (hex):");
+            ui.text_edit_singleline(&mut self.forge_target);
+        });
+        let input = preset.normalise(&self.label);
+        ui.horizontal(|ui| {
+            ui.label("Insert at:");
+            ui.add(egui::DragValue::new(&mut self.forge_offset).range(0..=input.len()));
+        });
+        match &target {
+            Ok(target) if !target.is_empty() => {
+                // Forging hashes the input a few hundred times, too many to redo every frame.
+                let key = InsertKey {
+                    hasher: self.hasher.clone(),
+                    options: preset.options(),
+                    offset: self.forge_offset.min(input.len()),
+                    input,
+                    target: target.clone(),
+                };
+                if self
+                    .forge_insert
+                    .as_ref()
+                    .is_none_or(|(forged, _)| *forged != key)
+                {
+                    let forger = Forger::new(&self.registry, &key.hasher, &key.options);
+                    let patch = forger.insert(&key.input, key.offset, &key.target);
+                    self.forge_insert = Some((key, patch));
+                }
+                match self.forge_insert.as_ref().map(|(_, patch)| patch) {
+                    Some(Ok(patch)) => {
+                        let hex = hex::encode_upper(patch);
+                        if ui.label(format!("Insert: 0x{hex}")).clicked() {
+                            ui.ctx().copy_text(hex);
+                        }
+                    }
+                    Some(Err(e)) => {
+                        ui.label(format!("ERROR: {e}."));
+                    }
+                    None => {}
+                }
+            }
+            Ok(_) => {}
+            Err(_) => {
+                ui.label("ERROR: The target is not valid hex.");
+            }
+        }
+
+        ui.horizontal(|ui| {
+            ui.label("Strings of length");
+            ui.add(egui::DragValue::new(&mut self.crc_reverse_len).range(1..=16));
+            ui.label("from");
+            ui.text_edit_singleline(&mut self.crc_reverse_charset);
+        });
+        self.poll_reverse(ui.ctx());
+        if let Some(reversing) = &self.crc_reverse {
+            if ui.button("Cancel").clicked() {
+                reversing.cancel();
+            }
+        } else if ui.button("Find Strings").clicked() {
+            match target {
+                Ok(target) => {
+                    self.crc_reverse_found.clear();
+                    self.crc_reverse = Some(Reversing::start(
+                        Arc::clone(&self.registry),
+                        self.hasher.clone(),
+                        preset.options(),
+                        self.crc_reverse_len,
+                        self.crc_reverse_charset.as_bytes().to_vec(),
+                        target,
+                    ));
+                }
+                Err(_) => {
+                    self.crc_reverse_found =
+                        vec![String::from("ERROR: The target is not valid hex.")];
+                }
+            }
+        }
+        for found in &self.crc_reverse_found {
+            ui.label(found);
+        }
+    }
+
+    /// Takes in the strings the running reverse has found, and repaints until it's done.
+    fn poll_reverse(&mut self, ctx: &egui::Context) {
+        let Some(reversing) = &self.crc_reverse else {
+            return;
+        };
+        // Checked first, so nothing sent before it finished is missed.
+        let finished = reversing.thread.is_finished();
+        self.crc_reverse_found.extend(reversing.found.try_iter());
+        if finished {
+            self.crc_reverse = None;
+        } else {
+            ctx.request_repaint_after(std::time::Duration::from_millis(200));
+        }
+    }
+
+    /// Searches for inputs between a prefix and suffix that hash to targets.
+    fn search_ui(&mut self, ui: &mut egui::Ui) {
+        ui.horizontal(|ui| {
+            ui.label("Targets (hex):");
+            ui.text_edit_singleline(&mut self.search_targets);
+            ui.label("And from files:");
+            ui.text_edit_singleline(&mut self.search_target_files)
+                .on_hover_text("Hex hashes one per line, files separated by ;");
+        });
+        ui.horizontal(|ui| {
+            ui.label("Prefix:");
+            ui.text_edit_singleline(&mut self.search_prefix);
+            ui.label("Suffix:");
+            ui.text_edit_singleline(&mut self.search_suffix);
+        });
+        ui.horizontal(|ui| {
+            ui.label("Up to");
+            ui.add(egui::DragValue::new(&mut self.search_max_len).range(0..=12));
+            ui.label("bytes from");
+            ui.text_edit_singleline(&mut self.search_charset);
+        });
+        ui.horizontal(|ui| {
+            ui.label("Or a mask:");
+            ui.text_edit_singleline(&mut self.search_mask)
+                .on_hover_text("?l, ?u, ?d, ?h, ?H, ?s and ?a stand for charsets, ?? for a ?");
+            ui.label("Or words from:");
+            ui.text_edit_singleline(&mut self.search_dictionary);
+        });
+        ui.horizontal(|ui| {
+            ui.label("Or names like those in:");
+            ui.text_edit_singleline(&mut self.search_markov)
+                .on_hover_text("Known names one per line, most likely names searched first");
+        });
+        ui.horizontal(|ui| {
+            ui.label("Or tokens:");
+            ui.text_edit_singleline(&mut self.search_template)
+                .on_hover_text("A list's file stem in braces stands for its tokens, and num:2 in braces for 00 to 99");
+            ui.label("From lists:");
+            ui.text_edit_singleline(&mut self.search_token_files)
+                .on_hover_text("Tokens one per line, files separated by ;");
+        });
+        self.poll_search(ui.ctx());
+        ui.horizontal(|ui| {
+            if let Some(engine) = &self.search_engine {
+                if engine.is_paused() {
+                    if ui.button("Resume").clicked() {
+                        engine.resume_paused();
+                    }
+                } else if ui.button("Pause").clicked() {
+                    engine.pause();
+                }
+                if ui.button("Cancel").clicked() {
+                    engine.cancel();
+                }
+            } else {
+                if ui.button("Search").clicked() {
+                    self.start_search(None);
+                }
+                let checkpoint = Checkpoint::default_path().ok().filter(|path| path.exists());
+                if let Some(path) = checkpoint {
+                    if ui.button("Resume Last Search").clicked() {
+                        self.start_search(Some(path));
+                    }
+                }
+            }
+        });
+        if self.search_engine.is_some() || self.search_progress.total > 0 {
+            let progress = &self.search_progress;
+            let eta = progress
+                .eta
+                .map_or_else(|| String::from("?"), |eta| format!("{}s", eta.as_secs()));
+            let text = format!(
+                "{}/{} at {:.0}/s, {eta} left",
+                progress.done, progress.total, progress.rate
+            );
+            ui.add(egui::ProgressBar::new(progress.fraction()).text(text));
+        }
+        for found in &self.search_found {
+            ui.label(found);
+        }
+    }
+
+    /// Takes in what the running search has found, and repaints until it's done.
+    fn poll_search(&mut self, ctx: &egui::Context) {
+        let Some(engine) = &self.search_engine else {
+            return;
+        };
+        let mut finished = false;
+        for event in engine.poll() {
+            match event {
+                Event::Progress(progress) => self.search_progress = progress,
+                Event::Found(found) => {
+                    let input = self.search_encoding.decode(&found.input);
+                    self.search_found.push(if found.tags.is_empty() {
+                        input
+                    } else {
+                        format!("{input} ({})", found.tags.join(", "))
+                    });
+                }
+                Event::Finished(outcome) => {
+                    finished = true;
+                    // Only a search that was cut short, by a crash or by
+                    // closing the app, is left to resume.
+                    if let Ok(path) = Checkpoint::default_path() {
+                        match std::fs::remove_file(&path) {
+                            Err(e) if e.kind() != std::io::ErrorKind::NotFound => error!(
+                                "Failed to remove the search checkpoint {}: {e}",
+                                path.display()
+                            ),
+                            _ => {}
+                        }
+                    }
+                    self.search_found.push(match outcome {
+                        Outcome::Completed => String::from("Done."),
+                        Outcome::Cancelled => String::from("Cancelled."),
+                        Outcome::Failed(e) => format!("ERROR: {e}."),
+                    });
+                }
+            }
+        }
+        if finished {
+            self.search_engine = None;
+        } else {
+            ctx.request_repaint_after(std::time::Duration::from_millis(200));
+        }
+    }
+
+    /// Starts a search with the current settings, or carries one on from the
+    /// checkpoint at `resume`.
+    fn start_search(&mut self, resume: Option<std::path::PathBuf>) {
+        self.search_found.clear();
+        self.search_progress = Progress::default();
+        self.search_encoding = self.encoding;
+        let engine = self.search_job().and_then(|job| match resume {
+            Some(path) => Engine::resume(Arc::clone(&self.registry), job, Checkpoint::load(path)?),
+            None => Ok(Engine::start(Arc::clone(&self.registry), job)),
+        });
+        match engine {
+            Ok(engine) => self.search_engine = Some(engine),
+            Err(e) => self.search_found = vec![format!("ERROR: {e}.")],
+        }
+    }
+
+    fn search_job(&self) -> anyhow::Result<Job> {
+        let preset = self.current_preset("");
+        let typed: Vec<_> = self.search_targets.split_whitespace().collect();
+        let mut lists = vec![(String::new(), Targets::parse_list(&typed.join("\n"))?)];
+        for path in self.search_target_files.split(';').map(str::trim) {
+            if !path.is_empty() {
+                lists.push(Targets::load_list(path)?);
+            }
+        }
+        let targets = Targets::from_lists(lists).with_bloom();
+        let keyspace: Arc<dyn Keyspace> = if !self.search_dictionary.trim().is_empty() {
+            Arc::new(Dictionary::load(self.search_dictionary.trim())?)
+        } else if !self.search_mask.is_empty() {
+            Arc::new(Mask::parse(&self.search_mask)?)
+        } else if !self.search_template.is_empty() {
+            let mut lists = BTreeMap::new();
+            for path in self.search_token_files.split(';').map(str::trim) {
+                if path.is_empty() {
+                    continue;
+                }
+                let name = Path::new(path).file_stem().unwrap_or_default();
+                let tokens = Dictionary::load(path)?.words;
+                lists.insert(name.to_string_lossy().into_owned(), tokens);
+            }
+            Arc::new(Combinator::parse(&self.search_template, &lists)?)
+        } else if !self.search_markov.trim().is_empty() {
+            let names: Vec<_> = Dictionary::load(self.search_markov.trim())?
+                .iter()
+                .collect();
+            let model = Markov::train(names.iter().map(Vec::as_slice), MARKOV_ORDER);
+            Arc::new(model.likely(1, model.max_len(), MARKOV_MAX_COST))
+        } else {
+            let charset = self.search_charset.as_bytes();
+            Arc::new(BruteForce::new(charset, 0, self.search_max_len))
+        };
+        // Targets are entered as shown, and candidates as text, like the input.
+        Ok(Job {
+            checkpoint: Checkpoint::default_path().ok(),
+            ..preset.job(keyspace, targets, &self.search_prefix, &self.search_suffix)
+        })
+    }
+
+    /// The tools for finding inputs that the current hasher supports.
+    fn tools_ui(&mut self, ui: &mut egui::Ui) {
+        if self.hasher.starts_with("CRC") {
+            ui.separator();
+            self.forge_ui(ui);
+        } else {
+            // Only a CRC's tools show the reverse, so it stops with them.
+            self.crc_reverse = None;
+        }
+
+        if let Some(invertible) = invert::find(&self.hasher) {
+            ui.separator();
+            self.reverse_ui(ui, invertible);
+        }
+
+        if self.registry.get(&self.hasher).is_some() {
+            ui.separator();
+            self.search_ui(ui);
+            ui.separator();
+            self.collide_ui(ui);
+        } else {
+            self.colliding = None;
+        }
+    }
+
+    /// Finds inputs whose hashes share their low bits.
+    fn collide_ui(&mut self, ui: &mut egui::Ui) {
+        ui.horizontal(|ui| {
+            ui.label("Collide the low");
+            ui.add(egui::DragValue::new(&mut self.collide_bits).range(1..=32));
+            ui.label("bits with bytes from");
+            ui.text_edit_singleline(&mut self.collide_charset);
+        });
+        self.poll_collide(ui.ctx());
+        ui.horizontal(|ui| {
+            if let Some(colliding) = &self.colliding {
+                if ui.button("Cancel").clicked() {
+                    colliding.cancel();
+                }
+                return;
+            }
+            let options = self.current_preset("").options();
+            // Set once a button is clicked, to the count for a multicollision.
+            let mut count = None;
+            if ui.button("Find Collision").clicked() {
+                count = Some(None);
+            }
+            let stream = Stream::new(&self.registry, &self.hasher, &options);
+            if stream.is_some_and(|stream| stream.truncates()) {
+                ui.add(egui::DragValue::new(&mut self.collide_count).range(1..=8));
+                if ui.button("Find Multicollision").clicked() {
+                    count = Some(Some(self.collide_count));
+                }
+            }
+            if let Some(count) = count {
+                self.collide_found.clear();
+                self.colliding = Some(Colliding::start(
+                    Arc::clone(&self.registry),
+                    self.hasher.clone(),
+                    options,
+                    self.collide_bits,
+                    self.collide_charset.as_bytes().to_vec(),
+                    count,
+                ));
+            }
+        });
+        for found in &self.collide_found {
+            ui.label(found);
+        }
+    }
+
+    /// Takes in the inputs the running collision search has found, and
+    /// repaints until it's done.
+    fn poll_collide(&mut self, ctx: &egui::Context) {
+        let Some(colliding) = &self.colliding else {
+            return;
+        };
+        // Checked first, so nothing sent before it finished is missed.
+        let finished = colliding.thread.is_finished();
+        self.collide_found.extend(colliding.found.try_iter());
+        if finished {
+            self.colliding = None;
+        } else {
+            ctx.request_repaint_after(std::time::Duration::from_millis(200));
+        }
+    }
+
+    fn preset_ui(&mut self, ui: &mut egui::Ui) {
+        let mut selected = None;
+        egui::ComboBox::from_label("Preset")
+            .selected_text(&self.preset)
+            .height(160.0)
+            .show_ui(ui, |ui| {
+                for preset in self.presets.iter() {
+                    let response = ui
+                        .selectable_label(self.preset == preset.name, &preset.name)
+                        .on_hover_text(&preset.description);
+                    if response.clicked() {
+                        selected = Some(preset.clone());
+                    }
+                }
+            });
+        if let Some(preset) = selected {
+            self.apply_preset(preset);
+        }
+
+        ui.horizontal(|ui| {
+            egui::ComboBox::from_id_salt("case")
+                .selected_text(format!("{:?} case", self.case))
+                .show_ui(ui, |ui| {
+                    for case in [Case::Unchanged, Case::Lower, Case::Upper] {
+                        ui.selectable_value(&mut self.case, case, format!("{case:?}"));
+                    }
+                });
+            egui::ComboBox::from_id_salt("encoding")
+                .selected_text(format!("{:?}", self.encoding))
+                .show_ui(ui, |ui| {
+                    for encoding in [Encoding::Utf8, Encoding::Utf16Le] {
+                        ui.selectable_value(&mut self.encoding, encoding, format!("{encoding:?}"));
+                    }
+                });
+            egui::ComboBox::from_id_salt("byte_order")
+                .selected_text(format!("{:?} endian", self.byte_order))
+                .show_ui(ui, |ui| {
+                    for order in [ByteOrder::Big, ByteOrder::Little] {
+                        ui.selectable_value(&mut self.byte_order, order, format!("{order:?}"));
+                    }
+                });
+        });
+
+        ui.horizontal(|ui| {
+            ui.text_edit_singleline(&mut self.preset_name);
+            let name = self.preset_name.trim().to_owned();
+            if ui
+                .add_enabled(!name.is_empty(), egui::Button::new("Save Preset"))
+                .clicked()
+            {
+                self.presets.insert(self.current_preset(&name));
+                self.save_presets();
+                self.preset = name;
+            }
+            if ui
+                .add_enabled(
+                    self.presets.is_user(&self.preset),
+                    egui::Button::new("Delete Preset"),
+                )
+                .clicked()
+            {
+                self.presets.remove(&self.preset);
+                self.save_presets();
+                self.preset = String::from("None");
+            }
+        });
+    }
+
+    fn register_plugins(&mut self) {
+        // Searches still running keep the plugins they started with loaded.
+        let mut registry = Registry::new();
+        if let Err(e) = Registry::default_plugin_dir().and_then(|dir| registry.load_dir(dir)) {
             error!("Failed to register plugins: {e}");
         }
-        Default::default()
+        self.registry = Arc::new(registry);
+        self.forge_insert = None;
     }
 }
 
@@ -89,9 +855,7 @@ impl eframe::App for AtlasApp {
                         ctx.send_viewport_cmd(egui::ViewportCommand::Close);
                     }
                     if ui.button("Refresh Plugins").clicked() {
-                        if let Err(e) = register_plugins() {
-                            error!("Failed to register plugins: {e}");
-                        }
+                        self.register_plugins();
                     }
                 });
                 ui.add_space(16.0);
@@ -100,31 +864,27 @@ impl eframe::App for AtlasApp {
         });
 
         egui::CentralPanel::default().show(ctx, |ui| {
+            self.preset_ui(ui);
+
             egui::ComboBox::from_label("Hashing Function")
                 .selected_text(&self.hasher)
                 .height(160.0)
                 .show_ui(ui, |ui| {
-                    for algo in HASHERS.read().keys() {
-                        ui.selectable_value(&mut self.hasher, algo.clone(), algo);
+                    for hasher in self.registry.hashers() {
+                        ui.selectable_value(
+                            &mut self.hasher,
+                            hasher.name().to_owned(),
+                            hasher.name(),
+                        );
                     }
                 });
 
             ui.label("Input:");
             ui.text_edit_singleline(&mut self.label);
 
-            // TODO: fix secrets
-            // Empty u8 vec works, but not a vec with data?
             if self.hasher != "None" {
-                let hashers_guard = HASHERS.read();
-                let hash_function = hashers_guard.get(&self.hasher);
-                if let Some((flags, func)) = hash_function {
-                    // let mut secret_data = Vec::new();
-                    // let mut secret_data_slice = Slice::<u8>::null();
-                    let mut options = CHashOptions {
-                        data: std::ptr::null(),
-                    };
-
-                    if flags.contains(HashFlags::SEEDED) {
+                if let Some(hasher) = self.registry.get(&self.hasher) {
+                    if hasher.flags().contains(HashFlags::SEEDED) {
                         ui.label("Seed: ");
                         ui.text_edit_singleline(&mut self.seed);
                         if let Ok(seed_int) = self.seed.parse::<u64>() {
@@ -132,53 +892,50 @@ impl eframe::App for AtlasApp {
                         } else {
                             self.seed_int = 0;
                         }
-                        options.data = Box::into_raw(Box::from(self.seed_int)).cast();
-                    }
-
-                    // if flags.contains(HashFlags::SECRET) {
-                    //     ui.label("Secret (hex): ");
-                    //     ui.text_edit_singleline(&mut self.secret);
-                    //     // TODO: does this reference live to the end of the function?
-                    //     if self.secret.starts_with("0x") {
-                    //         self.secret = self.secret.trim_start_matches("0x").to_owned();
-                    //     }
-                    //     if let Ok(secret_vec) = hex::decode(self.secret.clone()) {
-                    //         secret_data = secret_vec.clone();
-                    //     } else {
-                    //         secret_data = vec![0u8];
-                    //     }
-                    //     secret_data_slice = Slice::from_boxed_slice(secret_data.into_boxed_slice());
-                    //     options.data = std::ptr::from_ref(&secret_data_slice).cast();
-                    // }
-
-                    let input_data = self.label.as_bytes();
-                    let boxed = input_data.to_vec().into_boxed_slice();
-                    let mut data: Slice<u8> = Slice::from_boxed_slice(boxed);
-                    // SAFETY: options and data will never be null
-                    let ret = unsafe {
-                        func(
-                            std::ptr::from_mut(&mut data),
-                            std::ptr::from_ref(&options).cast(),
-                        )
-                    };
-                    if ret != 0 {
-                        ui.label(format!("ERROR: Internal error ocurred: {ret}."));
-                    } else if let Some(hash) = data.into_boxed_slice() {
-                        let hash_result = HashResult::from(hash);
-                        let hash_label = ui.label(format!("Output: 0x{}", hash_result.0));
-                        if hash_label.clicked() {
-                            ctx.copy_text(hash_result.0);
-                        } else if hash_label.secondary_clicked() {
-                            ctx.copy_text(hash_result.flip_endian().0);
+                    }
+                    if hasher.flags().contains(HashFlags::KEYED) {
+                        hex_ui(ui, "Key", &mut self.key);
+                    }
+                    if hasher.flags().contains(HashFlags::SECRET) {
+                        hex_ui(ui, "Secret", &mut self.secret);
+                    }
+
+                    for param in hasher.params() {
+                        let value = self
+                            .params
+                            .entry((self.hasher.clone(), param.name.clone()))
+                            .or_insert(param.default);
+                        ui.horizontal(|ui| {
+                            ui.label(format!("{}: ", param.name));
+                            ui.add(
+                                egui::DragValue::new(value)
+                                    .range(param.min..=param.max)
+                                    .speed(param.step as f64),
+                            );
+                        });
+                    }
+
+                    let preset = self.current_preset(&self.preset_name);
+                    match preset.hash(&self.registry, &self.label) {
+                        Ok(hash_result) => {
+                            let hash_label = ui.label(format!("Output: 0x{}", hash_result.0));
+                            if hash_label.clicked() {
+                                ctx.copy_text(hash_result.0);
+                            } else if hash_label.secondary_clicked() {
+                                ctx.copy_text(hash_result.flip_endian().0);
+                            }
+                        }
+                        Err(e) => {
+                            ui.label(format!("ERROR: {e}."));
                         }
-                    } else {
-                        ui.label("ERROR: Result returned null!");
                     }
                 } else {
                     ui.label("ERROR: Could not find the hash function!");
                 }
             }
 
+            self.tools_ui(ui);
+
             ui.separator();
 
             ui.with_layout(egui::Layout::bottom_up(egui::Align::LEFT), |ui| {
@@ -189,6 +946,29 @@ impl eframe::App for AtlasApp {
     }
 }
 
+/// An editor for bytes entered as hex, which are left unset while empty.
+fn hex_ui(ui: &mut egui::Ui, label: &str, text: &mut String) {
+    ui.label(format!("{label} (hex): "));
+    ui.text_edit_singleline(text);
+    if !text.trim().is_empty() && hex_bytes(text).is_none() {
+        ui.label(format!(
+            "ERROR: The {} is not valid hex.",
+            label.to_lowercase()
+        ));
+    }
+}
+
+/// The bytes `text` spells in hex, with or without `0x`, or `None` if it's
+/// empty or not hex.
+fn hex_bytes(text: &str) -> Option<Vec<u8>> {
+    let text = text.trim();
+    let text = text.strip_prefix("0x").unwrap_or(text);
+    if text.is_empty() {
+        return None;
+    }
+    hex::decode(text).ok()
+}
+
 fn powered_by_egui_and_eframe(ui: &mut egui::Ui) {
     ui.horizontal(|ui| {
         ui.spacing_mut().item_spacing.x = 0.0;
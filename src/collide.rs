@@ -0,0 +1,430 @@
+//! Finding distinct inputs whose hashes agree in their low bits.
+//!
+//! Hashes truncated to `bits` bits collide after about `2^(bits / 2)` tries.
+//! A [`Collider`] finds them with Pollard's rho and distinguished points: it
+//! walks chains `x -> hash(input(x))` through the truncated hash values, where
+//! `input(x)` spells `x` in the charset, and stores only the points ending
+//! each chain. Two chains ending at the same point merged somewhere, and
+//! walking them again from their starts finds the two inputs that merged.
+//!
+//! The low bits of FNV, djb2, sdbm and BKDR only depend on the low bits of
+//! their state, so truncated, each is the same hasher with a `bits`-bit
+//! state. Collisions of that state persist
+//! through any input that follows, so chaining `k` of them gives `2^k`
+//! inputs with the same truncated hash, as [`multicollision`] does.
+
+use std::collections::{HashMap, HashSet};
+use std::sync::Arc;
+use std::sync::atomic::{AtomicBool, Ordering};
+
+use crate::search::Stream;
+use crate::{HashError, HashOptions, Registry};
+
+/// Errors returned when finding collisions.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum CollideError {
+    /// The hasher failed.
+    Hash(HashError),
+    /// Only hashes truncated to between 1 and 64 bits can be collided.
+    Bits(u32),
+    /// The hash is shorter than the bits to collide.
+    HashTooShort { bits: u32, hash_bits: usize },
+    /// The charset needs at least two distinct bytes.
+    Charset,
+    /// Multicollisions are only found for hashers whose [`Stream`] truncates.
+    Unsupported(String),
+    /// At most 63 collisions can be chained into a multicollision.
+    TooMany(u32),
+    /// No new collision turned up in many times the tries one should take,
+    /// as happens when the hasher is a bijection on the inputs tried, or all
+    /// of the few collisions of a small truncation were found.
+    NotFound,
+    /// The search was cancelled before it found a collision.
+    Cancelled,
+}
+
+impl std::fmt::Display for CollideError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Hash(e) => e.fmt(f),
+            Self::Bits(bits) => write!(f, "Can only collide 1 to 64 bits, got {bits}"),
+            Self::HashTooShort { bits, hash_bits } => {
+                write!(f, "Can't collide {bits} bits of a {hash_bits}-bit hash")
+            }
+            Self::Charset => write!(f, "The charset needs at least two distinct bytes"),
+            Self::Unsupported(name) => write!(f, "{name} has no multicollisions"),
+            Self::TooMany(count) => {
+                write!(f, "Can chain at most 63 collisions, got {count}")
+            }
+            Self::NotFound => write!(f, "No new collision found, there may be none"),
+            Self::Cancelled => write!(f, "Cancelled"),
+        }
+    }
+}
+
+impl std::error::Error for CollideError {}
+
+impl From<HashError> for CollideError {
+    fn from(e: HashError) -> Self {
+        Self::Hash(e)
+    }
+}
+
+/// Two distinct inputs whose hashes agree in the truncated bits.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Collision {
+    pub inputs: [Vec<u8>; 2],
+    /// The low bits the hashes share.
+    pub hash: u64,
+}
+
+/// Spells values of up to `bits` bits as inputs from a charset.
+#[derive(Clone, Debug)]
+struct Alphabet {
+    charset: Vec<u8>,
+    len: usize,
+}
+
+impl Alphabet {
+    fn new(charset: &[u8], bits: u32) -> Result<Self, CollideError> {
+        if bits == 0 || bits > 64 {
+            return Err(CollideError::Bits(bits));
+        }
+        let mut charset = charset.to_vec();
+        charset.sort_unstable();
+        charset.dedup();
+        if charset.len() < 2 {
+            return Err(CollideError::Charset);
+        }
+        // One more character than the values need, so that a hasher which is
+        // a bijection on inputs of exactly the bits' length still collides.
+        let base = charset.len() as u128;
+        let mut len = 1;
+        let mut count = base;
+        while count < 1 << bits {
+            len += 1;
+            count *= base;
+        }
+        Ok(Self {
+            charset,
+            len: len + 1,
+        })
+    }
+
+    /// The input spelling `value`, most significant character first.
+    fn spell(&self, mut value: u64) -> Vec<u8> {
+        let base = self.charset.len() as u64;
+        let mut input = vec![self.charset[0]; self.len];
+        for byte in input.iter_mut().rev() {
+            *byte = self.charset[(value % base) as usize];
+            value /= base;
+        }
+        input
+    }
+}
+
+/// A generator of well spread chain starts, from `SplitMix64`.
+fn split_mix(state: &mut u64) -> u64 {
+    *state = state.wrapping_add(0x9e3779b97f4a7c15);
+    let mut z = *state;
+    z = (z ^ (z >> 30)).wrapping_mul(0xbf58476d1ce4e5b9);
+    z = (z ^ (z >> 27)).wrapping_mul(0x94d049bb133111eb);
+    z ^ (z >> 31)
+}
+
+/// Pollard's rho with distinguished points over a function on `bits` bits.
+struct Rho {
+    mask: u64,
+    /// Points with none of these bits set end chains.
+    distinguished: u64,
+    /// How many chains to run for a collision before giving up.
+    max_chains: u64,
+    /// The start and length of the chain ending at each distinguished point.
+    points: HashMap<u64, (u64, u64)>,
+    /// The pairs already returned, smallest first.
+    found: HashSet<(u64, u64)>,
+    seed: u64,
+}
+
+impl Rho {
+    fn new(bits: u32, seed: u64) -> Self {
+        // Chains of about 2^(bits / 4) steps balance the stored points
+        // against the steps wasted after two chains merge.
+        let distinguished = (1 << (bits / 4)) - 1;
+        Self {
+            mask: u64::MAX >> (64 - bits),
+            distinguished,
+            // A collision takes about 2^(bits / 2) steps, so chains for 16
+            // times that find one unless there's none to find.
+            max_chains: (16 << (bits / 2)) / (distinguished + 1),
+            points: HashMap::new(),
+            found: HashSet::new(),
+            seed,
+        }
+    }
+
+    /// Runs chains until two merge, returning distinct points `f` maps to the
+    /// same value.
+    ///
+    /// Gives up with [`CollideError::NotFound`] after `max_chains` chains, or
+    /// with [`CollideError::Cancelled`] once `cancelled` is set, which is
+    /// checked between chains.
+    fn collide<E>(
+        &mut self,
+        mut f: impl FnMut(u64) -> Result<u64, E>,
+        cancelled: Option<&AtomicBool>,
+    ) -> Result<(u64, u64), CollideError>
+    where
+        CollideError: From<E>,
+    {
+        // A chain that long is likely stuck in a cycle without a
+        // distinguished point, so it's abandoned.
+        let max_len = 20 * (self.distinguished + 1);
+        for _ in 0..self.max_chains {
+            if cancelled.is_some_and(|cancelled| cancelled.load(Ordering::Relaxed)) {
+                return Err(CollideError::Cancelled);
+            }
+            let start = split_mix(&mut self.seed) & self.mask;
+            let mut point = start;
+            let mut len = 0;
+            while len < max_len {
+                point = f(point)?;
+                len += 1;
+                if point & self.distinguished == 0 {
+                    break;
+                }
+            }
+            if point & self.distinguished != 0 {
+                continue;
+            }
+            let Some(&(other, other_len)) = self.points.get(&point) else {
+                self.points.insert(point, (start, len));
+                continue;
+            };
+            if let Some((a, b)) = Self::merge(&mut f, (start, len), (other, other_len))? {
+                if self.found.insert((a.min(b), a.max(b))) {
+                    return Ok((a, b));
+                }
+            }
+        }
+        Err(CollideError::NotFound)
+    }
+
+    /// Walks two chains ending at the same point to where they merge.
+    fn merge<E>(
+        f: &mut impl FnMut(u64) -> Result<u64, E>,
+        (mut a, mut a_len): (u64, u64),
+        (mut b, mut b_len): (u64, u64),
+    ) -> Result<Option<(u64, u64)>, E> {
+        while a_len > b_len {
+            a = f(a)?;
+            a_len -= 1;
+        }
+        while b_len > a_len {
+            b = f(b)?;
+            b_len -= 1;
+        }
+        // If one chain started on the other, they never differ.
+        while a != b {
+            let (next_a, next_b) = (f(a)?, f(b)?);
+            if next_a == next_b {
+                return Ok(Some((a, b)));
+            }
+            (a, b) = (next_a, next_b);
+        }
+        Ok(None)
+    }
+}
+
+/// A hasher truncated to its low bits.
+struct Truncated<'a> {
+    registry: &'a Registry,
+    hasher: &'a str,
+    options: &'a HashOptions,
+    /// Hashes on the host where it can, for speed.
+    stream: Option<Stream>,
+    mask: u64,
+}
+
+impl Truncated<'_> {
+    fn hash(&self, input: &[u8]) -> Result<u64, HashError> {
+        let hash = if let Some(stream) = &self.stream {
+            stream.value(stream.update(stream.start(), input))
+        } else {
+            let hash = self.registry.hash(self.hasher, input, self.options)?;
+            let hash = hex::decode(hash.0).unwrap_or_default();
+            let low = &hash[hash.len().saturating_sub(8)..];
+            low.iter()
+                .fold(0, |value, byte| (value << 8) | u64::from(*byte))
+        };
+        Ok(hash & self.mask)
+    }
+}
+
+/// Finds collisions of one hasher truncated to its low bits.
+///
+/// Every input is the same length, from the charset, and collisions are
+/// returned as they're found, never the same pair twice.
+///
+/// # Examples
+/// ```no_run
+/// use atlas::collide::Collider;
+/// use atlas::{HashOptions, Registry};
+///
+/// let mut registry = Registry::new();
+/// registry.load_dir(Registry::default_plugin_dir()?)?;
+///
+/// let options = HashOptions::default();
+/// let charset = b"abcdefghijklmnopqrstuvwxyz";
+/// let mut collider = Collider::new(&registry, "CRC-32", &options, 24, charset)?;
+/// let collision = collider.find()?;
+/// let [a, b] = &collision.inputs;
+/// assert_ne!(a, b);
+/// # Ok::<(), anyhow::Error>(())
+/// ```
+pub struct Collider<'a> {
+    truncated: Truncated<'a>,
+    alphabet: Alphabet,
+    rho: Rho,
+    cancelled: Option<Arc<AtomicBool>>,
+}
+
+impl<'a> Collider<'a> {
+    pub fn new(
+        registry: &'a Registry,
+        hasher: &'a str,
+        options: &'a HashOptions,
+        bits: u32,
+        charset: &[u8],
+    ) -> Result<Self, CollideError> {
+        let alphabet = Alphabet::new(charset, bits)?;
+        let hash = registry.hash(hasher, &[], options)?;
+        let hash_bits = hash.0.len() * 4;
+        if hash_bits < bits as usize {
+            return Err(CollideError::HashTooShort { bits, hash_bits });
+        }
+        let truncated = Truncated {
+            registry,
+            hasher,
+            options,
+            stream: Stream::new(registry, hasher, options),
+            mask: u64::MAX >> (64 - bits),
+        };
+        Ok(Self {
+            truncated,
+            alphabet,
+            rho: Rho::new(bits, 0),
+            cancelled: None,
+        })
+    }
+
+    /// Gives up with [`CollideError::Cancelled`] once `cancelled` is set.
+    ///
+    /// Collisions of many bits take longer than anyone waits, and the flag
+    /// is checked after every chain.
+    pub fn cancelled_by(mut self, cancelled: Arc<AtomicBool>) -> Self {
+        self.cancelled = Some(cancelled);
+        self
+    }
+}
+
+impl Collider<'_> {
+    /// The next collision, which takes about `2^(bits / 2)` hashes.
+    ///
+    /// Gives up with [`CollideError::NotFound`] after 16 times that.
+    pub fn find(&mut self) -> Result<Collision, CollideError> {
+        let Self {
+            truncated,
+            alphabet,
+            rho,
+            cancelled,
+        } = self;
+        let (a, b) = rho.collide(|x| truncated.hash(&alphabet.spell(x)), cancelled.as_deref())?;
+        let inputs = [alphabet.spell(a), alphabet.spell(b)];
+        let hash = truncated.hash(&inputs[0])?;
+        Ok(Collision { inputs, hash })
+    }
+}
+
+/// Ends when [`Collider::find`] gives up with [`CollideError::NotFound`] or
+/// [`CollideError::Cancelled`].
+impl Iterator for Collider<'_> {
+    type Item = Result<Collision, CollideError>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        match self.find() {
+            Err(CollideError::NotFound | CollideError::Cancelled) => None,
+            result => Some(result),
+        }
+    }
+}
+
+/// Inputs that all share the same truncated hash, made of a choice of one
+/// block from each pair.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Multicollision {
+    pub blocks: Vec<[Vec<u8>; 2]>,
+    /// The low bits the hashes share.
+    pub hash: u64,
+}
+
+impl Multicollision {
+    /// Every input, `2^blocks.len()` of them.
+    pub fn inputs(&self) -> impl Iterator<Item = Vec<u8>> + '_ {
+        (0..1u64 << self.blocks.len()).map(|choice| {
+            self.blocks
+                .iter()
+                .enumerate()
+                .flat_map(|(i, pair)| pair[((choice >> i) & 1) as usize].iter().copied())
+                .collect()
+        })
+    }
+}
+
+/// `2^count` inputs from `charset` with the same hash truncated to `bits`
+/// bits, for hashers whose [`Stream`] truncates: the 32- and 64-bit FNV
+/// hashers, djb2, djb2a, sdbm and BKDR.
+///
+/// Each block pair is a collision of the truncated state, which costs about
+/// `2^(bits / 2)` steps, so this takes `count` times that rather than the
+/// `2^(bits * (2^count - 1) / 2^count)` of a generic multicollision.
+///
+/// Gives up with [`CollideError::Cancelled`] once `cancelled` is set.
+pub fn multicollision(
+    registry: &Registry,
+    hasher: &str,
+    options: &HashOptions,
+    bits: u32,
+    charset: &[u8],
+    count: u32,
+    cancelled: Option<&AtomicBool>,
+) -> Result<Multicollision, CollideError> {
+    let alphabet = Alphabet::new(charset, bits)?;
+    if count > 63 {
+        return Err(CollideError::TooMany(count));
+    }
+    let stream = Stream::new(registry, hasher, options)
+        .filter(Stream::truncates)
+        .ok_or_else(|| CollideError::Unsupported(hasher.to_owned()))?;
+    let hash_bits = stream.to_bytes(0).len() * 8;
+    if hash_bits < bits as usize {
+        return Err(CollideError::HashTooShort { bits, hash_bits });
+    }
+
+    let mask = u64::MAX >> (64 - bits);
+    let mut state = stream.start();
+    let mut blocks = Vec::new();
+    for stage in 0..count {
+        let mut rho = Rho::new(bits, u64::from(stage));
+        let step =
+            |x| Ok::<_, HashError>(stream.value(stream.update(state, &alphabet.spell(x))) & mask);
+        let (a, b) = rho.collide(step, cancelled)?;
+        let pair = [alphabet.spell(a), alphabet.spell(b)];
+        state = stream.update(state, &pair[0]);
+        blocks.push(pair);
+    }
+    Ok(Multicollision {
+        blocks,
+        hash: stream.value(state) & mask,
+    })
+}
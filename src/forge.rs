@@ -0,0 +1,354 @@
+//! Making data hash to a chosen CRC.
+//!
+//! For inputs of one length, every CRC is affine over GF(2): flipping a bit of
+//! the input flips a fixed set of bits of the CRC, whatever the rest of the
+//! input is. A [`Forger`] measures those sets by calling the hasher, then
+//! solves for the bytes that move the CRC to a target. This works for any
+//! registered hasher with that property, such as every CRC the `CRC` hasher's
+//! parameters can describe, and is checked against the hasher for every result.
+
+use std::collections::VecDeque;
+use std::sync::Arc;
+use std::sync::atomic::{AtomicBool, Ordering};
+
+use crate::{HashError, HashOptions, Registry};
+
+/// Errors returned by [`Forger`].
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum ForgeError {
+    /// The hasher failed.
+    Hash(HashError),
+    /// Only hashes of up to 128 bits can be forged.
+    HashTooLong(usize),
+    /// The target isn't as long as the hasher's output.
+    TargetLength { expected: usize, actual: usize },
+    /// The offset is past the end of the data.
+    OffsetOutOfRange { offset: usize, len: usize },
+    /// No bytes at that position reach the target.
+    NoSolution,
+    /// The hasher isn't affine, so the solved bytes didn't reach the target.
+    NotLinear,
+}
+
+impl std::fmt::Display for ForgeError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Hash(e) => e.fmt(f),
+            Self::HashTooLong(len) => {
+                write!(f, "Only hashes of up to 16 bytes can be forged, got {len}")
+            }
+            Self::TargetLength { expected, actual } => {
+                write!(f, "The target must be {expected} bytes, got {actual}")
+            }
+            Self::OffsetOutOfRange { offset, len } => {
+                write!(
+                    f,
+                    "Offset {offset} is past the end of the {len} bytes of data"
+                )
+            }
+            Self::NoSolution => write!(f, "No bytes at that position reach the target"),
+            Self::NotLinear => write!(f, "The hasher is not linear, like a CRC is"),
+        }
+    }
+}
+
+impl std::error::Error for ForgeError {}
+
+impl From<HashError> for ForgeError {
+    fn from(e: HashError) -> Self {
+        Self::Hash(e)
+    }
+}
+
+/// A GF(2) linear map from up to 128 input bits to up to 128 hash bits, kept
+/// in a form that solves for inputs.
+struct System {
+    /// Reduced hash vectors by their highest bit, with the inputs giving them.
+    pivots: Vec<(u128, u128)>,
+    /// Combinations of inputs that don't change the hash.
+    kernel: Vec<u128>,
+}
+
+impl System {
+    /// Builds the system from the hash vector each input bit flips.
+    fn new(columns: impl IntoIterator<Item = u128>) -> Self {
+        let mut system = Self {
+            pivots: Vec::new(),
+            kernel: Vec::new(),
+        };
+        for (bit, column) in columns.into_iter().enumerate() {
+            let (vector, inputs) = system.reduce(column, 1 << bit);
+            if vector == 0 {
+                system.kernel.push(inputs);
+            } else {
+                system.pivots.push((vector, inputs));
+            }
+        }
+        system
+    }
+
+    /// Reduces `vector` by the pivots, tracking the inputs it came from.
+    fn reduce(&self, mut vector: u128, mut inputs: u128) -> (u128, u128) {
+        for (pivot, pivot_inputs) in &self.pivots {
+            if vector & (1 << (127 - pivot.leading_zeros())) != 0 {
+                vector ^= pivot;
+                inputs ^= pivot_inputs;
+            }
+        }
+        (vector, inputs)
+    }
+
+    /// Every set of inputs that flips exactly the bits of `target`.
+    fn solve(&self, target: u128) -> impl Iterator<Item = u128> + '_ {
+        let (residual, solution) = self.reduce(target, 0);
+        // A CRC's kernel is only what the extra bits of a partial last byte
+        // span, so this stays small; hashers it isn't are caught by the checks.
+        let count = if residual == 0 {
+            1u64 << self.kernel.len().min(16)
+        } else {
+            0
+        };
+        (0..count).map(move |mask| {
+            self.kernel
+                .iter()
+                .enumerate()
+                .filter(|(i, _)| mask & (1 << i) != 0)
+                .fold(solution, |solution, (_, kernel)| solution ^ kernel)
+        })
+    }
+}
+
+/// The bits of a hash as an integer, big-endian as the hasher returns it.
+fn to_bits(hash: &[u8]) -> u128 {
+    hash.iter()
+        .fold(0, |bits, byte| (bits << 8) | u128::from(*byte))
+}
+
+/// The `len` bytes an input bit mask stands for.
+fn to_bytes(inputs: u128, len: usize) -> Vec<u8> {
+    inputs.to_le_bytes()[..len].to_vec()
+}
+
+/// Forges inputs for one hasher, called with fixed options.
+///
+/// # Examples
+/// ```no_run
+/// use atlas::forge::Forger;
+/// use atlas::{HashOptions, Registry};
+///
+/// let mut registry = Registry::new();
+/// registry.load_dir(Registry::default_plugin_dir()?)?;
+///
+/// let options = HashOptions::default();
+/// let forger = Forger::new(&registry, "CRC-32", &options);
+/// let mut data = b"save data".to_vec();
+/// let patch = forger.insert(&data, data.len(), &[0xde, 0xad, 0xbe, 0xef])?;
+/// data.extend(patch);
+/// assert_eq!(registry.hash("CRC-32", &data, &options)?.0, "DEADBEEF");
+/// # Ok::<(), anyhow::Error>(())
+/// ```
+pub struct Forger<'a> {
+    registry: &'a Registry,
+    hasher: &'a str,
+    options: &'a HashOptions,
+}
+
+impl<'a> Forger<'a> {
+    pub fn new(registry: &'a Registry, hasher: &'a str, options: &'a HashOptions) -> Self {
+        Self {
+            registry,
+            hasher,
+            options,
+        }
+    }
+
+    fn hash(&self, input: &[u8]) -> Result<Vec<u8>, ForgeError> {
+        let hash = self.registry.hash(self.hasher, input, self.options)?;
+        Ok(hex::decode(hash.0).unwrap_or_default())
+    }
+
+    /// The hash of `data`, checking it can be forged and `target` matches it.
+    fn check_target(&self, data: &[u8], target: &[u8]) -> Result<Vec<u8>, ForgeError> {
+        let hash = self.hash(data)?;
+        if hash.len() > 16 {
+            return Err(ForgeError::HashTooLong(hash.len()));
+        }
+        if target.len() != hash.len() {
+            return Err(ForgeError::TargetLength {
+                expected: hash.len(),
+                actual: target.len(),
+            });
+        }
+        Ok(hash)
+    }
+
+    /// The system for the `len` bytes at `offset` of `data`, from the hash
+    /// vector each of their bits flips.
+    fn system(
+        &self,
+        data: &[u8],
+        hash: &[u8],
+        offset: usize,
+        len: usize,
+    ) -> Result<System, ForgeError> {
+        let base = to_bits(hash);
+        let mut input = data.to_vec();
+        let mut columns = Vec::with_capacity(8 * len);
+        for bit in 0..8 * len {
+            input[offset + bit / 8] ^= 1 << (bit % 8);
+            columns.push(to_bits(&self.hash(&input)?) ^ base);
+            input[offset + bit / 8] ^= 1 << (bit % 8);
+        }
+
+        // Flipping every bit must flip the sum of the columns, as it would for a CRC.
+        for byte in &mut input[offset..offset + len] {
+            *byte ^= 0xff;
+        }
+        let flipped = columns.iter().fold(base, |bits, column| bits ^ column);
+        if to_bits(&self.hash(&input)?) != flipped {
+            return Err(ForgeError::NotLinear);
+        }
+        Ok(System::new(columns))
+    }
+
+    /// The bytes to insert at `offset` of `data` so it hashes to `target`, as
+    /// many as the hash is long.
+    pub fn insert(&self, data: &[u8], offset: usize, target: &[u8]) -> Result<Vec<u8>, ForgeError> {
+        if offset > data.len() {
+            return Err(ForgeError::OffsetOutOfRange {
+                offset,
+                len: data.len(),
+            });
+        }
+        let mut input = data.to_vec();
+        let len = self.hash(data)?.len();
+        input.splice(offset..offset, std::iter::repeat_n(0, len));
+        let hash = self.check_target(&input, target)?;
+        let system = self.system(&input, &hash, offset, len)?;
+        let inputs = system
+            .solve(to_bits(&hash) ^ to_bits(target))
+            .next()
+            .ok_or(ForgeError::NoSolution)?;
+        let patch = to_bytes(inputs, len);
+        input[offset..offset + len].copy_from_slice(&patch);
+        if self.hash(&input)? != target {
+            return Err(ForgeError::NotLinear);
+        }
+        Ok(patch)
+    }
+
+    /// Inputs of `len` bytes from `charset` that hash to `target`.
+    ///
+    /// Every combination of the leading bytes is tried, and the last ones
+    /// (as many as the hash is long) are solved for, keeping solutions whose
+    /// bytes are all in the charset.
+    pub fn reverse(
+        &self,
+        len: usize,
+        charset: &'a [u8],
+        target: &[u8],
+    ) -> Result<Reverse<'a>, ForgeError> {
+        let zeros = vec![0; len];
+        let hash = self.check_target(&zeros, target)?;
+        let solved = hash.len().min(len);
+        let system = self.system(&zeros, &hash, len - solved, solved)?;
+        Ok(Reverse {
+            forger: Forger {
+                registry: self.registry,
+                hasher: self.hasher,
+                options: self.options,
+            },
+            charset,
+            target: target.to_vec(),
+            system,
+            solved,
+            prefix: vec![0; len - solved],
+            pending: VecDeque::new(),
+            done: charset.is_empty(),
+            cancelled: None,
+        })
+    }
+}
+
+/// The inputs found by [`Forger::reverse`], in the order their leading bytes
+/// are enumerated.
+pub struct Reverse<'a> {
+    forger: Forger<'a>,
+    charset: &'a [u8],
+    target: Vec<u8>,
+    system: System,
+    /// How many bytes at the end are solved for.
+    solved: usize,
+    /// The charset indices of the leading bytes to try next.
+    prefix: Vec<usize>,
+    pending: VecDeque<Vec<u8>>,
+    done: bool,
+    cancelled: Option<Arc<AtomicBool>>,
+}
+
+impl Reverse<'_> {
+    /// Stops early, as if every input had been tried, once `cancelled` is set.
+    ///
+    /// Long inputs from a large charset can take longer than anyone waits, and
+    /// the flag is checked between leading bytes, even when nothing is found.
+    pub fn cancelled_by(mut self, cancelled: Arc<AtomicBool>) -> Self {
+        self.cancelled = Some(cancelled);
+        self
+    }
+
+    /// Solves for the current leading bytes, then moves on to the next ones.
+    fn step(&mut self) -> Result<(), ForgeError> {
+        let mut input: Vec<u8> = self.prefix.iter().map(|i| self.charset[*i]).collect();
+        let offset = input.len();
+        input.resize(offset + self.solved, 0);
+        let flips = to_bits(&self.forger.hash(&input)?) ^ to_bits(&self.target);
+        for inputs in self.system.solve(flips) {
+            let tail = to_bytes(inputs, self.solved);
+            if tail.iter().all(|byte| self.charset.contains(byte)) {
+                input[offset..].copy_from_slice(&tail);
+                if self.forger.hash(&input)? != self.target {
+                    return Err(ForgeError::NotLinear);
+                }
+                self.pending.push_back(input.clone());
+            }
+        }
+
+        // Advance the leading bytes like an odometer, the last one fastest.
+        self.done = true;
+        for index in self.prefix.iter_mut().rev() {
+            *index += 1;
+            if *index < self.charset.len() {
+                self.done = false;
+                break;
+            }
+            *index = 0;
+        }
+        Ok(())
+    }
+}
+
+impl Iterator for Reverse<'_> {
+    type Item = Result<Vec<u8>, ForgeError>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        loop {
+            let cancelled = self
+                .cancelled
+                .as_ref()
+                .is_some_and(|cancelled| cancelled.load(Ordering::Relaxed));
+            if cancelled {
+                return None;
+            }
+            if let Some(input) = self.pending.pop_front() {
+                return Some(Ok(input));
+            }
+            if self.done {
+                return None;
+            }
+            if let Err(e) = self.step() {
+                self.done = true;
+                return Some(Err(e));
+            }
+        }
+    }
+}
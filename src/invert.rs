@@ -0,0 +1,329 @@
+//! Inverses of the bijective steps in Murmur3 and xxHash.
+//!
+//! The finalisers of Murmur3 (`fmix32`, `fmix64`) and XXH32/XXH64 (the
+//! avalanche) are built from xorshifts and odd multiplications, which can
+//! both be undone. For inputs exactly one block long, every other step is
+//! invertible as well, so the input can be recovered from the hash and seed.
+//! This is how integer IDs hashed as 4, 8 or 16 raw bytes are recovered.
+//!
+//! Other input lengths hash to the same values, so the preimage found is only
+//! the input of that one length.
+
+/// The multiplicative inverse of an odd `a` modulo 2^32, by Newton's method.
+const fn inverse32(a: u32) -> u32 {
+    let mut x = a;
+    let mut i = 0;
+    while i < 5 {
+        x = x.wrapping_mul(2u32.wrapping_sub(a.wrapping_mul(x)));
+        i += 1;
+    }
+    x
+}
+
+/// The multiplicative inverse of an odd `a` modulo 2^64, by Newton's method.
+const fn inverse64(a: u64) -> u64 {
+    let mut x = a;
+    let mut i = 0;
+    while i < 6 {
+        x = x.wrapping_mul(2u64.wrapping_sub(a.wrapping_mul(x)));
+        i += 1;
+    }
+    x
+}
+
+/// Undoes `x ^= x >> shift`.
+fn unshift32(x: u32, shift: u32) -> u32 {
+    let mut y = x;
+    for _ in 0..32 / shift {
+        y = x ^ (y >> shift);
+    }
+    y
+}
+
+/// Undoes `x ^= x >> shift`.
+fn unshift64(x: u64, shift: u32) -> u64 {
+    let mut y = x;
+    for _ in 0..64 / shift {
+        y = x ^ (y >> shift);
+    }
+    y
+}
+
+const FMIX32_M1: u32 = 0x85ebca6b;
+const FMIX32_M2: u32 = 0xc2b2ae35;
+const FMIX64_M1: u64 = 0xff51afd7ed558ccd;
+const FMIX64_M2: u64 = 0xc4ceb9fe1a85ec53;
+
+/// Murmur3's 32-bit finaliser.
+pub fn fmix32(mut h: u32) -> u32 {
+    h ^= h >> 16;
+    h = h.wrapping_mul(FMIX32_M1);
+    h ^= h >> 13;
+    h = h.wrapping_mul(FMIX32_M2);
+    h ^ (h >> 16)
+}
+
+/// The inverse of [`fmix32`].
+///
+/// # Examples
+/// ```
+/// use atlas::invert::{fmix32, unfmix32};
+///
+/// assert_eq!(unfmix32(fmix32(0xdeadbeef)), 0xdeadbeef);
+/// ```
+pub fn unfmix32(mut h: u32) -> u32 {
+    h = unshift32(h, 16);
+    h = h.wrapping_mul(inverse32(FMIX32_M2));
+    h = unshift32(h, 13);
+    h = h.wrapping_mul(inverse32(FMIX32_M1));
+    unshift32(h, 16)
+}
+
+/// Murmur3's 64-bit finaliser.
+pub fn fmix64(mut k: u64) -> u64 {
+    k ^= k >> 33;
+    k = k.wrapping_mul(FMIX64_M1);
+    k ^= k >> 33;
+    k = k.wrapping_mul(FMIX64_M2);
+    k ^ (k >> 33)
+}
+
+/// The inverse of [`fmix64`].
+pub fn unfmix64(mut k: u64) -> u64 {
+    k = unshift64(k, 33);
+    k = k.wrapping_mul(inverse64(FMIX64_M2));
+    k = unshift64(k, 33);
+    k = k.wrapping_mul(inverse64(FMIX64_M1));
+    unshift64(k, 33)
+}
+
+const XXH32_P2: u32 = 0x85ebca77;
+const XXH32_P3: u32 = 0xc2b2ae3d;
+const XXH32_P4: u32 = 0x27d4eb2f;
+const XXH32_P5: u32 = 0x165667b1;
+const XXH64_P1: u64 = 0x9e3779b185ebca87;
+const XXH64_P2: u64 = 0xc2b2ae3d27d4eb4f;
+const XXH64_P3: u64 = 0x165667b19e3779f9;
+const XXH64_P4: u64 = 0x85ebca77c2b2ae63;
+const XXH64_P5: u64 = 0x27d4eb2f165667c5;
+
+/// XXH32's final avalanche.
+pub fn xxh32_avalanche(mut h: u32) -> u32 {
+    h ^= h >> 15;
+    h = h.wrapping_mul(XXH32_P2);
+    h ^= h >> 13;
+    h = h.wrapping_mul(XXH32_P3);
+    h ^ (h >> 16)
+}
+
+/// The inverse of [`xxh32_avalanche`].
+pub fn unxxh32_avalanche(mut h: u32) -> u32 {
+    h = unshift32(h, 16);
+    h = h.wrapping_mul(inverse32(XXH32_P3));
+    h = unshift32(h, 13);
+    h = h.wrapping_mul(inverse32(XXH32_P2));
+    unshift32(h, 15)
+}
+
+/// XXH64's final avalanche.
+pub fn xxh64_avalanche(mut h: u64) -> u64 {
+    h ^= h >> 33;
+    h = h.wrapping_mul(XXH64_P2);
+    h ^= h >> 29;
+    h = h.wrapping_mul(XXH64_P3);
+    h ^ (h >> 32)
+}
+
+/// The inverse of [`xxh64_avalanche`].
+pub fn unxxh64_avalanche(mut h: u64) -> u64 {
+    h = unshift64(h, 32);
+    h = h.wrapping_mul(inverse64(XXH64_P3));
+    h = unshift64(h, 29);
+    h = h.wrapping_mul(inverse64(XXH64_P2));
+    unshift64(h, 33)
+}
+
+/// The 4-byte input `Murmur3_32` hashes to `hash` with `seed`.
+pub fn murmur3_32_preimage(hash: u32, seed: u32) -> [u8; 4] {
+    const C1: u32 = 0xcc9e2d51;
+    const C2: u32 = 0x1b873593;
+    let mut h = unfmix32(hash) ^ 4;
+    h = h.wrapping_sub(0xe6546b64).wrapping_mul(inverse32(5));
+    h = h.rotate_right(13);
+    let mut k = h ^ seed;
+    k = k.wrapping_mul(inverse32(C2));
+    k = k.rotate_right(15);
+    k = k.wrapping_mul(inverse32(C1));
+    k.to_le_bytes()
+}
+
+/// The 16-byte input `Murmur3_x64_128` hashes to `hash` with `seed`, where
+/// `hash` is the second half followed by the first, as the plugin returns it.
+pub fn murmur3_x64_128_preimage(hash: u128, seed: u32) -> [u8; 16] {
+    const C1: u64 = 0x87c37b91114253d5;
+    const C2: u64 = 0x4cf5ad432745937f;
+    let seed = u64::from(seed);
+    let (mut h1, mut h2) = (hash as u64, (hash >> 64) as u64);
+    h2 = h2.wrapping_sub(h1);
+    h1 = h1.wrapping_sub(h2);
+    h1 = unfmix64(h1);
+    h2 = unfmix64(h2);
+    h2 = h2.wrapping_sub(h1);
+    h1 = h1.wrapping_sub(h2);
+    h1 ^= 16;
+    h2 ^= 16;
+
+    h2 = h2.wrapping_sub(0x38495ab5).wrapping_mul(inverse64(5));
+    h2 = h2.wrapping_sub(h1).rotate_right(31);
+    let mut k2 = h2 ^ seed;
+    k2 = k2.wrapping_mul(inverse64(C1)).rotate_right(33);
+    k2 = k2.wrapping_mul(inverse64(C2));
+
+    h1 = h1.wrapping_sub(0x52dce729).wrapping_mul(inverse64(5));
+    h1 = h1.wrapping_sub(seed).rotate_right(27);
+    let mut k1 = h1 ^ seed;
+    k1 = k1.wrapping_mul(inverse64(C2)).rotate_right(31);
+    k1 = k1.wrapping_mul(inverse64(C1));
+
+    let mut input = [0u8; 16];
+    input[..8].copy_from_slice(&k1.to_le_bytes());
+    input[8..].copy_from_slice(&k2.to_le_bytes());
+    input
+}
+
+/// The 4-byte input `XXH32` hashes to `hash` with `seed`.
+pub fn xxh32_preimage(hash: u32, seed: u32) -> [u8; 4] {
+    let mut h = unxxh32_avalanche(hash);
+    h = h.wrapping_mul(inverse32(XXH32_P4)).rotate_right(17);
+    let start = seed.wrapping_add(XXH32_P5).wrapping_add(4);
+    let k = h.wrapping_sub(start).wrapping_mul(inverse32(XXH32_P3));
+    k.to_le_bytes()
+}
+
+/// The 8-byte input `XXH64` hashes to `hash` with `seed`.
+pub fn xxh64_preimage(hash: u64, seed: u64) -> [u8; 8] {
+    let mut h = unxxh64_avalanche(hash);
+    h = h.wrapping_sub(XXH64_P4).wrapping_mul(inverse64(XXH64_P1));
+    h = h.rotate_right(27);
+    let round = h ^ seed.wrapping_add(XXH64_P5).wrapping_add(8);
+    let k = round.wrapping_mul(inverse64(XXH64_P1)).rotate_right(31);
+    let k = k.wrapping_mul(inverse64(XXH64_P2));
+    k.to_le_bytes()
+}
+
+/// A hasher whose hashes of one input length can be inverted.
+pub struct Invertible {
+    /// The name the hasher is registered under.
+    pub hasher: &'static str,
+    /// The input length preimages are found for. Each hasher is a bijection
+    /// on inputs of this length, so it is also the length of the hash.
+    pub input_len: usize,
+    /// The width of the hasher's seed, in bits.
+    seed_bits: u32,
+    invert: fn(&[u8], u64) -> Vec<u8>,
+}
+
+/// Every hasher [`preimage`] can invert.
+pub const INVERTIBLE: &[Invertible] = &[
+    Invertible {
+        hasher: "Murmur3_32",
+        input_len: 4,
+        seed_bits: 32,
+        invert: |hash, seed| {
+            murmur3_32_preimage(u32::from_be_bytes(read(hash)), seed as u32).to_vec()
+        },
+    },
+    Invertible {
+        hasher: "Murmur3_x64_128",
+        input_len: 16,
+        seed_bits: 32,
+        invert: |hash, seed| {
+            murmur3_x64_128_preimage(u128::from_be_bytes(read(hash)), seed as u32).to_vec()
+        },
+    },
+    Invertible {
+        hasher: "XXH32",
+        input_len: 4,
+        seed_bits: 32,
+        invert: |hash, seed| xxh32_preimage(u32::from_be_bytes(read(hash)), seed as u32).to_vec(),
+    },
+    Invertible {
+        hasher: "XXH64",
+        input_len: 8,
+        seed_bits: 64,
+        invert: |hash, seed| xxh64_preimage(u64::from_be_bytes(read(hash)), seed).to_vec(),
+    },
+];
+
+/// The bytes of a hash, which [`Invertible::preimage`] has checked the length of.
+fn read<const N: usize>(hash: &[u8]) -> [u8; N] {
+    let mut bytes = [0u8; N];
+    bytes.copy_from_slice(hash);
+    bytes
+}
+
+/// Looks up an invertible hasher by name.
+pub fn find(hasher: &str) -> Option<&'static Invertible> {
+    INVERTIBLE
+        .iter()
+        .find(|invertible| invertible.hasher == hasher)
+}
+
+/// Errors returned by [`preimage`].
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum InvertError {
+    /// The hasher isn't one of [`INVERTIBLE`].
+    Unsupported(String),
+    /// The hash isn't as long as the hasher's output.
+    HashLength { expected: usize, actual: usize },
+    /// The seed is wider than the hasher takes.
+    SeedTooWide(u64),
+}
+
+impl std::fmt::Display for InvertError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Unsupported(name) => write!(f, "{name} can't be inverted"),
+            Self::HashLength { expected, actual } => {
+                write!(f, "The hash must be {expected} bytes, got {actual}")
+            }
+            Self::SeedTooWide(seed) => write!(f, "Seed {seed} does not fit in 32 bits"),
+        }
+    }
+}
+
+impl std::error::Error for InvertError {}
+
+impl Invertible {
+    /// The input of [`Self::input_len`] bytes that hashes to `hash` with `seed`.
+    pub fn preimage(&self, hash: &[u8], seed: u64) -> Result<Vec<u8>, InvertError> {
+        if hash.len() != self.input_len {
+            return Err(InvertError::HashLength {
+                expected: self.input_len,
+                actual: hash.len(),
+            });
+        }
+        if self.seed_bits < 64 && seed >> self.seed_bits != 0 {
+            return Err(InvertError::SeedTooWide(seed));
+        }
+        Ok((self.invert)(hash, seed))
+    }
+}
+
+/// The input of the invertible length that `hasher` hashes to `hash` with
+/// `seed`, with `hash` as the hasher returns it.
+///
+/// # Examples
+/// ```
+/// use atlas::invert::preimage;
+///
+/// // Murmur3_32 of the little-endian bytes of 1234, with seed 0.
+/// let input = preimage("Murmur3_32", &[0x6b, 0xb6, 0x53, 0x80], 0)?;
+/// assert_eq!(input, 1234u32.to_le_bytes());
+/// # Ok::<(), atlas::invert::InvertError>(())
+/// ```
+pub fn preimage(hasher: &str, hash: &[u8], seed: u64) -> Result<Vec<u8>, InvertError> {
+    find(hasher)
+        .ok_or_else(|| InvertError::Unsupported(hasher.to_owned()))?
+        .preimage(hash, seed)
+}
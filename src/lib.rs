@@ -1,4 +1,22 @@
+//! Atlas is a tool for RE work regarding hashed values.
+//!
+//! Hashing functions are provided by plugins; the [`Registry`] loads them and
+//! calls into them, and [`Presets`] bundle a hasher with the quirks an engine
+//! wraps it in. The egui frontend lives behind the `gui` feature.
+
 #![warn(clippy::all, rust_2018_idioms)]
 
+#[cfg(feature = "gui")]
 mod app;
+pub mod collide;
+pub mod forge;
+pub mod invert;
+pub mod preset;
+pub mod registry;
+pub mod search;
+
+#[cfg(feature = "gui")]
 pub use app::AtlasApp;
+pub use atlas_common::{ErrorCode, HashFlags, HashResult};
+pub use preset::{ByteOrder, Case, Encoding, Preset, Presets};
+pub use registry::{HashError, HashOptions, Hasher, Parameter, Registry};
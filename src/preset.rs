@@ -0,0 +1,355 @@
+//! Hashers bundled with the quirks an engine wraps them in.
+//!
+//! Engines rarely hash names as written: Wwise lowercases them before FNV-1,
+//! Unreal uppercases them and hashes UTF-16. A [`Preset`] names a hasher, the
+//! options it is called with, how input text is normalised and the byte order
+//! the hash is shown in. [`Presets`] holds the built-in catalogue along with
+//! user presets, which are saved to disk as RON.
+
+use std::collections::BTreeMap;
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+
+use serde::{Deserialize, Serialize};
+
+use crate::search::engine::Job;
+use crate::search::{Affixes, Keyspace, Targets};
+use crate::{HashError, HashOptions, HashResult, Registry};
+
+/// How the letters of the input are cased before hashing.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub enum Case {
+    #[default]
+    Unchanged,
+    Lower,
+    Upper,
+}
+
+/// How the input text is turned into bytes.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub enum Encoding {
+    #[default]
+    Utf8,
+    /// Two bytes per UTF-16 code unit, as hashed by engines built on `wchar_t`.
+    Utf16Le,
+}
+
+impl Encoding {
+    /// The text `bytes` encode, with anything invalid replaced.
+    pub fn decode(self, bytes: &[u8]) -> String {
+        match self {
+            Self::Utf8 => String::from_utf8_lossy(bytes).into_owned(),
+            Self::Utf16Le => {
+                let units: Vec<u16> = bytes
+                    .chunks(2)
+                    .map(|unit| u16::from_le_bytes([unit[0], unit.get(1).copied().unwrap_or(0)]))
+                    .collect();
+                String::from_utf16_lossy(&units)
+            }
+        }
+    }
+}
+
+/// The byte order a hash is shown in.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub enum ByteOrder {
+    /// The order the hasher returns, the integer as written.
+    #[default]
+    Big,
+    /// Reversed, the bytes as a little-endian engine stores them.
+    Little,
+}
+
+/// A hasher, its options and the normalisation applied around it, under one name.
+///
+/// # Examples
+/// ```no_run
+/// use atlas::{Case, Preset, Registry};
+///
+/// let mut registry = Registry::new();
+/// registry.load_dir(Registry::default_plugin_dir()?)?;
+///
+/// let preset = Preset {
+///     case: Case::Lower,
+///     ..Preset::new("Wwise", "FNV-1_32")
+/// };
+/// let id = preset.hash(&registry, "Play_Footstep")?;
+/// # Ok::<(), anyhow::Error>(())
+/// ```
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Preset {
+    pub name: String,
+    #[serde(default)]
+    pub description: String,
+    /// The name of the registered hasher.
+    pub hasher: String,
+    #[serde(default)]
+    pub seed: Option<u64>,
+    /// The key, for hashers flagged [`HashFlags::KEYED`](crate::HashFlags::KEYED).
+    #[serde(default)]
+    pub key: Option<Vec<u8>>,
+    /// The secret, for hashers flagged [`HashFlags::SECRET`](crate::HashFlags::SECRET).
+    #[serde(default)]
+    pub secret: Option<Vec<u8>>,
+    /// Values for the hasher's declared parameters, by name.
+    #[serde(default)]
+    pub params: BTreeMap<String, u64>,
+    #[serde(default)]
+    pub case: Case,
+    #[serde(default)]
+    pub encoding: Encoding,
+    #[serde(default)]
+    pub byte_order: ByteOrder,
+}
+
+impl Preset {
+    /// A preset calling `hasher` with default options on the input as given.
+    pub fn new(name: impl Into<String>, hasher: impl Into<String>) -> Self {
+        Self {
+            name: name.into(),
+            description: String::new(),
+            hasher: hasher.into(),
+            seed: None,
+            key: None,
+            secret: None,
+            params: BTreeMap::new(),
+            case: Case::default(),
+            encoding: Encoding::default(),
+            byte_order: ByteOrder::default(),
+        }
+    }
+
+    /// Sets the hasher's parameter `name` to `value`.
+    pub fn with_param(mut self, name: impl Into<String>, value: u64) -> Self {
+        self.params.insert(name.into(), value);
+        self
+    }
+
+    /// The bytes `text` is hashed as.
+    ///
+    /// # Examples
+    /// ```
+    /// use atlas::{Case, Encoding, Preset};
+    ///
+    /// let preset = Preset {
+    ///     case: Case::Upper,
+    ///     encoding: Encoding::Utf16Le,
+    ///     ..Preset::new("Example", "CRC-32/BZIP2")
+    /// };
+    /// assert_eq!(preset.normalise("None"), b"N\0O\0N\0E\0");
+    /// ```
+    pub fn normalise(&self, text: &str) -> Vec<u8> {
+        let text = match self.case {
+            Case::Unchanged => text.to_owned(),
+            Case::Lower => text.to_lowercase(),
+            Case::Upper => text.to_uppercase(),
+        };
+        match self.encoding {
+            Encoding::Utf8 => text.into_bytes(),
+            Encoding::Utf16Le => text.encode_utf16().flat_map(u16::to_le_bytes).collect(),
+        }
+    }
+
+    /// The options the hasher is called with.
+    pub fn options(&self) -> HashOptions {
+        HashOptions {
+            seed: self.seed,
+            key: self.key.clone(),
+            secret: self.secret.clone(),
+            params: self.params.clone(),
+        }
+    }
+
+    /// Hashes `text` the way the preset describes.
+    pub fn hash(&self, registry: &Registry, text: &str) -> Result<HashResult, HashError> {
+        let hash = registry.hash(&self.hasher, &self.normalise(text), &self.options())?;
+        Ok(match self.byte_order {
+            ByteOrder::Big => hash,
+            ByteOrder::Little => hash.flip_endian(),
+        })
+    }
+
+    /// Whether [`Self::normalise`] changes text at all.
+    pub fn normalises(&self) -> bool {
+        self.case != Case::Unchanged || self.encoding != Encoding::Utf8
+    }
+
+    /// A search for `text` between `prefix` and `suffix` that hashes the way
+    /// the preset describes to one of `targets`, shown in its byte order.
+    ///
+    /// Candidates are normalised like the affixes, so the inputs found are the
+    /// bytes hashed, which [`Encoding::decode`] turns back into text.
+    pub fn job(
+        &self,
+        keyspace: Arc<dyn Keyspace>,
+        targets: Targets,
+        prefix: &str,
+        suffix: &str,
+    ) -> Job {
+        let targets = match self.byte_order {
+            ByteOrder::Big => targets,
+            ByteOrder::Little => targets.map(|hash| Some(hash.iter().rev().copied().collect())),
+        };
+        let keyspace: Arc<dyn Keyspace> = if self.normalises() {
+            Arc::new(Normalised {
+                keyspace,
+                preset: self.clone(),
+            })
+        } else {
+            keyspace
+        };
+        Job {
+            options: self.options(),
+            affixes: Affixes {
+                prefix: self.normalise(prefix),
+                suffix: self.normalise(suffix),
+            },
+            targets: Arc::new(targets),
+            ..Job::new(self.hasher.clone(), Vec::new(), keyspace)
+        }
+    }
+}
+
+/// Candidates put through a preset's normalisation. It works on text, so
+/// bytes that aren't UTF-8 are replaced first.
+///
+/// The mask and token fast paths hash candidates as they are, so they don't
+/// apply here.
+struct Normalised {
+    keyspace: Arc<dyn Keyspace>,
+    preset: Preset,
+}
+
+impl Keyspace for Normalised {
+    fn size(&self) -> u64 {
+        self.keyspace.size()
+    }
+
+    fn candidate(&self, index: u64, input: &mut Vec<u8>) {
+        self.keyspace.candidate(index, input);
+        *input = self.preset.normalise(&String::from_utf8_lossy(input));
+    }
+}
+
+/// The presets shipped with Atlas.
+pub fn builtin_presets() -> Vec<Preset> {
+    vec![
+        Preset {
+            description: "Audiokinetic Wwise IDs, FNV-1 of the lowercased name".to_owned(),
+            case: Case::Lower,
+            ..Preset::new("Wwise", "FNV-1_32")
+        },
+        Preset {
+            description: "Source engine file and resource names, CRC-32 of the lowercased path"
+                .to_owned(),
+            case: Case::Lower,
+            ..Preset::new("Source", "CRC-32")
+        },
+        Preset {
+            description: "Unreal FName hashes, FCrc::StrCrc of the uppercased name".to_owned(),
+            case: Case::Upper,
+            encoding: Encoding::Utf16Le,
+            ..Preset::new("Unreal FName", "CRC-32/BZIP2")
+        },
+        Preset {
+            description: "Bungie Tiger engine tag names, FNV-1 as stored in the package files"
+                .to_owned(),
+            byte_order: ByteOrder::Little,
+            ..Preset::new("Bungie Tiger", "FNV-1_32")
+        },
+    ]
+}
+
+/// The built-in presets and the user's own.
+///
+/// A user preset with the same name as a built-in one hides it.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Presets {
+    builtin: Vec<Preset>,
+    user: Vec<Preset>,
+}
+
+impl Default for Presets {
+    fn default() -> Self {
+        Self {
+            builtin: builtin_presets(),
+            user: Vec::new(),
+        }
+    }
+}
+
+impl Presets {
+    /// The built-in presets, without any user presets.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// `presets.ron` next to the current executable.
+    pub fn default_path() -> anyhow::Result<PathBuf> {
+        let current_exe = std::env::current_exe()?;
+        let Some(exe_dir) = current_exe.parent() else {
+            return Err(anyhow::anyhow!("Failed to find parent of current exe"));
+        };
+        Ok(exe_dir.join("presets.ron"))
+    }
+
+    /// The built-in presets, with the user presets saved at `path`. A missing
+    /// file holds no presets.
+    pub fn load(path: impl AsRef<Path>) -> anyhow::Result<Self> {
+        let mut presets = Self::new();
+        match std::fs::read_to_string(path) {
+            Ok(text) => presets.user = ron::from_str(&text)?,
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => {}
+            Err(e) => return Err(e.into()),
+        }
+        Ok(presets)
+    }
+
+    /// Saves the user presets to `path`.
+    pub fn save(&self, path: impl AsRef<Path>) -> anyhow::Result<()> {
+        let text = ron::ser::to_string_pretty(&self.user, ron::ser::PrettyConfig::default())?;
+        std::fs::write(path, text)?;
+        Ok(())
+    }
+
+    /// Looks up a preset by name.
+    pub fn get(&self, name: &str) -> Option<&Preset> {
+        self.user
+            .iter()
+            .chain(&self.builtin)
+            .find(|preset| preset.name == name)
+    }
+
+    /// Every preset that isn't hidden, built-in ones first.
+    pub fn iter(&self) -> impl Iterator<Item = &Preset> {
+        let visible = |preset: &&Preset| {
+            self.get(&preset.name)
+                .is_some_and(|shown| std::ptr::eq(shown, *preset))
+        };
+        self.builtin.iter().chain(&self.user).filter(visible)
+    }
+
+    /// The user presets, in the order they were added.
+    pub fn user(&self) -> &[Preset] {
+        &self.user
+    }
+
+    /// Whether `name` is a user preset.
+    pub fn is_user(&self, name: &str) -> bool {
+        self.user.iter().any(|preset| preset.name == name)
+    }
+
+    /// Adds a user preset, replacing the one with the same name.
+    pub fn insert(&mut self, preset: Preset) {
+        match self.user.iter_mut().find(|user| user.name == preset.name) {
+            Some(user) => *user = preset,
+            None => self.user.push(preset),
+        }
+    }
+
+    /// Removes the user preset `name`, uncovering the built-in one it hid.
+    pub fn remove(&mut self, name: &str) -> Option<Preset> {
+        let index = self.user.iter().position(|preset| preset.name == name)?;
+        Some(self.user.remove(index))
+    }
+}
@@ -0,0 +1,716 @@
+//! Loading hashing plugins and calling into them.
+//!
+//! A [`Registry`] owns every plugin library it has loaded, along with the
+//! hashing functions those libraries registered. Hashing goes through
+//! [`Registry::hash`], which handles building the FFI arguments for the
+//! hasher's [`HashFlags`] and converting the result back.
+
+use libloading::Library;
+use log::{error, info};
+use serde::{Deserialize, Serialize};
+use std::cell::RefCell;
+use std::collections::BTreeMap;
+use std::path::{Path, PathBuf};
+
+use atlas_common::{
+    BatchHashFunction, BatchInput, BatchOutput, CHashOptions, ErrorCode, ErrorMessage, HashFlags,
+    HashFunction, HashParam, HashResult, OutputBuffer, RegisterHashersFunc, RegisterParamsFunc,
+    Slice,
+};
+
+/// The signature of the `register_hashers` function every plugin exports.
+pub type ExternalRegistration = RegisterHashersFunc;
+
+thread_local! {
+    /// Hashers registered by the plugin currently being loaded on this thread.
+    static PENDING: RefCell<Vec<(String, HashFlags, HashFunction)>> = const { RefCell::new(Vec::new()) };
+    /// Parameters declared by the plugin currently being loaded on this thread.
+    static PENDING_PARAMS: RefCell<Vec<(String, Parameter)>> = const { RefCell::new(Vec::new()) };
+}
+
+extern "C" fn register(name: *const std::ffi::c_char, flags: HashFlags, hasher: HashFunction) {
+    if name.is_null() {
+        return;
+    }
+    // SAFETY: plugins pass a pointer to a nul-terminated string that lives for the call.
+    let name = unsafe { std::ffi::CStr::from_ptr(name) };
+    let name = name.to_string_lossy().to_string();
+    PENDING.with_borrow_mut(|pending| pending.push((name, flags, hasher)));
+}
+
+extern "C" fn register_param(hasher: *const std::ffi::c_char, param: *const HashParam) {
+    if hasher.is_null() || param.is_null() || !param.is_aligned() {
+        return;
+    }
+    // SAFETY: plugins pass a pointer to a valid parameter that lives for the call.
+    let param = unsafe { &*param };
+    if param.name.is_null() {
+        return;
+    }
+    // SAFETY: plugins pass pointers to nul-terminated strings that live for the call.
+    let (hasher, name) = unsafe {
+        (
+            std::ffi::CStr::from_ptr(hasher),
+            std::ffi::CStr::from_ptr(param.name),
+        )
+    };
+    let parameter = Parameter {
+        name: name.to_string_lossy().to_string(),
+        min: param.min,
+        max: param.max,
+        step: param.step.max(1),
+        default: param.default_value,
+    };
+    let hasher = hasher.to_string_lossy().to_string();
+    PENDING_PARAMS.with_borrow_mut(|pending| pending.push((hasher, parameter)));
+}
+
+/// A numeric parameter declared by a hasher, such as a variant or an output size.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Parameter {
+    /// The name values are looked up by in [`HashOptions::params`].
+    pub name: String,
+    pub min: u64,
+    pub max: u64,
+    /// The distance between accepted values, starting from `min`.
+    pub step: u64,
+    /// The value used when [`HashOptions::params`] doesn't set one.
+    pub default: u64,
+}
+
+impl Parameter {
+    /// Whether `value` is one of the parameter's accepted values.
+    pub fn accepts(&self, value: u64) -> bool {
+        (self.min..=self.max).contains(&value) && (value - self.min) % self.step == 0
+    }
+}
+
+/// A registered hasher's entry point and what it accepts.
+struct Entry {
+    flags: HashFlags,
+    function: HashFunction,
+    /// The plugin's `hash_batch`, if it exports one.
+    batch: Option<BatchHashFunction>,
+    params: Vec<Parameter>,
+}
+
+impl Entry {
+    fn hasher<'a>(&'a self, name: &'a str) -> Hasher<'a> {
+        Hasher {
+            name,
+            flags: self.flags,
+            function: self.function,
+            batch: self.batch,
+            params: &self.params,
+        }
+    }
+}
+
+/// A hashing function registered by a plugin.
+#[derive(Clone, Copy)]
+pub struct Hasher<'a> {
+    name: &'a str,
+    flags: HashFlags,
+    function: HashFunction,
+    batch: Option<BatchHashFunction>,
+    params: &'a [Parameter],
+}
+
+impl<'a> Hasher<'a> {
+    /// The name the plugin registered the hasher under.
+    pub fn name(&self) -> &'a str {
+        self.name
+    }
+
+    /// The options the hasher accepts.
+    pub fn flags(&self) -> HashFlags {
+        self.flags
+    }
+
+    /// The parameters the hasher declared, in the order it reads them.
+    pub fn params(&self) -> &'a [Parameter] {
+        self.params
+    }
+
+    /// The raw FFI function, valid for as long as the owning [`Registry`] lives.
+    pub fn function(&self) -> HashFunction {
+        self.function
+    }
+
+    /// The plugin's raw batch function, if it exports one, valid for as long
+    /// as the owning [`Registry`] lives.
+    pub fn batch_function(&self) -> Option<BatchHashFunction> {
+        self.batch
+    }
+}
+
+/// Seeds, keys, secrets and parameters passed to a hasher.
+///
+/// Only the option matching the hasher's [`HashFlags`] is sent; unset options
+/// fall back to zero. Likewise only the parameters the hasher declared are
+/// sent, falling back to their defaults.
+#[derive(Clone, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct HashOptions {
+    /// Used by hashers flagged with [`HashFlags::SEEDED`].
+    pub seed: Option<u64>,
+    /// Used by hashers flagged with [`HashFlags::KEYED`].
+    pub key: Option<Vec<u8>>,
+    /// Used by hashers flagged with [`HashFlags::SECRET`].
+    pub secret: Option<Vec<u8>>,
+    /// Values for the hasher's declared [`Parameter`]s, by name.
+    pub params: BTreeMap<String, u64>,
+}
+
+impl HashOptions {
+    /// Options with only a seed set.
+    pub fn seeded(seed: u64) -> Self {
+        Self {
+            seed: Some(seed),
+            ..Default::default()
+        }
+    }
+
+    /// Options with only a key set.
+    pub fn keyed(key: impl Into<Vec<u8>>) -> Self {
+        Self {
+            key: Some(key.into()),
+            ..Default::default()
+        }
+    }
+
+    /// Sets the parameter `name` to `value`.
+    pub fn with_param(mut self, name: impl Into<String>, value: u64) -> Self {
+        self.params.insert(name.into(), value);
+        self
+    }
+}
+
+/// Errors returned by [`Registry::hash`].
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum HashError {
+    /// No hasher is registered under the requested name.
+    UnknownHasher(String),
+    /// The plugin returned a non-zero status code, and optionally a message describing it.
+    Plugin { code: i32, message: Option<String> },
+    /// The plugin reported success but didn't write a valid hash.
+    NullResult,
+    /// A parameter value isn't one the hasher declared.
+    InvalidParameter { name: String, value: u64 },
+    /// The plugin asked for an output buffer larger than [`MAX_OUTPUT_LEN`].
+    OutputTooLarge(usize),
+    /// A batch hashed to `len` bytes after hashes of `width` bytes, where
+    /// every hash in a batch must be as wide.
+    MixedWidth { width: usize, len: usize },
+}
+
+impl std::fmt::Display for HashError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::UnknownHasher(name) => write!(f, "Could not find the hash function {name:?}"),
+            Self::Plugin {
+                message: Some(message),
+                ..
+            } => f.write_str(message),
+            Self::Plugin {
+                code,
+                message: None,
+            } => match ErrorCode::from_i32(*code) {
+                Some(code) => f.write_str(code.message()),
+                None => write!(f, "Unknown error code {code}"),
+            },
+            Self::NullResult => write!(f, "Result returned null"),
+            Self::InvalidParameter { name, value } => {
+                write!(f, "{value} is not a valid value for the {name} parameter")
+            }
+            Self::OutputTooLarge(len) => write!(
+                f,
+                "The hasher asked for {len} bytes of output, more than the {MAX_OUTPUT_LEN} allowed"
+            ),
+            Self::MixedWidth { width, len } => write!(
+                f,
+                "Hashed {len} bytes after {width} bytes, batches need one width"
+            ),
+        }
+    }
+}
+
+impl std::error::Error for HashError {}
+
+impl HashError {
+    /// The plugin's status code, if this error came from a plugin and the code is
+    /// a known one. Parameters the host rejects map to [`ErrorCode::InvalidParameter`],
+    /// as the plugin would have reported.
+    pub fn error_code(&self) -> Option<ErrorCode> {
+        match self {
+            Self::Plugin { code, .. } => ErrorCode::from_i32(*code),
+            Self::InvalidParameter { .. } => Some(ErrorCode::InvalidParameter),
+            _ => None,
+        }
+    }
+}
+
+/// A set of loaded plugins and the hashers they registered.
+///
+/// Hashers are keyed by name; when two plugins register the same name, the
+/// one loaded last wins.
+///
+/// # Examples
+/// ```no_run
+/// use atlas::{HashOptions, Registry};
+///
+/// let mut registry = Registry::new();
+/// registry.load_dir(Registry::default_plugin_dir()?)?;
+///
+/// let hash = registry.hash("Murmur3_32", b"hello", &HashOptions::seeded(0))?;
+/// println!("0x{}", hash.0);
+/// # Ok::<(), anyhow::Error>(())
+/// ```
+#[derive(Default)]
+pub struct Registry {
+    // NOTE: `hashers` must be declared before `libraries`, so the function
+    // pointers are dropped before the code they point into is unloaded.
+    hashers: BTreeMap<String, Entry>,
+    libraries: Vec<Library>,
+}
+
+impl Registry {
+    /// Creates an empty registry.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// The `plugins` folder next to the current executable.
+    pub fn default_plugin_dir() -> anyhow::Result<PathBuf> {
+        let current_exe = std::env::current_exe()?;
+        let Some(exe_dir) = current_exe.parent() else {
+            return Err(anyhow::anyhow!("Failed to find parent of current exe"));
+        };
+        Ok(exe_dir.join("plugins"))
+    }
+
+    /// Loads every dynamic library in `dir`, returning how many were loaded.
+    pub fn load_dir(&mut self, dir: impl AsRef<Path>) -> anyhow::Result<usize> {
+        let mut loaded = 0;
+        for entry in std::fs::read_dir(dir)? {
+            let path = entry?.path();
+            if path
+                .extension()
+                .is_some_and(|x| matches!(x.to_str(), Some("dll" | "so" | "dylib")))
+            {
+                self.load_library(&path)?;
+                loaded += 1;
+            }
+        }
+        Ok(loaded)
+    }
+
+    /// Loads a single plugin library and registers its hashers.
+    pub fn load_library(&mut self, path: impl AsRef<Path>) -> anyhow::Result<()> {
+        // SAFETY: loading a plugin runs its initialisers; plugins are trusted.
+        let lib = unsafe { Library::new(path.as_ref())? };
+        // SAFETY: every plugin exports `register_hashers` with this signature.
+        let fn_register = unsafe { *lib.get::<ExternalRegistration>(b"register_hashers")? };
+
+        // SAFETY: plugins that export `register_params` do so with this signature.
+        let fn_register_params = unsafe { lib.get::<RegisterParamsFunc>(b"register_params") }
+            .ok()
+            .map(|symbol| *symbol);
+        // SAFETY: plugins that export `hash_batch` do so with this signature.
+        let batch = unsafe { lib.get::<BatchHashFunction>(b"hash_batch") }
+            .ok()
+            .map(|symbol| *symbol);
+
+        PENDING.with_borrow_mut(Vec::clear);
+        PENDING_PARAMS.with_borrow_mut(Vec::clear);
+        fn_register(register);
+        if let Some(fn_register_params) = fn_register_params {
+            fn_register_params(register_param);
+        }
+        let hashers = PENDING.take();
+        let mut params = PENDING_PARAMS.take();
+        params.retain(|(hasher, param)| {
+            let registered = hashers.iter().any(|(name, ..)| name == hasher);
+            if !registered {
+                error!(
+                    "Ignoring the {} parameter of {hasher}, which the plugin didn't register",
+                    param.name
+                );
+            }
+            registered
+        });
+        for (name, flags, function) in hashers {
+            info!("Registered plugin {name}");
+            let params = params
+                .iter()
+                .filter(|(hasher, _)| *hasher == name)
+                .map(|(_, param)| param.clone())
+                .collect();
+            let entry = Entry {
+                flags,
+                function,
+                batch,
+                params,
+            };
+            self.hashers.insert(name, entry);
+        }
+        self.libraries.push(lib);
+        Ok(())
+    }
+
+    /// Unloads every plugin.
+    pub fn clear(&mut self) {
+        self.hashers.clear();
+        self.libraries.clear();
+    }
+
+    /// Looks up a hasher by name.
+    pub fn get(&self, name: &str) -> Option<Hasher<'_>> {
+        self.hashers
+            .get_key_value(name)
+            .map(|(name, entry)| entry.hasher(name))
+    }
+
+    /// Every registered hasher, sorted by name.
+    pub fn hashers(&self) -> impl Iterator<Item = Hasher<'_>> {
+        self.hashers.iter().map(|(name, entry)| entry.hasher(name))
+    }
+
+    /// Hashes `input` with the hasher registered under `name`.
+    pub fn hash(
+        &self,
+        name: &str,
+        input: &[u8],
+        options: &HashOptions,
+    ) -> Result<HashResult, HashError> {
+        let hasher = self
+            .get(name)
+            .ok_or_else(|| HashError::UnknownHasher(name.to_owned()))?;
+        let prepared = Prepared::new(hasher, options)?;
+        let c_options = prepared.c_options();
+
+        let input = Slice::from_ref(input);
+        let mut output = vec![0u8; DEFAULT_OUTPUT_CAPACITY];
+        let mut ret = call_hasher(hasher.function(), &input, &c_options, &mut output);
+        if let Err(HashError::Plugin { code, .. }) = ret {
+            if code == ErrorCode::OutputTooSmall as i32 && output.len() > DEFAULT_OUTPUT_CAPACITY {
+                // `output` was resized to what the hasher asked for, try again.
+                ret = call_hasher(hasher.function(), &input, &c_options, &mut output);
+            }
+        }
+        if let Err(error) = &ret {
+            if error.error_code() == Some(ErrorCode::InternalPanic) {
+                error!("{name}: {error}");
+            }
+        }
+        ret.map(|()| HashResult::from(output))
+    }
+
+    /// Hashes every input of `batch` with the hasher registered under `name`.
+    ///
+    /// Plugins that export `hash_batch` hash the whole batch in one call, the
+    /// rest are called once per input. Either way the hashes end up in
+    /// `batch`, and an error stops at the first input that failed. Every hash
+    /// in a batch must have the same width.
+    ///
+    /// # Examples
+    /// ```no_run
+    /// use atlas::registry::Batch;
+    /// use atlas::{HashOptions, Registry};
+    ///
+    /// let mut registry = Registry::new();
+    /// registry.load_dir(Registry::default_plugin_dir()?)?;
+    ///
+    /// let mut batch = Batch::new();
+    /// for name in ["idle", "walk", "run"] {
+    ///     batch.push(&[name.as_bytes()]);
+    /// }
+    /// registry.hash_batch("FNV-1a_32", &mut batch, &HashOptions::default())?;
+    /// for i in 0..batch.len() {
+    ///     println!("{}: {}", String::from_utf8_lossy(batch.input(i)), hex::encode(batch.hash(i)));
+    /// }
+    /// # Ok::<(), anyhow::Error>(())
+    /// ```
+    pub fn hash_batch(
+        &self,
+        name: &str,
+        batch: &mut Batch,
+        options: &HashOptions,
+    ) -> Result<(), HashError> {
+        let hasher = self
+            .get(name)
+            .ok_or_else(|| HashError::UnknownHasher(name.to_owned()))?;
+        let Some(function) = hasher.batch_function() else {
+            return self.hash_each(name, batch, options);
+        };
+        let prepared = Prepared::new(hasher, options)?;
+        let c_options = prepared.c_options();
+
+        // The width of the last batch is likely right, otherwise the hasher
+        // says what it should be and this tries again.
+        let mut ret = call_batch(function, hasher.function(), &c_options, batch);
+        if let Err(HashError::Plugin { code, .. }) = ret {
+            if code == ErrorCode::OutputTooSmall as i32 {
+                ret = call_batch(function, hasher.function(), &c_options, batch);
+            }
+        }
+        if let Err(error) = &ret {
+            if error.error_code() == Some(ErrorCode::InternalPanic) {
+                error!("{name}: {error}");
+            }
+        }
+        ret
+    }
+
+    /// Hashes the inputs of `batch` one call at a time.
+    fn hash_each(
+        &self,
+        name: &str,
+        batch: &mut Batch,
+        options: &HashOptions,
+    ) -> Result<(), HashError> {
+        batch.hashes.clear();
+        for i in 0..batch.len() {
+            let hash = self.hash(name, batch.input(i), options)?;
+            let hash = hex::decode(hash.0).map_err(|_err| HashError::NullResult)?;
+            if i == 0 {
+                batch.width = hash.len();
+            } else if hash.len() != batch.width {
+                return Err(HashError::MixedWidth {
+                    width: batch.width,
+                    len: hash.len(),
+                });
+            }
+            batch.hashes.extend(hash);
+        }
+        Ok(())
+    }
+}
+
+/// The FFI options for one hasher, owning what [`CHashOptions`] points into.
+struct Prepared<'a> {
+    flags: HashFlags,
+    seed: u64,
+    key: Slice<u8>,
+    secret: Slice<u8>,
+    params: Vec<u64>,
+    _options: std::marker::PhantomData<&'a HashOptions>,
+}
+
+impl<'a> Prepared<'a> {
+    /// Checks the parameters in `options` against those `hasher` declared,
+    /// falling back to their defaults.
+    fn new(hasher: Hasher<'_>, options: &'a HashOptions) -> Result<Self, HashError> {
+        let params = hasher
+            .params()
+            .iter()
+            .map(|param| {
+                let value = options.params.get(&param.name).copied();
+                let value = value.unwrap_or(param.default);
+                if param.accepts(value) {
+                    Ok(value)
+                } else {
+                    Err(HashError::InvalidParameter {
+                        name: param.name.clone(),
+                        value,
+                    })
+                }
+            })
+            .collect::<Result<Vec<_>, _>>()?;
+        Ok(Self {
+            flags: hasher.flags(),
+            seed: options.seed.unwrap_or(0),
+            key: Slice::from_ref(options.key.as_deref().unwrap_or_default()),
+            secret: Slice::from_ref(options.secret.as_deref().unwrap_or_default()),
+            params,
+            _options: std::marker::PhantomData,
+        })
+    }
+
+    /// The options to pass, pointing into `self`, so only valid while it is.
+    fn c_options(&self) -> CHashOptions {
+        let mut c_options = CHashOptions {
+            data: std::ptr::null(),
+            params: Slice::from_ref(&self.params),
+        };
+        if self.flags.contains(HashFlags::SEEDED) {
+            c_options.data = std::ptr::from_ref(&self.seed).cast();
+        } else if self.flags.contains(HashFlags::KEYED) {
+            c_options.data = std::ptr::from_ref(&self.key).cast();
+        } else if self.flags.contains(HashFlags::SECRET) {
+            c_options.data = std::ptr::from_ref(&self.secret).cast();
+        }
+        c_options
+    }
+}
+
+/// Inputs packed together to hash in one call, and their hashes once hashed.
+///
+/// A batch can be cleared and refilled to reuse its buffers.
+#[derive(Clone, Debug)]
+pub struct Batch {
+    data: Vec<u8>,
+    /// Where each input starts in `data`, then where the last one ends.
+    offsets: Vec<usize>,
+    hashes: Vec<u8>,
+    width: usize,
+}
+
+impl Default for Batch {
+    fn default() -> Self {
+        Self {
+            data: Vec::new(),
+            offsets: vec![0],
+            hashes: Vec::new(),
+            width: 0,
+        }
+    }
+}
+
+impl Batch {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Removes every input, keeping the buffers.
+    pub fn clear(&mut self) {
+        self.data.clear();
+        self.offsets.truncate(1);
+        self.hashes.clear();
+    }
+
+    /// Adds an input made of `parts` one after the other.
+    pub fn push(&mut self, parts: &[&[u8]]) {
+        for part in parts {
+            self.data.extend_from_slice(part);
+        }
+        self.offsets.push(self.data.len());
+    }
+
+    pub fn len(&self) -> usize {
+        self.offsets.len() - 1
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    pub fn input(&self, i: usize) -> &[u8] {
+        &self.data[self.offsets[i]..self.offsets[i + 1]]
+    }
+
+    /// The hash of input `i`, once the batch is hashed.
+    pub fn hash(&self, i: usize) -> &[u8] {
+        self.hashes
+            .get(i * self.width..(i + 1) * self.width)
+            .unwrap_or_default()
+    }
+}
+
+/// The output buffer size tried first, enough for any hash up to 1024 bits.
+const DEFAULT_OUTPUT_CAPACITY: usize = 128;
+
+/// The largest output a plugin may ask for, so a buggy one can't make the
+/// host allocate without bound.
+pub const MAX_OUTPUT_LEN: usize = 1 << 20;
+
+/// Calls `function` once, truncating `output` to the hash on success, or
+/// resizing it to the size the hasher asked for on [`ErrorCode::OutputTooSmall`].
+fn call_hasher(
+    function: HashFunction,
+    input: &Slice<u8>,
+    options: &CHashOptions,
+    output: &mut Vec<u8>,
+) -> Result<(), HashError> {
+    let mut output_buffer = OutputBuffer::new(output);
+    let mut message_buffer = [0u8; 256];
+    let mut message = ErrorMessage::new(&mut message_buffer);
+    // A plugin that unwinds out of the call aborts the process, as it is
+    // `extern "C"`, so there is nothing to catch here.
+    // SAFETY: every pointer is valid for the duration of the call
+    let ret = unsafe {
+        function(
+            std::ptr::from_ref(input),
+            std::ptr::from_ref(options),
+            std::ptr::from_mut(&mut output_buffer),
+            std::ptr::from_mut(&mut message),
+        )
+    };
+
+    let len = output_buffer.len;
+    if ret == 0 {
+        if len > output.len() {
+            return Err(HashError::NullResult);
+        }
+        output.truncate(len);
+        Ok(())
+    } else {
+        if ret == ErrorCode::OutputTooSmall as i32 {
+            if len > MAX_OUTPUT_LEN {
+                return Err(HashError::OutputTooLarge(len));
+            }
+            output.resize(len, 0);
+        }
+        Err(HashError::Plugin {
+            code: ret,
+            message: message.as_str().map(str::to_owned),
+        })
+    }
+}
+
+/// Calls `function` once for the whole batch, resizing the batch's hashes to
+/// the width the hasher asked for on [`ErrorCode::OutputTooSmall`].
+fn call_batch(
+    function: BatchHashFunction,
+    hasher: HashFunction,
+    options: &CHashOptions,
+    batch: &mut Batch,
+) -> Result<(), HashError> {
+    batch.hashes.resize(batch.len() * batch.width, 0);
+    let inputs = BatchInput {
+        data: Slice::from_ref(&batch.data),
+        offsets: Slice::from_ref(&batch.offsets),
+    };
+    let mut outputs = BatchOutput {
+        ptr: batch.hashes.as_mut_ptr(),
+        width: batch.width,
+        len: 0,
+    };
+    let mut message_buffer = [0u8; 256];
+    let mut message = ErrorMessage::new(&mut message_buffer);
+    // SAFETY: every pointer is valid for the duration of the call, and
+    // `hashes` holds `width` bytes per input.
+    let ret = unsafe {
+        function(
+            hasher,
+            &raw const inputs,
+            std::ptr::from_ref(options),
+            &raw mut outputs,
+            &raw mut message,
+        )
+    };
+
+    if ret == 0 {
+        if outputs.len != batch.len() {
+            return Err(HashError::NullResult);
+        }
+        Ok(())
+    } else {
+        if ret == ErrorCode::OutputTooSmall as i32 && outputs.width != batch.width {
+            // Hashes before this one fit, so no width fits them all.
+            if outputs.len > 0 {
+                return Err(HashError::MixedWidth {
+                    width: batch.width,
+                    len: outputs.width,
+                });
+            }
+            if outputs.width > MAX_OUTPUT_LEN {
+                return Err(HashError::OutputTooLarge(outputs.width));
+            }
+            batch.width = outputs.width;
+        }
+        Err(HashError::Plugin {
+            code: ret,
+            message: message.as_str().map(str::to_owned),
+        })
+    }
+}
@@ -0,0 +1,463 @@
+//! Sources of candidate inputs.
+
+use std::collections::BTreeMap;
+use std::path::Path;
+
+/// Candidates that can be looked up by index, so a search can split them
+/// between threads and record how far it got.
+pub trait Keyspace: Send + Sync {
+    /// How many candidates there are, at most `u64::MAX`.
+    fn size(&self) -> u64;
+
+    /// Replaces `input` with candidate `index`, which is less than [`Self::size`].
+    fn candidate(&self, index: u64, input: &mut Vec<u8>);
+
+    /// The mask these candidates are, if they are one, so a search can hash
+    /// them with a [`Kernel`](super::kernel::Kernel).
+    fn mask(&self) -> Option<&Mask> {
+        None
+    }
+
+    /// The combinations of tokens these candidates are, if they are some, so
+    /// a search can hash each token once for all the candidates it starts.
+    fn combinator(&self) -> Option<&Combinator> {
+        None
+    }
+}
+
+/// Every string of `min_len` to `max_len` bytes from a charset, shortest first.
+///
+/// # Examples
+/// ```
+/// use atlas::search::BruteForce;
+///
+/// let candidates: Vec<_> = BruteForce::new(b"ab", 1, 2).collect();
+/// assert_eq!(candidates, [&b"a"[..], b"b", b"aa", b"ab", b"ba", b"bb"]);
+/// ```
+#[derive(Clone, Debug)]
+pub struct BruteForce {
+    charset: Vec<u8>,
+    min_len: usize,
+    max_len: usize,
+    /// The charset indices of the next candidate, or `None` once done.
+    indices: Option<Vec<usize>>,
+}
+
+impl BruteForce {
+    pub fn new(charset: &[u8], min_len: usize, max_len: usize) -> Self {
+        let mut charset = charset.to_vec();
+        charset.sort_unstable();
+        charset.dedup();
+        let done = charset.is_empty() && min_len > 0 || min_len > max_len;
+        Self {
+            charset,
+            min_len,
+            max_len,
+            indices: (!done).then(|| vec![0; min_len]),
+        }
+    }
+
+    /// How many candidates there are in total, if that fits in a `u128`.
+    pub fn len(&self) -> Option<u128> {
+        let Some(indices) = &self.indices else {
+            return Some(0);
+        };
+        let base = self.charset.len() as u128;
+        (indices.len()..=self.max_len).try_fold(0u128, |total, len| {
+            total.checked_add(base.checked_pow(u32::try_from(len).ok()?)?)
+        })
+    }
+
+    /// Whether there are no candidates.
+    pub fn is_empty(&self) -> bool {
+        self.indices.is_none()
+    }
+}
+
+impl Iterator for BruteForce {
+    type Item = Vec<u8>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let indices = self.indices.as_mut()?;
+        let candidate = indices.iter().map(|i| self.charset[*i]).collect();
+
+        // Advance like an odometer, the last byte fastest, then grow.
+        let mut carried = true;
+        for index in indices.iter_mut().rev() {
+            *index += 1;
+            if *index < self.charset.len() {
+                carried = false;
+                break;
+            }
+            *index = 0;
+        }
+        if carried {
+            if indices.len() < self.max_len && !self.charset.is_empty() {
+                indices.push(0);
+            } else {
+                self.indices = None;
+            }
+        }
+        Some(candidate)
+    }
+}
+
+/// Candidates in the order they're iterated, from the first.
+impl Keyspace for BruteForce {
+    fn size(&self) -> u64 {
+        let base = self.charset.len() as u128;
+        let size = (self.min_len..=self.max_len).try_fold(0u128, |total, len| {
+            total.checked_add(base.checked_pow(u32::try_from(len).ok()?)?)
+        });
+        size.map_or(u64::MAX, |size| u64::try_from(size).unwrap_or(u64::MAX))
+    }
+
+    fn candidate(&self, mut index: u64, input: &mut Vec<u8>) {
+        let base = self.charset.len() as u64;
+        let mut len = self.min_len;
+        // Skip the shorter lengths; a count that overflows is past any index.
+        while let Some(count) = u32::try_from(len)
+            .ok()
+            .and_then(|len| base.checked_pow(len))
+        {
+            if index < count {
+                break;
+            }
+            index -= count;
+            len += 1;
+        }
+        input.clear();
+        input.resize(len, 0);
+        for byte in input.iter_mut().rev() {
+            *byte = self.charset[(index % base) as usize];
+            index /= base;
+        }
+    }
+}
+
+/// A list of words, one candidate each.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct Dictionary {
+    pub words: Vec<Vec<u8>>,
+}
+
+impl Dictionary {
+    /// One word per line, ignoring blank lines and trailing whitespace.
+    pub fn parse(text: &str) -> Self {
+        let words = text
+            .lines()
+            .map(str::trim_end)
+            .filter(|line| !line.is_empty())
+            .map(|line| line.as_bytes().to_vec())
+            .collect();
+        Self { words }
+    }
+
+    /// Reads a word list, one word per line.
+    pub fn load(path: impl AsRef<Path>) -> anyhow::Result<Self> {
+        Ok(Self::parse(&std::fs::read_to_string(path)?))
+    }
+
+    pub fn iter(&self) -> impl Iterator<Item = Vec<u8>> + '_ {
+        self.words.iter().cloned()
+    }
+}
+
+impl Keyspace for Dictionary {
+    fn size(&self) -> u64 {
+        self.words.len() as u64
+    }
+
+    fn candidate(&self, index: u64, input: &mut Vec<u8>) {
+        input.clear();
+        input.extend_from_slice(&self.words[index as usize]);
+    }
+}
+
+/// Candidates of a fixed length, each position from its own charset, with the
+/// last position changing fastest.
+///
+/// # Examples
+/// ```
+/// use atlas::search::{Keyspace as _, Mask};
+///
+/// let mask = Mask::parse("id?d?d")?;
+/// assert_eq!(mask.size(), 100);
+/// let mut input = Vec::new();
+/// mask.candidate(42, &mut input);
+/// assert_eq!(input, b"id42");
+/// # Ok::<(), anyhow::Error>(())
+/// ```
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct Mask {
+    positions: Vec<Vec<u8>>,
+}
+
+impl Mask {
+    /// A mask with the given charsets, which are sorted and deduplicated.
+    pub fn new(positions: impl IntoIterator<Item = Vec<u8>>) -> Self {
+        let positions = positions
+            .into_iter()
+            .map(|mut charset| {
+                charset.sort_unstable();
+                charset.dedup();
+                charset
+            })
+            .collect();
+        Self { positions }
+    }
+
+    /// Reads a mask in hashcat's notation: `?l`, `?u`, `?d`, `?h`, `?H`, `?s`
+    /// and `?a` stand for their charsets, `??` for a `?`, and anything else
+    /// for itself.
+    pub fn parse(pattern: &str) -> anyhow::Result<Self> {
+        const SPECIAL: &[u8] = b" !\"#$%&'()*+,-./:;<=>?@[\\]^_`{|}~";
+        let mut positions = Vec::new();
+        let mut bytes = pattern.bytes();
+        while let Some(byte) = bytes.next() {
+            if byte != b'?' {
+                positions.push(vec![byte]);
+                continue;
+            }
+            let charset = match bytes.next() {
+                Some(b'l') => (b'a'..=b'z').collect(),
+                Some(b'u') => (b'A'..=b'Z').collect(),
+                Some(b'd') => (b'0'..=b'9').collect(),
+                Some(b'h') => (b'0'..=b'9').chain(b'a'..=b'f').collect(),
+                Some(b'H') => (b'0'..=b'9').chain(b'A'..=b'F').collect(),
+                Some(b's') => SPECIAL.to_vec(),
+                Some(b'a') => (b' '..=b'~').collect(),
+                Some(b'?') => vec![b'?'],
+                Some(other) => anyhow::bail!("Unknown charset ?{}", other as char),
+                None => anyhow::bail!("The mask ends in a lone ?"),
+            };
+            positions.push(charset);
+        }
+        Ok(Self::new(positions))
+    }
+
+    /// The charset of each position.
+    pub fn positions(&self) -> &[Vec<u8>] {
+        &self.positions
+    }
+
+    /// The length of every candidate.
+    pub fn len(&self) -> usize {
+        self.positions.len()
+    }
+
+    /// Whether the only candidate is empty.
+    pub fn is_empty(&self) -> bool {
+        self.positions.is_empty()
+    }
+
+    /// The charset index of each position in candidate `index`.
+    pub(crate) fn digits(&self, mut index: u64, digits: &mut Vec<usize>) {
+        digits.clear();
+        digits.resize(self.positions.len(), 0);
+        for (digit, charset) in digits.iter_mut().zip(&self.positions).rev() {
+            let base = charset.len() as u64;
+            *digit = (index % base) as usize;
+            index /= base;
+        }
+    }
+
+    /// Moves `digits` on to the next candidate, back to the first after the
+    /// last, returning the first position that changed.
+    pub(crate) fn advance(&self, digits: &mut [usize]) -> usize {
+        for (position, (digit, charset)) in digits.iter_mut().zip(&self.positions).enumerate().rev()
+        {
+            *digit += 1;
+            if *digit < charset.len() {
+                return position;
+            }
+            *digit = 0;
+        }
+        0
+    }
+}
+
+impl Keyspace for Mask {
+    fn size(&self) -> u64 {
+        self.positions
+            .iter()
+            .try_fold(1u64, |size, charset| size.checked_mul(charset.len() as u64))
+            .unwrap_or(u64::MAX)
+    }
+
+    fn candidate(&self, index: u64, input: &mut Vec<u8>) {
+        let mut digits = Vec::new();
+        self.digits(index, &mut digits);
+        input.clear();
+        input.extend(
+            digits
+                .iter()
+                .zip(&self.positions)
+                .map(|(digit, charset)| charset[*digit]),
+        );
+    }
+
+    fn mask(&self) -> Option<&Mask> {
+        Some(self)
+    }
+}
+
+/// One part of every candidate of a [`Combinator`].
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum Slot {
+    /// One of a list of tokens, in order.
+    Tokens(Vec<Vec<u8>>),
+    /// A number of this many digits, zero-padded, counting up from zero.
+    Number(u32),
+}
+
+impl Slot {
+    /// How many tokens the slot has, at most `u64::MAX`.
+    pub fn len(&self) -> u64 {
+        match self {
+            Self::Tokens(tokens) => tokens.len() as u64,
+            Self::Number(digits) => 10u64.checked_pow(*digits).unwrap_or(u64::MAX),
+        }
+    }
+
+    /// Whether the slot has no tokens, so there are no candidates.
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// Appends token `index` to `input`.
+    pub fn push(&self, index: u64, input: &mut Vec<u8>) {
+        match self {
+            Self::Tokens(tokens) => input.extend_from_slice(&tokens[index as usize]),
+            Self::Number(digits) => {
+                let start = input.len();
+                input.resize(start + *digits as usize, b'0');
+                let mut number = index;
+                for digit in input[start..].iter_mut().rev() {
+                    *digit = b'0' + (number % 10) as u8;
+                    number /= 10;
+                }
+            }
+        }
+    }
+}
+
+/// Every combination of a token from each slot, with the last slot changing
+/// fastest.
+///
+/// # Examples
+/// ```
+/// use std::collections::BTreeMap;
+///
+/// use atlas::search::{Combinator, Keyspace as _};
+///
+/// let lists = BTreeMap::from([
+///     (String::from("noun"), vec![b"vendor".to_vec(), b"guard".to_vec()]),
+///     (String::from("verb"), vec![b"idle".to_vec(), b"alert".to_vec()]),
+/// ]);
+/// let combinator = Combinator::parse("npc_{noun}_{verb}_{num:2}", &lists)?;
+/// assert_eq!(combinator.size(), 2 * 2 * 100);
+/// let mut input = Vec::new();
+/// combinator.candidate(142, &mut input);
+/// assert_eq!(input, b"npc_vendor_alert_42");
+/// # Ok::<(), anyhow::Error>(())
+/// ```
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct Combinator {
+    slots: Vec<Slot>,
+}
+
+impl Combinator {
+    pub fn new(slots: impl IntoIterator<Item = Slot>) -> Self {
+        Self {
+            slots: slots.into_iter().collect(),
+        }
+    }
+
+    /// Reads a template where `{name}` stands for a token from the list
+    /// `name` in `lists`, `{num:N}` for an `N`-digit number, up to 19 digits,
+    /// and anything else for itself.
+    pub fn parse(template: &str, lists: &BTreeMap<String, Vec<Vec<u8>>>) -> anyhow::Result<Self> {
+        let mut slots = Vec::new();
+        let mut rest = template;
+        while !rest.is_empty() {
+            let literal = rest.find(['{', '}']).unwrap_or(rest.len());
+            if literal > 0 {
+                slots.push(Slot::Tokens(vec![rest[..literal].as_bytes().to_vec()]));
+            }
+            rest = &rest[literal..];
+            let Some(inner) = rest.strip_prefix('{') else {
+                anyhow::ensure!(rest.is_empty(), "The template has a }} with no {{");
+                break;
+            };
+            let Some((name, after)) = inner.split_once('}') else {
+                anyhow::bail!("The template has a {{ with no }}");
+            };
+            rest = after;
+            if let Some(digits) = name.strip_prefix("num:") {
+                let digits = digits
+                    .parse()
+                    .ok()
+                    .filter(|digits| (1..=19).contains(digits))
+                    .ok_or_else(|| anyhow::anyhow!("{{{name}}} should have 1 to 19 digits"))?;
+                slots.push(Slot::Number(digits));
+            } else {
+                let tokens = lists
+                    .get(name)
+                    .ok_or_else(|| anyhow::anyhow!("There is no token list {name}"))?;
+                slots.push(Slot::Tokens(tokens.clone()));
+            }
+        }
+        Ok(Self::new(slots))
+    }
+
+    pub fn slots(&self) -> &[Slot] {
+        &self.slots
+    }
+
+    /// The token index of each slot in candidate `index`.
+    pub(crate) fn digits(&self, mut index: u64, digits: &mut Vec<u64>) {
+        digits.clear();
+        digits.resize(self.slots.len(), 0);
+        for (digit, slot) in digits.iter_mut().zip(&self.slots).rev() {
+            let base = slot.len().max(1);
+            *digit = index % base;
+            index /= base;
+        }
+    }
+
+    /// Moves `digits` on to the next candidate, back to the first after the
+    /// last, returning the first slot that changed.
+    pub(crate) fn advance(&self, digits: &mut [u64]) -> usize {
+        for (slot, (digit, tokens)) in digits.iter_mut().zip(&self.slots).enumerate().rev() {
+            *digit += 1;
+            if *digit < tokens.len() {
+                return slot;
+            }
+            *digit = 0;
+        }
+        0
+    }
+}
+
+impl Keyspace for Combinator {
+    fn size(&self) -> u64 {
+        self.slots
+            .iter()
+            .try_fold(1u64, |size, slot| size.checked_mul(slot.len()))
+            .unwrap_or(u64::MAX)
+    }
+
+    fn candidate(&self, index: u64, input: &mut Vec<u8>) {
+        let mut digits = Vec::new();
+        self.digits(index, &mut digits);
+        input.clear();
+        for (digit, slot) in digits.iter().zip(&self.slots) {
+            slot.push(*digit, input);
+        }
+    }
+
+    fn combinator(&self) -> Option<&Combinator> {
+        Some(self)
+    }
+}
@@ -0,0 +1,645 @@
+//! Running searches on a thread pool, in the background.
+//!
+//! An [`Engine`] splits a [`Keyspace`] into ranges of indices and checks them
+//! on worker threads. Each worker takes small chunks from the front of its
+//! own range, and when that runs out, takes the back half of the largest
+//! range left, so no worker idles while there's work. Matches and progress
+//! arrive as [`Event`]s, which a UI can poll each frame without blocking, and
+//! the remaining ranges are saved as a [`Checkpoint`] to resume from later.
+
+use std::collections::HashSet;
+use std::ops::Range;
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::mpsc::{self, Receiver, RecvTimeoutError, Sender};
+use std::sync::{Arc, Condvar, Mutex, MutexGuard, PoisonError};
+use std::thread::JoinHandle;
+use std::time::{Duration, Instant};
+
+use log::error;
+use serde::{Deserialize, Serialize};
+
+use super::kernel::Scan;
+use super::{Affixes, Keyspace, Match, Search, Targets};
+use crate::registry::Batch;
+use crate::{HashError, HashOptions, Registry};
+
+/// How many candidates a worker checks between looking at its controls.
+const CHUNK: u64 = 1024;
+/// How often progress is reported.
+const PROGRESS_INTERVAL: Duration = Duration::from_millis(200);
+/// How often the checkpoint is saved while running.
+const CHECKPOINT_INTERVAL: Duration = Duration::from_secs(10);
+
+/// What to search for, and where.
+#[derive(Clone)]
+pub struct Job {
+    pub hasher: String,
+    pub options: HashOptions,
+    pub affixes: Affixes,
+    pub targets: Arc<Targets>,
+    pub keyspace: Arc<dyn Keyspace>,
+    /// How many worker threads to run, or 0 for one per core.
+    pub threads: usize,
+    /// Where to save the progress, if anywhere.
+    pub checkpoint: Option<PathBuf>,
+}
+
+impl Job {
+    pub fn new(
+        hasher: impl Into<String>,
+        targets: impl IntoIterator<Item = Vec<u8>>,
+        keyspace: Arc<dyn Keyspace>,
+    ) -> Self {
+        Self {
+            hasher: hasher.into(),
+            options: HashOptions::default(),
+            affixes: Affixes::default(),
+            targets: Arc::new(targets.into_iter().collect()),
+            keyspace,
+            threads: 0,
+            checkpoint: None,
+        }
+    }
+}
+
+/// How far a search has got.
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+pub struct Progress {
+    /// Candidates checked, including those checked before resuming.
+    pub done: u64,
+    pub total: u64,
+    /// Candidates checked per second, lately.
+    pub rate: f64,
+    /// The time left at the current rate, unless it's stalled.
+    pub eta: Option<Duration>,
+}
+
+impl Progress {
+    /// The fraction of candidates checked, from 0 to 1.
+    pub fn fraction(&self) -> f32 {
+        if self.total == 0 {
+            1.0
+        } else {
+            (self.done as f64 / self.total as f64) as f32
+        }
+    }
+}
+
+/// How a search ended.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum Outcome {
+    /// Every candidate was checked.
+    Completed,
+    Cancelled,
+    /// The hasher failed, stopping the search.
+    Failed(HashError),
+}
+
+/// What an [`Engine`] reports.
+#[derive(Clone, Debug, PartialEq)]
+pub enum Event {
+    Progress(Progress),
+    Found(Match),
+    /// The last event of every search.
+    Finished(Outcome),
+}
+
+/// The progress of a search as saved to disk, to carry on from.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Checkpoint {
+    pub hasher: String,
+    pub options: HashOptions,
+    pub affixes: Affixes,
+    /// The [`Targets::digest`] of the targets searched for.
+    pub targets: u64,
+    pub total: u64,
+    /// The index ranges not yet checked, in order and apart.
+    pub remaining: Vec<Range<u64>>,
+    pub found: Vec<Match>,
+}
+
+impl Checkpoint {
+    /// `search.ron` next to the current executable.
+    pub fn default_path() -> anyhow::Result<PathBuf> {
+        let current_exe = std::env::current_exe()?;
+        let Some(exe_dir) = current_exe.parent() else {
+            return Err(anyhow::anyhow!("Failed to find parent of current exe"));
+        };
+        Ok(exe_dir.join("search.ron"))
+    }
+
+    pub fn load(path: impl AsRef<Path>) -> anyhow::Result<Self> {
+        Ok(ron::from_str(&std::fs::read_to_string(path)?)?)
+    }
+
+    /// Saves the checkpoint, replacing the file only once it's fully written.
+    pub fn save(&self, path: impl AsRef<Path>) -> anyhow::Result<()> {
+        let path = path.as_ref();
+        let text = ron::ser::to_string_pretty(self, ron::ser::PrettyConfig::default())?;
+        let partial = path.with_extension("partial");
+        std::fs::write(&partial, text)?;
+        std::fs::rename(partial, path)?;
+        Ok(())
+    }
+
+    /// A checkpoint of `job` with nothing checked yet.
+    pub fn new(job: &Job) -> Self {
+        Self {
+            hasher: job.hasher.clone(),
+            options: job.options.clone(),
+            affixes: job.affixes.clone(),
+            targets: job.targets.digest(),
+            total: job.keyspace.size(),
+            remaining: std::iter::once(0..job.keyspace.size()).collect(),
+            found: Vec::new(),
+        }
+    }
+
+    /// How many candidates were checked.
+    pub fn done(&self) -> u64 {
+        let remaining: u64 = self
+            .remaining
+            .iter()
+            .map(|range| range.end.saturating_sub(range.start))
+            .sum();
+        self.total.saturating_sub(remaining)
+    }
+
+    /// Checks the ranges left are in order, apart and within the keyspace.
+    fn validate(&self) -> anyhow::Result<()> {
+        let mut checked = 0;
+        for range in &self.remaining {
+            if range.start < checked || range.end < range.start || range.end > self.total {
+                anyhow::bail!(
+                    "The checkpoint's remaining range {range:?} is out of order or outside 0..{}",
+                    self.total
+                );
+            }
+            checked = range.end;
+        }
+        Ok(())
+    }
+}
+
+#[derive(Clone, Copy, PartialEq, Eq)]
+enum State {
+    Running,
+    Paused,
+    Cancelled,
+}
+
+/// The state shared between an [`Engine`] and its threads.
+///
+/// Whoever holds more than one of the locks on the work takes `stealing`
+/// first, then slots in order, then `pool`, so they can't deadlock.
+struct Shared {
+    state: Mutex<State>,
+    changed: Condvar,
+    stealing: Mutex<()>,
+    /// Each worker's chunk in progress and the range it takes chunks from.
+    slots: Vec<Mutex<(Range<u64>, Range<u64>)>>,
+    /// Ranges no worker has taken yet.
+    pool: Mutex<Vec<Range<u64>>>,
+    /// Candidates checked, only changed with the worker's slot locked.
+    done: AtomicU64,
+    error: Mutex<Option<HashError>>,
+}
+
+/// Locks `mutex`, carrying on if a thread panicked holding it; every update
+/// under these locks leaves the data consistent.
+fn lock<T>(mutex: &Mutex<T>) -> MutexGuard<'_, T> {
+    mutex.lock().unwrap_or_else(PoisonError::into_inner)
+}
+
+impl Shared {
+    fn set_state(&self, state: State) {
+        let mut current = lock(&self.state);
+        if *current != State::Cancelled {
+            *current = state;
+        }
+        self.changed.notify_all();
+    }
+
+    /// Blocks while paused, returning whether to carry on.
+    fn proceed(&self) -> bool {
+        let state = lock(&self.state);
+        let state = self
+            .changed
+            .wait_while(state, |state| *state == State::Paused)
+            .unwrap_or_else(PoisonError::into_inner);
+        *state == State::Running
+    }
+
+    /// The next chunk for worker `id`, from its own range, the pool, or
+    /// stolen from the back of the largest range left.
+    fn next_chunk(&self, id: usize) -> Option<Range<u64>> {
+        let mut slot = lock(&self.slots[id]);
+        if slot.1.is_empty() {
+            if let Some(range) = lock(&self.pool).pop() {
+                slot.1 = range;
+            } else {
+                drop(slot);
+                self.steal(id)?;
+                slot = lock(&self.slots[id]);
+            }
+        }
+        let end = slot.1.end.min(slot.1.start + CHUNK);
+        slot.0 = slot.1.start..end;
+        slot.1.start = end;
+        Some(slot.0.clone())
+    }
+
+    /// Moves the back half of the largest range left to worker `thief`.
+    fn steal(&self, thief: usize) -> Option<()> {
+        let _stealing = lock(&self.stealing);
+        let victim = (0..self.slots.len())
+            .filter(|id| *id != thief)
+            .max_by_key(|id| {
+                let range = &lock(&self.slots[*id]).1;
+                range.end - range.start
+            })?;
+        let mut first = lock(&self.slots[thief.min(victim)]);
+        let mut second = lock(&self.slots[thief.max(victim)]);
+        let (thief, victim) = if thief < victim {
+            (&mut first.1, &mut second.1)
+        } else {
+            (&mut second.1, &mut first.1)
+        };
+        // Ranges of a chunk or less are left to their owner.
+        if victim.end - victim.start <= CHUNK {
+            return None;
+        }
+        let middle = victim.start + (victim.end - victim.start) / 2;
+        *thief = middle..victim.end;
+        victim.end = middle;
+        Some(())
+    }
+
+    /// Marks worker `id`'s chunk in progress as checked.
+    fn finish_chunk(&self, id: usize) {
+        let mut slot = lock(&self.slots[id]);
+        self.done
+            .fetch_add(slot.0.end - slot.0.start, Ordering::Relaxed);
+        slot.0 = 0..0;
+    }
+
+    fn done(&self) -> u64 {
+        self.done.load(Ordering::Relaxed)
+    }
+
+    /// The ranges not yet checked, counting chunks in progress.
+    fn remaining(&self) -> Vec<Range<u64>> {
+        let _stealing = lock(&self.stealing);
+        let slots: Vec<_> = self.slots.iter().map(lock).collect();
+        let mut remaining = lock(&self.pool).clone();
+        for slot in &slots {
+            remaining.extend([slot.0.clone(), slot.1.clone()]);
+        }
+        remaining.retain(|range| !range.is_empty());
+        remaining.sort_by_key(|range| range.start);
+        remaining
+    }
+}
+
+/// A search running in the background.
+///
+/// Dropping it cancels the search and waits for its threads to stop.
+///
+/// # Examples
+/// ```no_run
+/// use std::sync::Arc;
+///
+/// use atlas::Registry;
+/// use atlas::search::engine::{Engine, Event, Job};
+/// use atlas::search::BruteForce;
+///
+/// let mut registry = Registry::new();
+/// registry.load_dir(Registry::default_plugin_dir()?)?;
+///
+/// let keyspace = Arc::new(BruteForce::new(b"abcdefghijklmnopqrstuvwxyz_", 1, 6));
+/// let job = Job::new("CRC-32", [hex::decode("3610A686")?], keyspace);
+/// let engine = Engine::start(Arc::new(registry), job);
+/// for event in engine.events() {
+///     match event {
+///         Event::Progress(progress) => println!("{:.1}%", progress.fraction() * 100.0),
+///         Event::Found(found) => println!("{}", String::from_utf8_lossy(&found.input)),
+///         Event::Finished(outcome) => println!("{outcome:?}"),
+///     }
+/// }
+/// # Ok::<(), anyhow::Error>(())
+/// ```
+pub struct Engine {
+    shared: Arc<Shared>,
+    events: Receiver<Event>,
+    thread: Option<JoinHandle<()>>,
+}
+
+impl Engine {
+    /// Starts searching the whole keyspace.
+    pub fn start(registry: Arc<Registry>, job: Job) -> Self {
+        let checkpoint = Checkpoint::new(&job);
+        Self::spawn(registry, job, checkpoint)
+    }
+
+    /// Carries on a search from a checkpoint of the same job.
+    pub fn resume(
+        registry: Arc<Registry>,
+        job: Job,
+        checkpoint: Checkpoint,
+    ) -> anyhow::Result<Self> {
+        if checkpoint.hasher != job.hasher || checkpoint.total != job.keyspace.size() {
+            anyhow::bail!(
+                "The checkpoint is of a search of {} candidates with {}, not {} with {}",
+                checkpoint.total,
+                checkpoint.hasher,
+                job.keyspace.size(),
+                job.hasher
+            );
+        }
+        if checkpoint.options != job.options
+            || checkpoint.affixes != job.affixes
+            || checkpoint.targets != job.targets.digest()
+        {
+            anyhow::bail!("The checkpoint is of a search with other options, affixes or targets");
+        }
+        checkpoint.validate()?;
+        Ok(Self::spawn(registry, job, checkpoint))
+    }
+
+    fn spawn(registry: Arc<Registry>, job: Job, checkpoint: Checkpoint) -> Self {
+        let threads = match job.threads {
+            0 => std::thread::available_parallelism().map_or(1, usize::from),
+            threads => threads,
+        };
+        // Split the ranges left between the threads to start them off.
+        let mut pool = Vec::new();
+        for range in &checkpoint.remaining {
+            let step = (range.end - range.start)
+                .div_ceil(threads as u64)
+                .max(CHUNK);
+            let mut start = range.start;
+            while start < range.end {
+                pool.push(start..range.end.min(start + step));
+                start += step;
+            }
+        }
+        let shared = Arc::new(Shared {
+            state: Mutex::new(State::Running),
+            changed: Condvar::new(),
+            stealing: Mutex::new(()),
+            slots: (0..threads).map(|_| Mutex::new((0..0, 0..0))).collect(),
+            pool: Mutex::new(pool),
+            done: AtomicU64::new(checkpoint.done()),
+            error: Mutex::new(None),
+        });
+        let (sender, events) = mpsc::channel();
+        let thread = {
+            let shared = Arc::clone(&shared);
+            std::thread::spawn(move || run(&registry, &job, &shared, checkpoint, &sender))
+        };
+        Self {
+            shared,
+            events,
+            thread: Some(thread),
+        }
+    }
+
+    /// The events since the last poll, without blocking.
+    pub fn poll(&self) -> impl Iterator<Item = Event> + '_ {
+        self.events.try_iter()
+    }
+
+    /// Every event, blocking until the search finishes.
+    pub fn events(&self) -> impl Iterator<Item = Event> + '_ {
+        self.events.iter()
+    }
+
+    pub fn pause(&self) {
+        self.shared.set_state(State::Paused);
+    }
+
+    pub fn resume_paused(&self) {
+        self.shared.set_state(State::Running);
+    }
+
+    pub fn is_paused(&self) -> bool {
+        *lock(&self.shared.state) == State::Paused
+    }
+
+    /// Stops the search, which finishes with [`Outcome::Cancelled`].
+    pub fn cancel(&self) {
+        self.shared.set_state(State::Cancelled);
+    }
+
+    /// Whether the search has stopped, though events may be left to poll.
+    pub fn is_finished(&self) -> bool {
+        self.thread.as_ref().is_none_or(JoinHandle::is_finished)
+    }
+}
+
+impl Drop for Engine {
+    fn drop(&mut self) {
+        self.cancel();
+        if let Some(thread) = self.thread.take() {
+            thread.join().ok();
+        }
+    }
+}
+
+/// Checks the chunks worker `id` takes until none are left.
+fn work(
+    search: &Search<'_>,
+    keyspace: &dyn Keyspace,
+    shared: &Shared,
+    id: usize,
+    found: &Sender<Match>,
+) {
+    let matcher = search.matcher();
+    let scan = keyspace.mask().and_then(|mask| Scan::new(search, mask));
+    let mut input = Vec::new();
+    let mut candidates = Batch::new();
+    let mut scratch = Batch::new();
+    while shared.proceed() {
+        let Some(chunk) = shared.next_chunk(id) else {
+            break;
+        };
+        let matches = if let Some(scan) = &scan {
+            let mut matches = Vec::new();
+            scan.run(chunk, &mut matches);
+            Ok(matches)
+        } else if let Some(combinator) = keyspace.combinator().filter(|_| matcher.is_streamed()) {
+            matcher.check_combinations(combinator, chunk)
+        } else {
+            candidates.clear();
+            for index in chunk {
+                keyspace.candidate(index, &mut input);
+                candidates.push(&[&input]);
+            }
+            matcher.check_batch(&candidates, &mut scratch)
+        };
+        match matches {
+            Ok(matches) => {
+                for matched in matches {
+                    found.send(matched).ok();
+                }
+            }
+            Err(e) => {
+                lock(&shared.error).get_or_insert(e);
+                shared.set_state(State::Cancelled);
+                return;
+            }
+        }
+        shared.finish_chunk(id);
+    }
+}
+
+/// Runs the workers, forwarding their matches and reporting progress until
+/// they finish.
+fn run(
+    registry: &Registry,
+    job: &Job,
+    shared: &Shared,
+    mut checkpoint: Checkpoint,
+    events: &Sender<Event>,
+) {
+    let search = Search::new(registry, &job.hasher, &job.options, Vec::new())
+        .with_targets(Arc::clone(&job.targets))
+        .with_affixes(&job.affixes.prefix, &job.affixes.suffix);
+    let mut matches = Matches {
+        seen: checkpoint
+            .found
+            .iter()
+            .map(|found| found.input.clone())
+            .collect(),
+        events,
+    };
+    for found in &checkpoint.found {
+        events.send(Event::Found(found.clone())).ok();
+    }
+
+    let (sender, found) = mpsc::channel();
+    std::thread::scope(|scope| {
+        for id in 0..shared.slots.len() {
+            let sender = sender.clone();
+            let search = &search;
+            scope.spawn(move || work(search, &*job.keyspace, shared, id, &sender));
+        }
+        drop(sender);
+
+        let mut reporter = Reporter::new(shared.done(), checkpoint.total);
+        let mut reported = Instant::now();
+        let mut saved = Instant::now();
+        let mut was_paused = false;
+        loop {
+            match found.recv_timeout(PROGRESS_INTERVAL) {
+                Ok(matched) => matches.add(matched, &mut checkpoint),
+                Err(RecvTimeoutError::Timeout) => {}
+                Err(RecvTimeoutError::Disconnected) => break,
+            }
+            if reported.elapsed() < PROGRESS_INTERVAL {
+                continue;
+            }
+            reported = Instant::now();
+            let paused = *lock(&shared.state) == State::Paused;
+            let progress = reporter.update(shared.done(), paused);
+            events.send(Event::Progress(progress)).ok();
+            if saved.elapsed() >= CHECKPOINT_INTERVAL || paused && !was_paused {
+                save(job, shared, &found, &mut matches, &mut checkpoint);
+                saved = Instant::now();
+            }
+            was_paused = paused;
+        }
+    });
+
+    save(job, shared, &found, &mut matches, &mut checkpoint);
+    let done = shared.done();
+    let progress = Reporter::new(done, checkpoint.total).update(done, true);
+    events.send(Event::Progress(progress)).ok();
+    let outcome = match lock(&shared.error).take() {
+        Some(e) => Outcome::Failed(e),
+        None if checkpoint.remaining.is_empty() => Outcome::Completed,
+        None => Outcome::Cancelled,
+    };
+    events.send(Event::Finished(outcome)).ok();
+}
+
+/// Forwards the matches the workers find, once each.
+struct Matches<'a> {
+    seen: HashSet<Vec<u8>>,
+    events: &'a Sender<Event>,
+}
+
+impl Matches<'_> {
+    fn add(&mut self, matched: Match, checkpoint: &mut Checkpoint) {
+        // Chunks in progress when a search is saved are checked again when
+        // it resumes, finding their matches again.
+        if self.seen.insert(matched.input.clone()) {
+            checkpoint.found.push(matched.clone());
+            self.events.send(Event::Found(matched)).ok();
+        }
+    }
+}
+
+/// Records the ranges left in `checkpoint`, and saves it if the job has a path.
+fn save(
+    job: &Job,
+    shared: &Shared,
+    found: &Receiver<Match>,
+    matches: &mut Matches<'_>,
+    checkpoint: &mut Checkpoint,
+) {
+    checkpoint.remaining = shared.remaining();
+    // Workers send matches before marking their chunk checked, so every
+    // match in the chunks no longer remaining has been sent by now.
+    for matched in found.try_iter() {
+        matches.add(matched, checkpoint);
+    }
+    if let Some(path) = &job.checkpoint {
+        if let Err(e) = checkpoint.save(path) {
+            error!(
+                "Failed to save the search checkpoint to {}: {e}",
+                path.display()
+            );
+        }
+    }
+}
+
+/// Measures the rate candidates are checked at.
+struct Reporter {
+    total: u64,
+    last_done: u64,
+    last_time: Instant,
+    rate: f64,
+}
+
+impl Reporter {
+    fn new(done: u64, total: u64) -> Self {
+        Self {
+            total,
+            last_done: done,
+            last_time: Instant::now(),
+            rate: 0.0,
+        }
+    }
+
+    /// The progress with `done` candidates checked, the rate smoothed over
+    /// the last few seconds. Time spent paused doesn't count.
+    fn update(&mut self, done: u64, paused: bool) -> Progress {
+        let elapsed = self.last_time.elapsed().as_secs_f64();
+        if !paused && elapsed > 0.0 {
+            let rate = (done - self.last_done) as f64 / elapsed;
+            self.rate += (rate - self.rate) * (elapsed / 2.0).min(1.0);
+        }
+        self.last_done = done;
+        self.last_time = Instant::now();
+        let left = self.total - done;
+        let eta = (self.rate > 0.0).then(|| Duration::from_secs_f64(left as f64 / self.rate));
+        Progress {
+            done,
+            total: self.total,
+            rate: self.rate,
+            eta,
+        }
+    }
+}
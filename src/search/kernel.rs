@@ -0,0 +1,763 @@
+//! Vectorised kernels that hash eight candidates of a [`Mask`] at once.
+//!
+//! The 32-bit FNV-1a and Murmur3 hashers are brute forced over billions of
+//! candidates. Candidates of a mask all have the same length, so eight of them
+//! can be hashed in lockstep, one per lane of a vector: the bytes at each
+//! position are gathered across the eight, and every step of the hash runs on
+//! all lanes at once. The kernel uses AVX2 or SSE4.1 on x86-64 and NEON on
+//! 64-bit ARM, whichever the CPU has, and plain arrays anywhere else.
+
+use std::ops::Range;
+
+use super::{Affixes, Keyspace as _, Mask, Match, Search};
+use crate::{HashOptions, Registry};
+
+/// How many candidates a kernel hashes at once.
+const LANES: usize = 8;
+
+/// The instruction sets a [`Kernel`] can run on.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Isa {
+    Avx2,
+    Sse41,
+    Neon,
+    /// Plain arrays, which run anywhere.
+    Portable,
+}
+
+impl Isa {
+    /// Every instruction set, fastest first.
+    pub const ALL: [Self; 4] = [Self::Avx2, Self::Sse41, Self::Neon, Self::Portable];
+
+    /// The fastest instruction set this CPU has.
+    pub fn detect() -> Self {
+        Self::ALL
+            .into_iter()
+            .find(|isa| isa.is_supported())
+            .unwrap_or(Self::Portable)
+    }
+
+    /// Whether this CPU has the instruction set.
+    pub fn is_supported(self) -> bool {
+        match self {
+            Self::Portable => true,
+            #[cfg(target_arch = "x86_64")]
+            Self::Avx2 => std::arch::is_x86_feature_detected!("avx2"),
+            #[cfg(target_arch = "x86_64")]
+            Self::Sse41 => std::arch::is_x86_feature_detected!("sse4.1"),
+            #[cfg(target_arch = "aarch64")]
+            Self::Neon => std::arch::is_aarch64_feature_detected!("neon"),
+            // Instruction sets of other architectures.
+            _ => false,
+        }
+    }
+}
+
+/// The hashers with kernels.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+enum Algorithm {
+    Fnv1a32,
+    Murmur3_32 { seed: u32 },
+}
+
+const FNV_BASIS: u32 = 0x811c_9dc5;
+const FNV_PRIME: u32 = 0x0100_0193;
+const MURMUR_C1: u32 = 0xcc9e_2d51;
+const MURMUR_C2: u32 = 0x1b87_3593;
+
+/// Hashes candidates of a [`Mask`] between fixed affixes, eight at a time.
+///
+/// # Examples
+/// ```no_run
+/// use atlas::search::kernel::Kernel;
+/// use atlas::search::{Affixes, Mask};
+/// use atlas::{HashOptions, Registry};
+///
+/// let mut registry = Registry::new();
+/// registry.load_dir(Registry::default_plugin_dir()?)?;
+///
+/// let kernel = Kernel::new(&registry, "FNV-1a_32", &HashOptions::default())
+///     .expect("FNV-1a_32 has a kernel");
+/// let mask = Mask::parse("npc_?l?l?d")?;
+/// for hash in kernel.hashes(&Affixes::default(), &mask, 0..10) {
+///     println!("{}", hex::encode(hash));
+/// }
+/// # Ok::<(), anyhow::Error>(())
+/// ```
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct Kernel {
+    algorithm: Algorithm,
+    isa: Isa,
+}
+
+impl Kernel {
+    /// The kernel for the hasher registered as `name`, if there is one, on
+    /// the fastest instruction set this CPU has.
+    ///
+    /// The registered hasher is checked against the kernel, in case a plugin
+    /// registered something else under the same name.
+    pub fn new(registry: &Registry, name: &str, options: &HashOptions) -> Option<Self> {
+        let algorithm = match name {
+            "FNV-1a_32" => Algorithm::Fnv1a32,
+            "Murmur3_32" => Algorithm::Murmur3_32 {
+                seed: u32::try_from(options.seed.unwrap_or(0)).ok()?,
+            },
+            _ => return None,
+        };
+        let kernel = Self {
+            algorithm,
+            isa: Isa::detect(),
+        };
+        let sample = Mask::parse("npc_vendor_idle_?d?d.wem").ok()?;
+        let hash = registry
+            .hash(name, b"npc_vendor_idle_01.wem", options)
+            .ok()?;
+        let expected = kernel.hashes(&Affixes::default(), &sample, 1..2);
+        (hex::decode(hash.0).ok()? == expected.concat()).then_some(kernel)
+    }
+
+    /// The same kernel on another instruction set, if this CPU has it.
+    pub fn with_isa(self, isa: Isa) -> Option<Self> {
+        isa.is_supported().then_some(Self { isa, ..self })
+    }
+
+    pub fn isa(&self) -> Isa {
+        self.isa
+    }
+
+    /// The hashes of candidates `range` of `mask`, wrapped in `affixes`.
+    pub fn hashes(&self, affixes: &Affixes, mask: &Mask, range: Range<u64>) -> Vec<[u8; 4]> {
+        let layout = Layout::new(self.algorithm, affixes, mask);
+        let mut hashes = Vec::new();
+        self.drive(&layout, mask, range, |_, lanes| {
+            hashes.extend(lanes.iter().map(|hash| hash.to_be_bytes()));
+        });
+        hashes
+    }
+
+    /// Runs `each` on the first index and hashes of every eight candidates
+    /// of `range`, of which only those before its end are real.
+    fn drive(
+        &self,
+        layout: &Layout,
+        mask: &Mask,
+        range: Range<u64>,
+        each: impl FnMut(u64, &[u32]),
+    ) {
+        match self.isa {
+            #[cfg(target_arch = "x86_64")]
+            // SAFETY: kernels only get an instruction set the CPU has.
+            Isa::Avx2 => unsafe { x86::drive_avx2(self.algorithm, layout, mask, range, each) },
+            #[cfg(target_arch = "x86_64")]
+            // SAFETY: kernels only get an instruction set the CPU has.
+            Isa::Sse41 => unsafe { x86::drive_sse41(self.algorithm, layout, mask, range, each) },
+            #[cfg(target_arch = "aarch64")]
+            // SAFETY: kernels only get an instruction set the CPU has.
+            Isa::Neon => unsafe { arm::drive_neon(self.algorithm, layout, mask, range, each) },
+            _ => drive::<[u32; LANES]>(self.algorithm, layout, mask, range, each),
+        }
+    }
+}
+
+/// The bytes a kernel hashes for each candidate, one row per byte and one
+/// column per lane, with the affixes filled in.
+#[derive(Clone, Debug)]
+struct Layout {
+    /// The state after what comes before `rows`.
+    start: u32,
+    rows: Vec<[u8; LANES]>,
+    /// The row of the candidate's first byte.
+    at: usize,
+    /// The length of the whole input.
+    len: u32,
+}
+
+impl Layout {
+    fn new(algorithm: Algorithm, affixes: &Affixes, mask: &Mask) -> Self {
+        let Affixes { prefix, suffix } = affixes;
+        let len = (prefix.len() + mask.len() + suffix.len()) as u32;
+        // FNV hashes the prefix ahead, Murmur3 only its whole blocks.
+        let (start, prefix): (_, &[u8]) = match algorithm {
+            Algorithm::Fnv1a32 => {
+                let start = prefix.iter().fold(FNV_BASIS, |hash, byte| {
+                    (hash ^ u32::from(*byte)).wrapping_mul(FNV_PRIME)
+                });
+                (start, &[])
+            }
+            Algorithm::Murmur3_32 { seed } => {
+                let blocks = prefix.chunks_exact(4);
+                let rest = blocks.remainder();
+                let start = blocks.fold(seed, |hash, block| {
+                    let block = u32::from_le_bytes([block[0], block[1], block[2], block[3]]);
+                    murmur3_block(hash, block)
+                });
+                (start, rest)
+            }
+        };
+        let rows = prefix
+            .iter()
+            .chain(&vec![0; mask.len()])
+            .chain(suffix)
+            .map(|byte| [*byte; LANES])
+            .collect();
+        Self {
+            start,
+            rows,
+            at: prefix.len(),
+            len,
+        }
+    }
+}
+
+fn murmur3_block(hash: u32, block: u32) -> u32 {
+    let block = block
+        .wrapping_mul(MURMUR_C1)
+        .rotate_left(15)
+        .wrapping_mul(MURMUR_C2);
+    (hash ^ block)
+        .rotate_left(13)
+        .wrapping_mul(5)
+        .wrapping_add(0xe654_6b64)
+}
+
+/// Eight 32-bit lanes, and the operations the kernels need on them.
+///
+/// The vector implementations may only be used from functions that enable
+/// their instruction set, and only once the CPU is known to have it.
+trait Lanes: Copy {
+    fn splat(value: u32) -> Self;
+    /// Widens one byte per lane.
+    fn bytes(row: &[u8; LANES]) -> Self;
+    fn store(self) -> [u32; LANES];
+    fn xor(self, other: Self) -> Self;
+    fn or(self, other: Self) -> Self;
+    fn add(self, other: Self) -> Self;
+    fn mul(self, other: Self) -> Self;
+    fn shl(self, bits: u32) -> Self;
+    fn shr(self, bits: u32) -> Self;
+
+    #[inline(always)]
+    fn rotl(self, bits: u32) -> Self {
+        self.shl(bits).or(self.shr(32 - bits))
+    }
+}
+
+impl Lanes for [u32; LANES] {
+    #[inline(always)]
+    fn splat(value: u32) -> Self {
+        [value; LANES]
+    }
+
+    #[inline(always)]
+    fn bytes(row: &[u8; LANES]) -> Self {
+        row.map(u32::from)
+    }
+
+    #[inline(always)]
+    fn store(self) -> [u32; LANES] {
+        self
+    }
+
+    #[inline(always)]
+    fn xor(self, other: Self) -> Self {
+        std::array::from_fn(|i| self[i] ^ other[i])
+    }
+
+    #[inline(always)]
+    fn or(self, other: Self) -> Self {
+        std::array::from_fn(|i| self[i] | other[i])
+    }
+
+    #[inline(always)]
+    fn add(self, other: Self) -> Self {
+        std::array::from_fn(|i| self[i].wrapping_add(other[i]))
+    }
+
+    #[inline(always)]
+    fn mul(self, other: Self) -> Self {
+        std::array::from_fn(|i| self[i].wrapping_mul(other[i]))
+    }
+
+    #[inline(always)]
+    fn shl(self, bits: u32) -> Self {
+        self.map(|lane| lane << bits)
+    }
+
+    #[inline(always)]
+    fn shr(self, bits: u32) -> Self {
+        self.map(|lane| lane >> bits)
+    }
+}
+
+/// Hashes the eight candidates in `rows`.
+///
+/// Everything here is inlined into functions enabling an instruction set, so
+/// it avoids closures, which wouldn't be.
+#[inline(always)]
+fn hash<L: Lanes>(algorithm: Algorithm, layout: &Layout, rows: &[[u8; LANES]]) -> [u32; LANES] {
+    let mut hash = L::splat(layout.start);
+    match algorithm {
+        Algorithm::Fnv1a32 => {
+            let prime = L::splat(FNV_PRIME);
+            for row in rows {
+                hash = hash.xor(L::bytes(row)).mul(prime);
+            }
+        }
+        Algorithm::Murmur3_32 { .. } => {
+            let blocks = rows.chunks_exact(4);
+            let tail = blocks.remainder();
+            for block in blocks {
+                hash = hash
+                    .xor(murmur3_word::<L>(block))
+                    .rotl(13)
+                    .mul(L::splat(5))
+                    .add(L::splat(0xe654_6b64));
+            }
+            if !tail.is_empty() {
+                hash = hash.xor(murmur3_word::<L>(tail));
+            }
+            hash = hash.xor(L::splat(layout.len));
+            hash = hash.xor(hash.shr(16)).mul(L::splat(0x85eb_ca6b));
+            hash = hash.xor(hash.shr(13)).mul(L::splat(0xc2b2_ae35));
+            hash = hash.xor(hash.shr(16));
+        }
+    }
+    hash.store()
+}
+
+/// The little-endian word of up to four rows, mixed like a Murmur3 block.
+#[inline(always)]
+fn murmur3_word<L: Lanes>(rows: &[[u8; LANES]]) -> L {
+    let mut word = L::splat(0);
+    for row in rows.iter().rev() {
+        word = word.shl(8).or(L::bytes(row));
+    }
+    word.mul(L::splat(MURMUR_C1))
+        .rotl(15)
+        .mul(L::splat(MURMUR_C2))
+}
+
+/// Hashes every candidate of `range` eight at a time, handing each eight to
+/// `each` with the index of the first.
+#[inline(always)]
+fn drive<L: Lanes>(
+    algorithm: Algorithm,
+    layout: &Layout,
+    mask: &Mask,
+    range: Range<u64>,
+    mut each: impl FnMut(u64, &[u32]),
+) {
+    let mut rows = layout.rows.clone();
+    let mut digits = Vec::new();
+    mask.digits(range.start, &mut digits);
+    // The first position each of the last eight steps changed, to only
+    // rewrite what changed since a lane's last candidate.
+    let mut changed = [0; LANES];
+    let mut index = range.start;
+    while index < range.end {
+        for lane in 0..LANES {
+            let from = changed.iter().copied().min().unwrap_or(0);
+            let candidate = rows[layout.at + from..].iter_mut().zip(&digits[from..]);
+            for ((row, digit), charset) in candidate.zip(&mask.positions()[from..]) {
+                row[lane] = charset[*digit];
+            }
+            changed[lane] = mask.advance(&mut digits);
+        }
+        let hashes = hash::<L>(algorithm, layout, &rows);
+        let count = (range.end - index).min(LANES as u64) as usize;
+        each(index, &hashes[..count]);
+        index += count as u64;
+    }
+}
+
+#[cfg(target_arch = "x86_64")]
+mod x86 {
+    use std::arch::x86_64::{
+        __m128i, __m256i, _mm_add_epi32, _mm_cvtepu8_epi32, _mm_cvtsi32_si128, _mm_loadl_epi64,
+        _mm_mullo_epi32, _mm_or_si128, _mm_set1_epi32, _mm_sll_epi32, _mm_srl_epi32,
+        _mm_storeu_si128, _mm_xor_si128, _mm256_add_epi32, _mm256_cvtepu8_epi32,
+        _mm256_mullo_epi32, _mm256_or_si256, _mm256_set1_epi32, _mm256_sll_epi32, _mm256_srl_epi32,
+        _mm256_storeu_si256, _mm256_xor_si256,
+    };
+    use std::ops::Range;
+
+    use super::{Algorithm, LANES, Lanes, Layout, Mask};
+
+    /// All eight lanes in one AVX2 register.
+    #[derive(Clone, Copy)]
+    pub struct Avx2(__m256i);
+
+    /// Four lanes in each of two SSE registers.
+    #[derive(Clone, Copy)]
+    pub struct Sse41([__m128i; 2]);
+
+    /// Runs [`super::drive`] with AVX2.
+    ///
+    /// # Safety
+    /// The CPU must have AVX2.
+    #[target_feature(enable = "avx2")]
+    pub unsafe fn drive_avx2(
+        algorithm: Algorithm,
+        layout: &Layout,
+        mask: &Mask,
+        range: Range<u64>,
+        each: impl FnMut(u64, &[u32]),
+    ) {
+        super::drive::<Avx2>(algorithm, layout, mask, range, each);
+    }
+
+    /// Runs [`super::drive`] with SSE4.1.
+    ///
+    /// # Safety
+    /// The CPU must have SSE4.1.
+    #[target_feature(enable = "sse4.1")]
+    pub unsafe fn drive_sse41(
+        algorithm: Algorithm,
+        layout: &Layout,
+        mask: &Mask,
+        range: Range<u64>,
+        each: impl FnMut(u64, &[u32]),
+    ) {
+        super::drive::<Sse41>(algorithm, layout, mask, range, each);
+    }
+
+    // SAFETY (for every block below): `Avx2` is only used inside
+    // `drive_avx2`, which is only called once the CPU is known to have AVX2.
+    impl Lanes for Avx2 {
+        #[inline(always)]
+        fn splat(value: u32) -> Self {
+            // SAFETY: see above.
+            Self(unsafe { _mm256_set1_epi32(value as i32) })
+        }
+
+        #[inline(always)]
+        fn bytes(row: &[u8; LANES]) -> Self {
+            // SAFETY: see above, and `row` is eight bytes long.
+            Self(unsafe { _mm256_cvtepu8_epi32(_mm_loadl_epi64(row.as_ptr().cast())) })
+        }
+
+        #[inline(always)]
+        fn store(self) -> [u32; LANES] {
+            let mut lanes = [0; LANES];
+            // SAFETY: see above, and `lanes` is 32 bytes long.
+            unsafe { _mm256_storeu_si256(lanes.as_mut_ptr().cast(), self.0) };
+            lanes
+        }
+
+        #[inline(always)]
+        fn xor(self, other: Self) -> Self {
+            // SAFETY: see above.
+            Self(unsafe { _mm256_xor_si256(self.0, other.0) })
+        }
+
+        #[inline(always)]
+        fn or(self, other: Self) -> Self {
+            // SAFETY: see above.
+            Self(unsafe { _mm256_or_si256(self.0, other.0) })
+        }
+
+        #[inline(always)]
+        fn add(self, other: Self) -> Self {
+            // SAFETY: see above.
+            Self(unsafe { _mm256_add_epi32(self.0, other.0) })
+        }
+
+        #[inline(always)]
+        fn mul(self, other: Self) -> Self {
+            // SAFETY: see above.
+            Self(unsafe { _mm256_mullo_epi32(self.0, other.0) })
+        }
+
+        #[inline(always)]
+        fn shl(self, bits: u32) -> Self {
+            // SAFETY: see above.
+            Self(unsafe { _mm256_sll_epi32(self.0, _mm_cvtsi32_si128(bits as i32)) })
+        }
+
+        #[inline(always)]
+        fn shr(self, bits: u32) -> Self {
+            // SAFETY: see above.
+            Self(unsafe { _mm256_srl_epi32(self.0, _mm_cvtsi32_si128(bits as i32)) })
+        }
+    }
+
+    // SAFETY (for every block below): `Sse41` is only used inside
+    // `drive_sse41`, which is only called once the CPU is known to have SSE4.1.
+    impl Lanes for Sse41 {
+        #[inline(always)]
+        fn splat(value: u32) -> Self {
+            // SAFETY: see above.
+            let lanes = unsafe { _mm_set1_epi32(value as i32) };
+            Self([lanes; 2])
+        }
+
+        #[inline(always)]
+        fn bytes(row: &[u8; LANES]) -> Self {
+            let [a, b, c, d, e, f, g, h] = *row;
+            let low = i32::from_le_bytes([a, b, c, d]);
+            let high = i32::from_le_bytes([e, f, g, h]);
+            // SAFETY: see above.
+            unsafe {
+                Self([
+                    _mm_cvtepu8_epi32(_mm_cvtsi32_si128(low)),
+                    _mm_cvtepu8_epi32(_mm_cvtsi32_si128(high)),
+                ])
+            }
+        }
+
+        #[inline(always)]
+        fn store(self) -> [u32; LANES] {
+            let mut lanes = [0; LANES];
+            // SAFETY: see above, and `lanes` is 32 bytes long.
+            unsafe {
+                _mm_storeu_si128(lanes.as_mut_ptr().cast(), self.0[0]);
+                _mm_storeu_si128(lanes[4..].as_mut_ptr().cast(), self.0[1]);
+            }
+            lanes
+        }
+
+        #[inline(always)]
+        fn xor(self, other: Self) -> Self {
+            // SAFETY: see above.
+            unsafe {
+                Self([
+                    _mm_xor_si128(self.0[0], other.0[0]),
+                    _mm_xor_si128(self.0[1], other.0[1]),
+                ])
+            }
+        }
+
+        #[inline(always)]
+        fn or(self, other: Self) -> Self {
+            // SAFETY: see above.
+            unsafe {
+                Self([
+                    _mm_or_si128(self.0[0], other.0[0]),
+                    _mm_or_si128(self.0[1], other.0[1]),
+                ])
+            }
+        }
+
+        #[inline(always)]
+        fn add(self, other: Self) -> Self {
+            // SAFETY: see above.
+            unsafe {
+                Self([
+                    _mm_add_epi32(self.0[0], other.0[0]),
+                    _mm_add_epi32(self.0[1], other.0[1]),
+                ])
+            }
+        }
+
+        #[inline(always)]
+        fn mul(self, other: Self) -> Self {
+            // SAFETY: see above.
+            unsafe {
+                Self([
+                    _mm_mullo_epi32(self.0[0], other.0[0]),
+                    _mm_mullo_epi32(self.0[1], other.0[1]),
+                ])
+            }
+        }
+
+        #[inline(always)]
+        fn shl(self, bits: u32) -> Self {
+            // SAFETY: see above.
+            unsafe {
+                let bits = _mm_cvtsi32_si128(bits as i32);
+                Self([
+                    _mm_sll_epi32(self.0[0], bits),
+                    _mm_sll_epi32(self.0[1], bits),
+                ])
+            }
+        }
+
+        #[inline(always)]
+        fn shr(self, bits: u32) -> Self {
+            // SAFETY: see above.
+            unsafe {
+                let bits = _mm_cvtsi32_si128(bits as i32);
+                Self([
+                    _mm_srl_epi32(self.0[0], bits),
+                    _mm_srl_epi32(self.0[1], bits),
+                ])
+            }
+        }
+    }
+}
+
+#[cfg(target_arch = "aarch64")]
+mod arm {
+    use std::arch::aarch64::{
+        uint32x4_t, vaddq_u32, vdupq_n_s32, vdupq_n_u32, veorq_u32, vget_high_u16, vget_low_u16,
+        vld1_u8, vmovl_u8, vmovl_u16, vmulq_u32, vorrq_u32, vshlq_u32, vst1q_u32,
+    };
+    use std::ops::Range;
+
+    use super::{Algorithm, LANES, Lanes, Layout, Mask};
+
+    /// Four lanes in each of two NEON registers.
+    #[derive(Clone, Copy)]
+    pub struct Neon([uint32x4_t; 2]);
+
+    /// Runs [`super::drive`] with NEON.
+    ///
+    /// # Safety
+    /// The CPU must have NEON.
+    #[target_feature(enable = "neon")]
+    pub unsafe fn drive_neon(
+        algorithm: Algorithm,
+        layout: &Layout,
+        mask: &Mask,
+        range: Range<u64>,
+        each: impl FnMut(u64, &[u32]),
+    ) {
+        super::drive::<Neon>(algorithm, layout, mask, range, each);
+    }
+
+    // SAFETY (for every block below): `Neon` is only used inside
+    // `drive_neon`, which is only called once the CPU is known to have NEON.
+    impl Lanes for Neon {
+        #[inline(always)]
+        fn splat(value: u32) -> Self {
+            // SAFETY: see above.
+            let lanes = unsafe { vdupq_n_u32(value) };
+            Self([lanes; 2])
+        }
+
+        #[inline(always)]
+        fn bytes(row: &[u8; LANES]) -> Self {
+            // SAFETY: see above, and `row` is eight bytes long.
+            unsafe {
+                let wide = vmovl_u8(vld1_u8(row.as_ptr()));
+                Self([
+                    vmovl_u16(vget_low_u16(wide)),
+                    vmovl_u16(vget_high_u16(wide)),
+                ])
+            }
+        }
+
+        #[inline(always)]
+        fn store(self) -> [u32; LANES] {
+            let mut lanes = [0; LANES];
+            // SAFETY: see above, and `lanes` is 32 bytes long.
+            unsafe {
+                vst1q_u32(lanes.as_mut_ptr(), self.0[0]);
+                vst1q_u32(lanes[4..].as_mut_ptr(), self.0[1]);
+            }
+            lanes
+        }
+
+        #[inline(always)]
+        fn xor(self, other: Self) -> Self {
+            // SAFETY: see above.
+            unsafe {
+                Self([
+                    veorq_u32(self.0[0], other.0[0]),
+                    veorq_u32(self.0[1], other.0[1]),
+                ])
+            }
+        }
+
+        #[inline(always)]
+        fn or(self, other: Self) -> Self {
+            // SAFETY: see above.
+            unsafe {
+                Self([
+                    vorrq_u32(self.0[0], other.0[0]),
+                    vorrq_u32(self.0[1], other.0[1]),
+                ])
+            }
+        }
+
+        #[inline(always)]
+        fn add(self, other: Self) -> Self {
+            // SAFETY: see above.
+            unsafe {
+                Self([
+                    vaddq_u32(self.0[0], other.0[0]),
+                    vaddq_u32(self.0[1], other.0[1]),
+                ])
+            }
+        }
+
+        #[inline(always)]
+        fn mul(self, other: Self) -> Self {
+            // SAFETY: see above.
+            unsafe {
+                Self([
+                    vmulq_u32(self.0[0], other.0[0]),
+                    vmulq_u32(self.0[1], other.0[1]),
+                ])
+            }
+        }
+
+        #[inline(always)]
+        fn shl(self, bits: u32) -> Self {
+            // SAFETY: see above.
+            unsafe {
+                let bits = vdupq_n_s32(bits as i32);
+                Self([vshlq_u32(self.0[0], bits), vshlq_u32(self.0[1], bits)])
+            }
+        }
+
+        #[inline(always)]
+        fn shr(self, bits: u32) -> Self {
+            // A negative shift moves right.
+            // SAFETY: see above.
+            unsafe {
+                let bits = vdupq_n_s32(-(bits as i32));
+                Self([vshlq_u32(self.0[0], bits), vshlq_u32(self.0[1], bits)])
+            }
+        }
+    }
+}
+
+/// Checks the candidates of a [`Mask`] for a [`Search`] with a [`Kernel`].
+pub struct Scan<'a> {
+    search: &'a Search<'a>,
+    kernel: Kernel,
+    mask: &'a Mask,
+    layout: Layout,
+}
+
+impl<'a> Scan<'a> {
+    /// The scan of `mask` for `search`, if its hasher has a kernel.
+    pub fn new(search: &'a Search<'a>, mask: &'a Mask) -> Option<Self> {
+        let kernel = Kernel::new(search.registry, search.hasher, search.options)?;
+        let layout = Layout::new(kernel.algorithm, &search.affixes, mask);
+        Some(Self {
+            search,
+            kernel,
+            mask,
+            layout,
+        })
+    }
+
+    pub fn kernel(&self) -> Kernel {
+        self.kernel
+    }
+
+    /// The same scan on another instruction set, if this CPU has it.
+    pub fn with_isa(self, isa: Isa) -> Option<Self> {
+        let kernel = self.kernel.with_isa(isa)?;
+        Some(Self { kernel, ..self })
+    }
+
+    /// Adds the matches among candidates `range` to `found`, in order.
+    pub fn run(&self, range: Range<u64>, found: &mut Vec<Match>) {
+        let targets = &self.search.targets;
+        let mut candidate = Vec::new();
+        self.kernel
+            .drive(&self.layout, self.mask, range, |first, hashes| {
+                for (index, hash) in (first..).zip(hashes) {
+                    let hash = hash.to_be_bytes();
+                    if targets.contains(&hash) {
+                        self.mask.candidate(index, &mut candidate);
+                        let input = self.search.affixes.wrap(&candidate);
+                        found.push(self.search.found(input, hash.to_vec()));
+                    }
+                }
+            });
+    }
+}
@@ -0,0 +1,310 @@
+//! Candidates generated from names already recovered, most likely first.
+//!
+//! A [`Markov`] model learns, from known names, how likely each byte is after
+//! the few before it, and how likely each length is. Every probability `p` is
+//! turned into a whole cost of `⌊-log2 p⌋`, capped at [`MAX_LEVEL`], so a name's
+//! cost is about how many bits of surprise it holds, and a [`Likely`] keyspace
+//! lists every name the model can spell in order of cost, cheapest first.
+//!
+//! Counting how many names of each length and cost continue from each context
+//! lets the keyspace turn an index straight into its candidate, like any other
+//! [`Keyspace`], so a search can split it between threads and resume it.
+
+use std::collections::{BTreeMap, HashMap};
+
+use super::Keyspace;
+
+/// The highest cost of one step, for anything rarer than one in `2^MAX_LEVEL`.
+pub const MAX_LEVEL: u32 = 10;
+
+/// The symbol before the start of a name, padding the first contexts.
+const BOUNDARY: u8 = 0;
+
+/// A byte that can follow a context, and what it costs.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+struct Transition {
+    byte: u8,
+    cost: u32,
+    /// The context after the byte.
+    next: usize,
+}
+
+/// A character-level Markov model of names.
+///
+/// # Examples
+/// ```
+/// use atlas::search::{Keyspace as _, Markov};
+///
+/// let names = ["npc_vendor_idle", "npc_guard_idle", "npc_guard_alert"];
+/// let model = Markov::train(names.iter().map(|name| name.as_bytes()), 3);
+/// assert!(model.cost(b"npc_guard_idle").is_some());
+/// assert!(model.cost(b"xyz").is_none());
+///
+/// let likely = model.likely(1, 16, 12);
+/// let mut input = Vec::new();
+/// likely.candidate(0, &mut input);
+/// assert!(model.cost(&input) <= model.cost(b"npc_guard_idle"));
+/// ```
+#[derive(Clone, Debug)]
+pub struct Markov {
+    /// How many bytes before one it depends on.
+    context_len: usize,
+    /// The transitions out of each context, cheapest first.
+    transitions: Vec<Vec<Transition>>,
+    /// Contexts by the bytes in them, [`BOUNDARY`] before the start.
+    contexts: HashMap<Vec<u8>, usize>,
+    /// The cost of each length of name seen.
+    lengths: Vec<u32>,
+}
+
+impl Markov {
+    /// Learns from `names` how likely each byte is after the `order - 1`
+    /// before it, with `order` at least 1.
+    ///
+    /// Names with a zero byte are skipped, as that's what pads the start.
+    pub fn train<'a>(names: impl IntoIterator<Item = &'a [u8]>, order: usize) -> Self {
+        let context_len = order.max(1) - 1;
+        // Ordered, so contexts are numbered the same way every time.
+        let mut counts: BTreeMap<Vec<u8>, BTreeMap<u8, u64>> = BTreeMap::new();
+        let mut lengths = Vec::new();
+        for name in names {
+            if name.is_empty() || name.contains(&BOUNDARY) {
+                continue;
+            }
+            if lengths.len() <= name.len() {
+                lengths.resize(name.len() + 1, 0);
+            }
+            lengths[name.len()] += 1;
+            let padded: Vec<u8> = std::iter::repeat_n(BOUNDARY, context_len)
+                .chain(name.iter().copied())
+                .collect();
+            for gram in padded.windows(context_len + 1) {
+                let (context, byte) = gram.split_at(context_len);
+                *counts
+                    .entry(context.to_vec())
+                    .or_default()
+                    .entry(byte[0])
+                    .or_default() += 1;
+            }
+        }
+
+        let mut model = Self {
+            context_len,
+            transitions: Vec::new(),
+            contexts: HashMap::new(),
+            lengths: Vec::new(),
+        };
+        let total: u64 = lengths.iter().sum();
+        model.lengths = lengths.iter().map(|count| level(*count, total)).collect();
+        for (context, bytes) in counts {
+            let id = model.context(&context);
+            let total = bytes.values().sum();
+            let mut transitions: Vec<_> = bytes
+                .into_iter()
+                .map(|(byte, count)| {
+                    let next = [&context[..], &[byte]].concat();
+                    Transition {
+                        byte,
+                        cost: level(count, total),
+                        next: model.context(&next[1..]),
+                    }
+                })
+                .collect();
+            transitions.sort_unstable_by_key(|transition| (transition.cost, transition.byte));
+            model.transitions[id] = transitions;
+        }
+        model
+    }
+
+    /// The id of `context`, adding it if it's new.
+    fn context(&mut self, context: &[u8]) -> usize {
+        let next = self.contexts.len();
+        let id = *self.contexts.entry(context.to_vec()).or_insert(next);
+        if id == next {
+            self.transitions.push(Vec::new());
+        }
+        id
+    }
+
+    /// The context before the first byte.
+    fn start(&self) -> Option<usize> {
+        self.contexts
+            .get(&vec![BOUNDARY; self.context_len])
+            .copied()
+    }
+
+    /// What a length costs, [`MAX_LEVEL`] if no name that long was seen.
+    fn length_cost(&self, len: usize) -> u32 {
+        self.lengths.get(len).copied().unwrap_or(MAX_LEVEL)
+    }
+
+    /// The length of the longest name it learned from.
+    pub fn max_len(&self) -> usize {
+        self.lengths.len().saturating_sub(1)
+    }
+
+    /// How unlikely `name` is to the model, or `None` if it has a byte the
+    /// model never saw after the ones before it.
+    pub fn cost(&self, name: &[u8]) -> Option<u32> {
+        let mut context = self.start()?;
+        let mut cost = self.length_cost(name.len());
+        for byte in name {
+            let transition = self.transitions[context]
+                .iter()
+                .find(|transition| transition.byte == *byte)?;
+            cost += transition.cost;
+            context = transition.next;
+        }
+        Some(cost)
+    }
+
+    /// Every name of `min_len` to `max_len` bytes that costs at most
+    /// `max_cost`, cheapest first.
+    pub fn likely(&self, min_len: usize, max_len: usize, max_cost: u32) -> Likely {
+        Likely::new(self.clone(), min_len, max_len, max_cost)
+    }
+}
+
+/// The cost of something seen `count` times out of `total`.
+fn level(count: u64, total: u64) -> u32 {
+    if count == 0 {
+        return MAX_LEVEL;
+    }
+    // `⌊log2(total / count)⌋`, from the leading bits of both.
+    let mut level = total.ilog2().saturating_sub(count.ilog2());
+    if count << level > total {
+        level -= 1;
+    }
+    level.min(MAX_LEVEL)
+}
+
+/// The names a [`Markov`] model can spell, cheapest first.
+///
+/// Names of the same cost are listed shortest first, and the keyspace stops
+/// short of a cost whose names would take it past `u64::MAX`.
+#[derive(Clone, Debug)]
+pub struct Likely {
+    model: Markov,
+    max_cost: u32,
+    /// How many ways there are to spell `len` more bytes from a context for
+    /// exactly a cost, by `len`, then context, then cost.
+    counts: Vec<u64>,
+    /// The cost and length of each run of candidates, and the index of its
+    /// first, in order.
+    runs: Vec<Run>,
+    size: u64,
+}
+
+#[derive(Clone, Copy, Debug)]
+struct Run {
+    first: u64,
+    len: usize,
+    /// The cost of the bytes, without the length.
+    cost: u32,
+}
+
+impl Likely {
+    fn new(model: Markov, min_len: usize, max_len: usize, max_cost: u32) -> Self {
+        let contexts = model.transitions.len();
+        let costs = max_cost as usize + 1;
+        let mut counts = vec![0u64; (max_len + 1) * contexts * costs];
+        for context in 0..contexts {
+            counts[context * costs] = 1;
+        }
+        for len in 1..=max_len {
+            for (context, transitions) in model.transitions.iter().enumerate() {
+                for cost in 0..costs {
+                    let count = transitions
+                        .iter()
+                        .take_while(|transition| transition.cost as usize <= cost)
+                        .map(|transition| {
+                            let rest = cost - transition.cost as usize;
+                            counts[((len - 1) * contexts + transition.next) * costs + rest]
+                        })
+                        .fold(0u64, u64::saturating_add);
+                    counts[(len * contexts + context) * costs + cost] = count;
+                }
+            }
+        }
+
+        let mut likely = Self {
+            model,
+            max_cost,
+            counts,
+            runs: Vec::new(),
+            size: 0,
+        };
+        let Some(start) = likely.model.start() else {
+            return likely;
+        };
+        'costs: for total in 0..=max_cost {
+            for len in min_len..=max_len {
+                let Some(cost) = total.checked_sub(likely.model.length_cost(len)) else {
+                    continue;
+                };
+                let count = likely.count(len, start, cost);
+                if count == 0 {
+                    continue;
+                }
+                let Some(size) = likely
+                    .size
+                    .checked_add(count)
+                    .filter(|size| *size < u64::MAX)
+                else {
+                    break 'costs;
+                };
+                likely.runs.push(Run {
+                    first: likely.size,
+                    len,
+                    cost,
+                });
+                likely.size = size;
+            }
+        }
+        likely
+    }
+
+    fn count(&self, len: usize, context: usize, cost: u32) -> u64 {
+        let contexts = self.model.transitions.len();
+        let costs = self.max_cost as usize + 1;
+        self.counts[(len * contexts + context) * costs + cost as usize]
+    }
+
+    /// The cost of candidate `index`, which is less than [`Keyspace::size`].
+    pub fn cost_of(&self, index: u64) -> u32 {
+        let run = self.runs[self.runs.partition_point(|run| run.first <= index) - 1];
+        run.cost + self.model.length_cost(run.len)
+    }
+}
+
+impl Keyspace for Likely {
+    fn size(&self) -> u64 {
+        self.size
+    }
+
+    fn candidate(&self, index: u64, input: &mut Vec<u8>) {
+        input.clear();
+        let run = self.runs[self.runs.partition_point(|run| run.first <= index) - 1];
+        let Some(mut context) = self.model.start() else {
+            return;
+        };
+        // Walk down the counts, skipping the transitions that come before.
+        let mut index = index - run.first;
+        let mut cost = run.cost;
+        for left in (0..run.len).rev() {
+            for transition in &self.model.transitions[context] {
+                let Some(rest) = cost.checked_sub(transition.cost) else {
+                    break;
+                };
+                let count = self.count(left, transition.next, rest);
+                if index < count {
+                    input.push(transition.byte);
+                    context = transition.next;
+                    cost = rest;
+                    break;
+                }
+                index -= count;
+            }
+        }
+    }
+}
@@ -0,0 +1,301 @@
+//! Searching for inputs that hash to known values.
+//!
+//! A [`Search`] hashes candidates from a [`BruteForce`] or [`Dictionary`]
+//! between a fixed prefix and suffix, and keeps those that hash to one of its
+//! targets. Hashers with a [`Stream`] hash the prefix once and, where they
+//! can run backwards, peel the suffix off the targets instead of hashing it.
+//! Candidates of a [`Mask`] are hashed eight at a time by a [`kernel`] where
+//! the hasher has one, and a [`Markov`] model trained on known names lists
+//! the names most like them first. A [`Combinator`] joins tokens from several
+//! lists, and hashers with a [`Stream`] hash each token once for all the
+//! candidates it starts.
+
+pub mod candidates;
+pub mod engine;
+pub mod kernel;
+pub mod markov;
+pub mod stream;
+pub mod targets;
+
+use std::ops::Range;
+use std::sync::Arc;
+
+use serde::{Deserialize, Serialize};
+
+pub use candidates::{BruteForce, Combinator, Dictionary, Keyspace, Mask, Slot};
+pub use markov::{Likely, Markov};
+pub use stream::Stream;
+pub use targets::Targets;
+
+use crate::registry::Batch;
+use crate::{HashError, HashOptions, Registry};
+
+/// Bytes every candidate is wrapped in before hashing.
+#[derive(Clone, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Affixes {
+    pub prefix: Vec<u8>,
+    pub suffix: Vec<u8>,
+}
+
+impl Affixes {
+    /// The whole input for `candidate`.
+    pub fn wrap(&self, candidate: &[u8]) -> Vec<u8> {
+        [&self.prefix[..], candidate, &self.suffix].concat()
+    }
+}
+
+/// An input that hashed to a target.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Match {
+    /// The whole input, with the prefix and suffix.
+    pub input: Vec<u8>,
+    pub hash: Vec<u8>,
+    /// The tags of the target lists the hash is in.
+    #[serde(default)]
+    pub tags: Vec<String>,
+}
+
+/// Looks for inputs that hash to any of a set of targets.
+///
+/// # Examples
+/// ```no_run
+/// use atlas::search::{Dictionary, Search};
+/// use atlas::{HashOptions, Registry};
+///
+/// let mut registry = Registry::new();
+/// registry.load_dir(Registry::default_plugin_dir()?)?;
+///
+/// let options = HashOptions::default();
+/// let target = hex::decode("1F7C3C8D")?;
+/// let words = Dictionary::parse("npc_vendor_idle_01\nnpc_guard_alert_02\n");
+/// let found = Search::new(&registry, "FNV-1a_32", &options, [target])
+///     .with_affixes(b"sound/vo/", b".wem")
+///     .run(words.iter())?;
+/// for found in found {
+///     println!("{}", String::from_utf8_lossy(&found.input));
+/// }
+/// # Ok::<(), anyhow::Error>(())
+/// ```
+pub struct Search<'a> {
+    registry: &'a Registry,
+    hasher: &'a str,
+    options: &'a HashOptions,
+    affixes: Affixes,
+    targets: Arc<Targets>,
+}
+
+impl<'a> Search<'a> {
+    pub fn new(
+        registry: &'a Registry,
+        hasher: &'a str,
+        options: &'a HashOptions,
+        targets: impl IntoIterator<Item = Vec<u8>>,
+    ) -> Self {
+        Self {
+            registry,
+            hasher,
+            options,
+            affixes: Affixes::default(),
+            targets: Arc::new(targets.into_iter().collect()),
+        }
+    }
+
+    /// Looks for `targets` instead, which may come from several tagged lists.
+    pub fn with_targets(mut self, targets: Arc<Targets>) -> Self {
+        self.targets = targets;
+        self
+    }
+
+    pub fn with_affixes(mut self, prefix: &[u8], suffix: &[u8]) -> Self {
+        self.affixes = Affixes {
+            prefix: prefix.to_vec(),
+            suffix: suffix.to_vec(),
+        };
+        self
+    }
+
+    pub fn affixes(&self) -> &Affixes {
+        &self.affixes
+    }
+
+    /// Hashes every candidate, returning those that hit a target in order.
+    pub fn run(
+        &self,
+        candidates: impl IntoIterator<Item = Vec<u8>>,
+    ) -> Result<Vec<Match>, HashError> {
+        let matcher = self.matcher();
+        let mut found = Vec::new();
+        for candidate in candidates {
+            found.extend(matcher.check(&candidate)?);
+        }
+        Ok(found)
+    }
+
+    /// The match of `input`, whose `hash` is a target.
+    fn found(&self, input: Vec<u8>, hash: Vec<u8>) -> Match {
+        Match {
+            tags: self.targets.tags(&hash),
+            input,
+            hash,
+        }
+    }
+
+    /// A checker for single candidates, with the prefix already hashed if
+    /// the hasher has a [`Stream`].
+    pub fn matcher(&self) -> Matcher<'_> {
+        let native = Stream::new(self.registry, self.hasher, self.options).map(|stream| {
+            let start = stream.update(stream.start(), &self.affixes.prefix);
+            // Hashers that can't run backwards hash the suffix of every candidate.
+            let peeled = stream.runs_backwards().then(|| {
+                self.targets.map(|target| {
+                    let state = stream.rewind(stream.from_bytes(target)?, &self.affixes.suffix)?;
+                    Some(stream.to_bytes(state))
+                })
+            });
+            Native {
+                stream,
+                start,
+                peeled,
+            }
+        });
+        Matcher {
+            search: self,
+            native,
+        }
+    }
+}
+
+/// A hasher run on the host, from the state after the prefix.
+struct Native {
+    stream: Stream,
+    start: u64,
+    /// The targets with the suffix rewound, if the hasher runs backwards.
+    peeled: Option<Targets>,
+}
+
+impl Native {
+    /// The hash of the input `state` is after once the suffix is hashed, if
+    /// it's one of `targets`.
+    fn matches(&self, targets: &Targets, suffix: &[u8], state: u64) -> Option<Vec<u8>> {
+        let stream = self.stream;
+        if let Some(peeled) = &self.peeled {
+            return peeled
+                .contains(&stream.to_bytes(state))
+                .then(|| stream.to_bytes(stream.update(state, suffix)));
+        }
+        let hash = stream.to_bytes(stream.update(state, suffix));
+        targets.contains(&hash).then_some(hash)
+    }
+}
+
+/// Checks candidates for a [`Search`], one at a time.
+///
+/// It can be shared between threads, each checking their own candidates.
+pub struct Matcher<'a> {
+    search: &'a Search<'a>,
+    native: Option<Native>,
+}
+
+impl Matcher<'_> {
+    /// The match for `candidate`, if it hashes to a target.
+    pub fn check(&self, candidate: &[u8]) -> Result<Option<Match>, HashError> {
+        let search = self.search;
+        if let Some(native) = &self.native {
+            let state = native.stream.update(native.start, candidate);
+            let hash = native.matches(&search.targets, &search.affixes.suffix, state);
+            return Ok(hash.map(|hash| search.found(search.affixes.wrap(candidate), hash)));
+        }
+
+        let input = search.affixes.wrap(candidate);
+        let hash = search
+            .registry
+            .hash(search.hasher, &input, search.options)?;
+        let hash = hex::decode(hash.0).unwrap_or_default();
+        Ok(search
+            .targets
+            .contains(&hash)
+            .then(|| search.found(input, hash)))
+    }
+
+    /// Whether candidates are hashed on the host, from a [`Stream`].
+    pub fn is_streamed(&self) -> bool {
+        self.native.is_some()
+    }
+
+    /// The matches among candidates `range` of `combinator`, in order.
+    ///
+    /// On the host, the state after each slot is kept, so only the slots that
+    /// change from one candidate to the next are hashed again.
+    pub fn check_combinations(
+        &self,
+        combinator: &Combinator,
+        range: Range<u64>,
+    ) -> Result<Vec<Match>, HashError> {
+        let mut found = Vec::new();
+        let mut candidate = Vec::new();
+        let Some(native) = &self.native else {
+            for index in range {
+                combinator.candidate(index, &mut candidate);
+                found.extend(self.check(&candidate)?);
+            }
+            return Ok(found);
+        };
+
+        let search = self.search;
+        let stream = native.stream;
+        let slots = combinator.slots();
+        let mut digits = Vec::new();
+        combinator.digits(range.start, &mut digits);
+        // The state after each slot, and where its token ends in the candidate.
+        let mut states = vec![native.start; slots.len() + 1];
+        let mut ends = vec![0; slots.len() + 1];
+        let mut changed = 0;
+        for _ in range {
+            candidate.truncate(ends[changed]);
+            for slot in changed..slots.len() {
+                slots[slot].push(digits[slot], &mut candidate);
+                ends[slot + 1] = candidate.len();
+                states[slot + 1] = stream.update(states[slot], &candidate[ends[slot]..]);
+            }
+            let state = states[slots.len()];
+            if let Some(hash) = native.matches(&search.targets, &search.affixes.suffix, state) {
+                found.push(search.found(search.affixes.wrap(&candidate), hash));
+            }
+            changed = combinator.advance(&mut digits);
+        }
+        Ok(found)
+    }
+
+    /// The matches among `candidates`, in order.
+    ///
+    /// Hashers that aren't hashed on the host hash every candidate in one
+    /// [`Registry::hash_batch`] call, wrapping them into `scratch`, which is
+    /// there to be reused across calls.
+    pub fn check_batch(
+        &self,
+        candidates: &Batch,
+        scratch: &mut Batch,
+    ) -> Result<Vec<Match>, HashError> {
+        let search = self.search;
+        if self.native.is_some() {
+            let mut found = Vec::new();
+            for i in 0..candidates.len() {
+                found.extend(self.check(candidates.input(i))?);
+            }
+            return Ok(found);
+        }
+
+        scratch.clear();
+        let Affixes { prefix, suffix } = &search.affixes;
+        for i in 0..candidates.len() {
+            scratch.push(&[prefix, candidates.input(i), suffix]);
+        }
+        search
+            .registry
+            .hash_batch(search.hasher, scratch, search.options)?;
+        Ok((0..scratch.len())
+            .filter(|&i| search.targets.contains(scratch.hash(i)))
+            .map(|i| search.found(scratch.input(i).to_vec(), scratch.hash(i).to_vec()))
+            .collect())
+    }
+}
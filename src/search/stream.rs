@@ -0,0 +1,216 @@
+//! Hashers the host can run byte by byte, to cache their state after a prefix.
+//!
+//! Plugins only hash whole inputs, so a search can't ask them for the state
+//! after a fixed prefix. For hashers whose whole state fits in a word, the
+//! host runs them itself instead: the prefix is hashed once, and each
+//! candidate continues from there. Most can also run backwards, which peels a
+//! fixed suffix off the targets so it never has to be hashed.
+//!
+//! The host knows FNV-0, FNV-1 and FNV-1a of 32 and 64 bits, djb2, djb2a,
+//! sdbm, BKDR and CRC-32. `Java_hashCode` isn't among them, as it hashes the
+//! UTF-16 of the input rather than its bytes.
+
+use crate::{HashOptions, Registry};
+
+/// What one byte of input does to the state.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+enum Step {
+    /// Multiply by the prime, then xor in the byte, as FNV-0, FNV-1 and djb2a do.
+    MultiplyXor,
+    /// Xor in the byte, then multiply by the prime, as FNV-1a does.
+    XorMultiply,
+    /// Multiply by the prime, then add the byte, as djb2, sdbm and BKDR do.
+    MultiplyAdd,
+    /// The reflected CRC-32 of zlib, a byte at a time from [`CRC32_TABLE`].
+    Crc32,
+}
+
+/// The reflected CRC-32 polynomial.
+const CRC32_POLY: u32 = 0xedb88320;
+
+/// What each byte does to the reflected CRC-32 register.
+const CRC32_TABLE: [u32; 256] = {
+    let mut table = [0; 256];
+    let mut i = 0;
+    while i < 256 {
+        let mut crc = i as u32;
+        let mut bit = 0;
+        while bit < 8 {
+            crc = if crc & 1 == 1 {
+                (crc >> 1) ^ CRC32_POLY
+            } else {
+                crc >> 1
+            };
+            bit += 1;
+        }
+        table[i] = crc;
+        i += 1;
+    }
+    table
+};
+
+/// The index of each [`CRC32_TABLE`] entry by its top byte, which differs
+/// for every entry, to run CRC-32 backwards.
+const CRC32_INDEX: [u8; 256] = {
+    let mut index = [0; 256];
+    let mut i = 0;
+    while i < 256 {
+        index[(CRC32_TABLE[i] >> 24) as usize] = i as u8;
+        i += 1;
+    }
+    index
+};
+
+/// A hasher the host runs itself, with its state as a word.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct Stream {
+    step: Step,
+    bits: u32,
+    basis: u64,
+    prime: u64,
+    /// What the state is xored with to give the hash.
+    xorout: u64,
+}
+
+impl Stream {
+    /// The stream for the hasher registered as `name`, if the host knows it.
+    ///
+    /// The registered hasher is checked against the host's version, in case a
+    /// plugin registered something else under the same name.
+    pub fn new(registry: &Registry, name: &str, options: &HashOptions) -> Option<Self> {
+        const FNV32: (u64, u64) = (0x811c9dc5, 0x01000193);
+        const FNV64: (u64, u64) = (0xcbf29ce484222325, 0x100000001b3);
+        let (step, bits, basis, prime) = match name {
+            "FNV-0_32" => (Step::MultiplyXor, 32, 0, FNV32.1),
+            "FNV-0_64" => (Step::MultiplyXor, 64, 0, FNV64.1),
+            "FNV-1_32" => (Step::MultiplyXor, 32, FNV32.0, FNV32.1),
+            "FNV-1_64" => (Step::MultiplyXor, 64, FNV64.0, FNV64.1),
+            "FNV-1a_32" => (Step::XorMultiply, 32, FNV32.0, FNV32.1),
+            "FNV-1a_64" => (Step::XorMultiply, 64, FNV64.0, FNV64.1),
+            "djb2" => (Step::MultiplyAdd, 32, 5381, 33),
+            "djb2a" => (Step::MultiplyXor, 32, 5381, 33),
+            // `(hash << 6) + (hash << 16) - hash` is `hash * 65599`.
+            "sdbm" => (Step::MultiplyAdd, 32, 0, 65599),
+            // The seed is the multiplier, with `0` picking the usual `131`.
+            "BKDR" => match options.seed.unwrap_or(0) {
+                0 => (Step::MultiplyAdd, 32, 0, 131),
+                seed => (
+                    Step::MultiplyAdd,
+                    32,
+                    0,
+                    u64::from(u32::try_from(seed).ok()?),
+                ),
+            },
+            "CRC-32" => (Step::Crc32, 32, 0xffffffff, 0),
+            _ => return None,
+        };
+        let stream = Self {
+            step,
+            bits,
+            basis,
+            prime,
+            xorout: if step == Step::Crc32 { 0xffffffff } else { 0 },
+        };
+        let sample = b"sound/vo/npc_vendor_idle_01.wem";
+        let hash = registry.hash(name, sample, options).ok()?;
+        (hex::decode(hash.0).ok()? == stream.to_bytes(stream.update(stream.start(), sample)))
+            .then_some(stream)
+    }
+
+    fn mask(&self) -> u64 {
+        u64::MAX >> (64 - self.bits)
+    }
+
+    /// The state before any input.
+    pub fn start(&self) -> u64 {
+        self.basis
+    }
+
+    /// The state after `bytes` more input.
+    pub fn update(&self, state: u64, bytes: &[u8]) -> u64 {
+        let mask = self.mask();
+        bytes.iter().fold(state, |hash, byte| {
+            let byte = u64::from(*byte);
+            match self.step {
+                Step::MultiplyXor => (hash.wrapping_mul(self.prime) & mask) ^ byte,
+                Step::XorMultiply => (hash ^ byte).wrapping_mul(self.prime) & mask,
+                Step::MultiplyAdd => hash.wrapping_mul(self.prime).wrapping_add(byte) & mask,
+                Step::Crc32 => {
+                    (hash >> 8) ^ u64::from(CRC32_TABLE[((hash ^ byte) & 0xff) as usize])
+                }
+            }
+        })
+    }
+
+    /// The state that [`Self::update`] turns into `state` after `bytes`, or
+    /// `None` if the hasher can't run backwards, such as BKDR with an even
+    /// multiplier.
+    ///
+    /// # Examples
+    /// ```
+    /// use atlas::search::Stream;
+    /// # fn check(stream: Stream) {
+    /// let state = stream.update(stream.start(), b"npc_vendor");
+    /// let end = stream.update(state, b".wem");
+    /// assert_eq!(stream.rewind(end, b".wem"), Some(state));
+    /// # }
+    /// ```
+    pub fn rewind(&self, state: u64, bytes: &[u8]) -> Option<u64> {
+        let mask = self.mask();
+        let inverse = match self.step {
+            Step::Crc32 => 0,
+            _ if self.prime % 2 == 0 => return None,
+            // The prime is odd, so it has an inverse modulo any power of two.
+            _ => (0..6).fold(self.prime, |x, _| {
+                x.wrapping_mul(2u64.wrapping_sub(self.prime.wrapping_mul(x)))
+            }),
+        };
+        Some(bytes.iter().rev().fold(state, |hash, byte| {
+            let byte = u64::from(*byte);
+            match self.step {
+                Step::MultiplyXor => (hash ^ byte).wrapping_mul(inverse) & mask,
+                Step::XorMultiply => (hash.wrapping_mul(inverse) & mask) ^ byte,
+                Step::MultiplyAdd => hash.wrapping_sub(byte).wrapping_mul(inverse) & mask,
+                // The top byte of the register comes from the table alone,
+                // which gives the entry, and so the byte shifted out.
+                Step::Crc32 => {
+                    let i = CRC32_INDEX[(hash >> 24) as usize];
+                    let shifted = hash ^ u64::from(CRC32_TABLE[usize::from(i)]);
+                    ((shifted << 8) & mask) | (u64::from(i) ^ byte)
+                }
+            }
+        }))
+    }
+
+    /// Whether [`Self::rewind`] can run the hasher backwards.
+    pub fn runs_backwards(&self) -> bool {
+        self.step == Step::Crc32 || self.prime % 2 == 1
+    }
+
+    /// Whether the low bits of the state depend on nothing but the low bits
+    /// before them and the input, so states that agree in their low bits
+    /// still do after more input. True of all but CRC-32, whose register
+    /// shifts its high bits down.
+    pub fn truncates(&self) -> bool {
+        self.step != Step::Crc32
+    }
+
+    /// The hash a state stands for, as an integer.
+    pub fn value(&self, state: u64) -> u64 {
+        state ^ self.xorout
+    }
+
+    /// Reads a hash as the hasher returns it into a state.
+    pub fn from_bytes(&self, hash: &[u8]) -> Option<u64> {
+        (hash.len() * 8 == self.bits as usize).then(|| {
+            hash.iter()
+                .fold(0, |state, byte| (state << 8) | u64::from(*byte))
+                ^ self.xorout
+        })
+    }
+
+    /// A state as the hasher would return it.
+    pub fn to_bytes(&self, state: u64) -> Vec<u8> {
+        self.value(state).to_be_bytes()[8 - self.bits as usize / 8..].to_vec()
+    }
+}
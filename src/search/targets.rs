@@ -0,0 +1,369 @@
+//! The hashes a search looks for, from one or more tagged lists.
+//!
+//! A dump can hold hundreds of thousands of unknown hashes, and every
+//! candidate's hash is looked up among them. Hashes are uniformly distributed,
+//! so [`Targets`] keeps them as sorted integers and finds one by interpolating
+//! where it should be, which takes a couple of probes where a binary search
+//! takes seventeen. An optional Bloom filter rejects most misses before that.
+
+use std::path::Path;
+
+/// How many interpolation steps a lookup takes before it bisects, in case
+/// the hashes aren't as uniform as they should be.
+const INTERPOLATION_STEPS: usize = 4;
+
+/// Bits in a Bloom filter per hash, for about one false positive in a hundred.
+const BLOOM_BITS_PER_HASH: usize = 10;
+const BLOOM_PROBES: u32 = 7;
+
+/// Target hashes from any number of lists, each with a tag saying where it
+/// came from, such as the file it was read from.
+///
+/// # Examples
+/// ```
+/// use atlas::search::Targets;
+///
+/// let vo = Targets::parse_list("0x1F7C3C8D\n9E5A21B0\n")?;
+/// let sfx = Targets::parse_list("# footsteps\n9e5a21b0\n")?;
+/// let targets = Targets::from_lists([("vo.txt", vo), ("sfx.txt", sfx)]).with_bloom();
+/// assert!(targets.contains(&hex::decode("1F7C3C8D")?));
+/// assert_eq!(targets.tags(&hex::decode("9E5A21B0")?), ["vo.txt", "sfx.txt"]);
+/// assert!(!targets.contains(&hex::decode("00000000")?));
+/// # Ok::<(), anyhow::Error>(())
+/// ```
+#[derive(Clone, Debug, Default)]
+pub struct Targets {
+    /// The tag of each list, empty for lists without one.
+    tags: Vec<String>,
+    /// The hashes of each width, narrowest first.
+    groups: Vec<Group>,
+}
+
+impl Targets {
+    /// Targets from lists of hashes and their tags.
+    pub fn from_lists<T: Into<String>>(lists: impl IntoIterator<Item = (T, Vec<Vec<u8>>)>) -> Self {
+        let mut tags = Vec::new();
+        let mut hashes = Vec::new();
+        for (list, (tag, list_hashes)) in lists.into_iter().enumerate() {
+            tags.push(tag.into());
+            hashes.extend(list_hashes.into_iter().map(|hash| (hash, list as u32)));
+        }
+        hashes.sort_unstable_by(|(a, a_list), (b, b_list)| {
+            (a.len(), a, a_list).cmp(&(b.len(), b, b_list))
+        });
+        hashes.dedup();
+        let groups = hashes
+            .chunk_by(|(a, _), (b, _)| a.len() == b.len())
+            .map(Group::new)
+            .collect();
+        Self { tags, groups }
+    }
+
+    /// Reads a list of hashes in hex, one per line, with or without `0x`.
+    ///
+    /// Anything after the hash on its line is ignored, as are blank lines and
+    /// those starting with `#`.
+    pub fn parse_list(text: &str) -> anyhow::Result<Vec<Vec<u8>>> {
+        text.lines()
+            .enumerate()
+            .filter_map(|(number, line)| {
+                let hash = line.split_whitespace().next()?;
+                (!hash.starts_with('#')).then_some((number, hash))
+            })
+            .map(|(number, hash)| {
+                let digits = hash.strip_prefix("0x").unwrap_or(hash);
+                hex::decode(digits)
+                    .map_err(|e| anyhow::anyhow!("Line {}: {hash} is not a hash: {e}", number + 1))
+            })
+            .collect()
+    }
+
+    /// Reads a list of hashes from a file, tagged with its file name.
+    pub fn load_list(path: impl AsRef<Path>) -> anyhow::Result<(String, Vec<Vec<u8>>)> {
+        let path = path.as_ref();
+        let text = std::fs::read_to_string(path)
+            .map_err(|e| anyhow::anyhow!("Failed to read {}: {e}", path.display()))?;
+        let hashes =
+            Self::parse_list(&text).map_err(|e| anyhow::anyhow!("{}: {e}", path.display()))?;
+        let tag = path.file_name().unwrap_or(path.as_os_str());
+        Ok((tag.to_string_lossy().into_owned(), hashes))
+    }
+
+    /// Reads lists of hashes from files, tagging each with its file name.
+    pub fn load<P: AsRef<Path>>(paths: impl IntoIterator<Item = P>) -> anyhow::Result<Self> {
+        let lists = paths
+            .into_iter()
+            .map(Self::load_list)
+            .collect::<anyhow::Result<Vec<_>>>()?;
+        Ok(Self::from_lists(lists))
+    }
+
+    /// The same targets with a Bloom filter in front of each width, which
+    /// rejects most hashes that aren't targets without searching for them.
+    #[must_use]
+    pub fn with_bloom(mut self) -> Self {
+        for group in &mut self.groups {
+            let mut bloom = Bloom::new(group.len());
+            for i in 0..group.len() {
+                bloom.insert(group.keys.get(i));
+            }
+            group.bloom = Some(bloom);
+        }
+        self
+    }
+
+    /// How many hashes there are, counting those in several lists once per list.
+    pub fn len(&self) -> usize {
+        self.groups.iter().map(Group::len).sum()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.groups.is_empty()
+    }
+
+    /// The tag of every list, in the order they were given.
+    pub fn lists(&self) -> &[String] {
+        &self.tags
+    }
+
+    /// Whether `hash` is in any of the lists.
+    pub fn contains(&self, hash: &[u8]) -> bool {
+        self.find(hash).next().is_some()
+    }
+
+    /// The tags of the lists `hash` is in, leaving out lists without one.
+    pub fn tags(&self, hash: &[u8]) -> Vec<String> {
+        self.find(hash)
+            .map(|list| &self.tags[list as usize])
+            .filter(|tag| !tag.is_empty())
+            .cloned()
+            .collect()
+    }
+
+    /// Every target, with the index of the list it's in.
+    pub fn iter(&self) -> impl Iterator<Item = (Vec<u8>, usize)> + '_ {
+        self.groups
+            .iter()
+            .flat_map(|group| (0..group.len()).map(|i| (group.hash(i), group.lists[i] as usize)))
+    }
+
+    /// A digest of the tags and every target, the same from run to run, to
+    /// tell whether a checkpoint was saved searching for these targets.
+    pub fn digest(&self) -> u64 {
+        let mut digest = 0xcbf29ce484222325u64;
+        let mut write = |bytes: &[u8]| {
+            for byte in (bytes.len() as u64).to_le_bytes().iter().chain(bytes) {
+                digest = (digest ^ u64::from(*byte)).wrapping_mul(0x100000001b3);
+            }
+        };
+        for tag in &self.tags {
+            write(tag.as_bytes());
+        }
+        for (hash, list) in self.iter() {
+            write(&hash);
+            write(&(list as u64).to_le_bytes());
+        }
+        digest
+    }
+
+    /// The targets with every hash replaced by `map`, dropping those it
+    /// returns `None` for, in the same lists.
+    pub(crate) fn map(&self, mut map: impl FnMut(&[u8]) -> Option<Vec<u8>>) -> Self {
+        let mut lists = vec![Vec::new(); self.tags.len()];
+        for (hash, list) in self.iter() {
+            lists[list].extend(map(&hash));
+        }
+        let targets = Self::from_lists(self.tags.iter().cloned().zip(lists));
+        if self.groups.iter().any(|group| group.bloom.is_some()) {
+            targets.with_bloom()
+        } else {
+            targets
+        }
+    }
+
+    /// The lists `hash` is in.
+    fn find<'a>(&'a self, hash: &'a [u8]) -> impl Iterator<Item = u32> + 'a {
+        self.groups
+            .iter()
+            .find(|group| group.width == hash.len())
+            .into_iter()
+            .flat_map(move |group| group.find(hash))
+    }
+}
+
+/// One target list, untagged.
+impl FromIterator<Vec<u8>> for Targets {
+    fn from_iter<I: IntoIterator<Item = Vec<u8>>>(hashes: I) -> Self {
+        Self::from_lists([(String::new(), hashes.into_iter().collect())])
+    }
+}
+
+/// The targets of one width, sorted.
+#[derive(Clone, Debug)]
+struct Group {
+    width: usize,
+    /// The first eight bytes of each hash, or all of them if it's shorter.
+    keys: Keys,
+    /// The bytes after the first eight of each hash, for wider hashes.
+    rest: Vec<u8>,
+    /// The list each hash is in, a hash in several lists being repeated.
+    lists: Vec<u32>,
+    bloom: Option<Bloom>,
+}
+
+impl Group {
+    /// A group from hashes of the same width, sorted.
+    fn new(hashes: &[(Vec<u8>, u32)]) -> Self {
+        let width = hashes.first().map_or(0, |(hash, _)| hash.len());
+        let keys = hashes.iter().map(|(hash, _)| key(hash));
+        let keys = if width <= 4 {
+            Keys::Narrow(keys.map(|key| key as u32).collect())
+        } else {
+            Keys::Wide(keys.collect())
+        };
+        Self {
+            width,
+            keys,
+            rest: hashes
+                .iter()
+                .flat_map(|(hash, _)| hash.get(8..).unwrap_or_default())
+                .copied()
+                .collect(),
+            lists: hashes.iter().map(|(_, list)| *list).collect(),
+            bloom: None,
+        }
+    }
+
+    fn len(&self) -> usize {
+        self.lists.len()
+    }
+
+    fn rest(&self, i: usize) -> &[u8] {
+        let len = self.width.saturating_sub(8);
+        &self.rest[i * len..(i + 1) * len]
+    }
+
+    fn hash(&self, i: usize) -> Vec<u8> {
+        let key = self.keys.get(i).to_be_bytes();
+        let mut hash = key[8 - self.width.min(8)..].to_vec();
+        hash.extend_from_slice(self.rest(i));
+        hash
+    }
+
+    /// The lists `hash`, which is `width` bytes long, is in.
+    fn find<'a>(&'a self, hash: &'a [u8]) -> impl Iterator<Item = u32> + 'a {
+        let key = key(hash);
+        let rest = hash.get(8..).unwrap_or_default();
+        let start = if self.bloom.as_ref().is_none_or(|bloom| bloom.contains(key)) {
+            self.keys.lower_bound(key)
+        } else {
+            self.len()
+        };
+        (start..self.len())
+            .take_while(move |&i| self.keys.get(i) == key)
+            .filter(move |&i| self.rest(i) == rest)
+            .map(|i| self.lists[i])
+    }
+}
+
+/// The first eight bytes of `hash` as a big-endian integer, or all of them
+/// if it's shorter.
+fn key(hash: &[u8]) -> u64 {
+    hash.iter()
+        .take(8)
+        .fold(0, |key, byte| (key << 8) | u64::from(*byte))
+}
+
+/// Sorted keys, in integers only as wide as the hashes need.
+#[derive(Clone, Debug)]
+enum Keys {
+    Narrow(Vec<u32>),
+    Wide(Vec<u64>),
+}
+
+impl Keys {
+    fn get(&self, i: usize) -> u64 {
+        match self {
+            Self::Narrow(keys) => u64::from(keys[i]),
+            Self::Wide(keys) => keys[i],
+        }
+    }
+
+    /// The index of the first key not less than `key`.
+    fn lower_bound(&self, key: u64) -> usize {
+        match self {
+            Self::Narrow(keys) => {
+                u32::try_from(key).map_or(keys.len(), |key| interpolation_search(keys, key))
+            }
+            Self::Wide(keys) => interpolation_search(keys, key),
+        }
+    }
+}
+
+/// The index of the first of the sorted `keys` not less than `key`.
+///
+/// Each step guesses where `key` would be if the keys between the ends were
+/// evenly spread, and keeps the side of the guess it's on.
+fn interpolation_search<T: Copy + Ord + Into<u64>>(keys: &[T], key: T) -> usize {
+    let (mut low, mut high) = (0, keys.len());
+    for _ in 0..INTERPOLATION_STEPS {
+        if high - low <= 8 {
+            break;
+        }
+        let (first, last) = (keys[low].into(), keys[high - 1].into());
+        let wanted = key.into();
+        if wanted <= first {
+            return low;
+        }
+        if wanted > last {
+            return high;
+        }
+        // `first < wanted <= last`, so the guess is in `low..high`.
+        let offset = u128::from(wanted - first) * (high - 1 - low) as u128;
+        let guess = low + (offset / u128::from(last - first)) as usize;
+        if keys[guess] < key {
+            low = guess + 1;
+        } else {
+            high = guess + 1;
+        }
+    }
+    low + keys[low..high].partition_point(|probe| *probe < key)
+}
+
+/// A Bloom filter over keys, which are already uniformly distributed.
+#[derive(Clone, Debug)]
+struct Bloom {
+    bits: Vec<u64>,
+    /// How far to shift a 64-bit hash down to index a bit.
+    shift: u32,
+}
+
+impl Bloom {
+    fn new(len: usize) -> Self {
+        let bits = (len * BLOOM_BITS_PER_HASH).next_power_of_two().max(64);
+        Self {
+            bits: vec![0; bits / 64],
+            shift: 64 - bits.trailing_zeros(),
+        }
+    }
+
+    /// The bits `key` sets, by double hashing two mixes of it.
+    fn probes(&self, key: u64) -> impl Iterator<Item = usize> + use<> {
+        let first = key.wrapping_mul(0x9e37_79b9_7f4a_7c15);
+        let step = (key ^ (key >> 29)).wrapping_mul(0xbf58_476d_1ce4_e5b9) | 1;
+        let shift = self.shift;
+        (0..u64::from(BLOOM_PROBES))
+            .map(move |i| (first.wrapping_add(i.wrapping_mul(step)) >> shift) as usize)
+    }
+
+    fn insert(&mut self, key: u64) {
+        for bit in self.probes(key) {
+            self.bits[bit / 64] |= 1 << (bit % 64);
+        }
+    }
+
+    fn contains(&self, key: u64) -> bool {
+        self.probes(key)
+            .all(|bit| self.bits[bit / 64] & (1 << (bit % 64)) != 0)
+    }
+}
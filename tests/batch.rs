@@ -0,0 +1,169 @@
+mod common;
+
+use atlas::registry::Batch;
+use atlas::{ErrorCode, HashError, HashOptions, Registry};
+
+const PLUGINS: [&str; 15] = [
+    "atlas-checksum",
+    "atlas-classic",
+    "atlas-crc",
+    "atlas-crypto",
+    "atlas-digest",
+    "atlas-fnv",
+    "atlas-google",
+    "atlas-jenkins",
+    "atlas-md",
+    "atlas-modern",
+    "atlas-murmur",
+    "atlas-panic-test",
+    "atlas-sha",
+    "atlas-siphash",
+    "atlas-xxhash",
+];
+
+fn load_registry() -> Registry {
+    let mut registry = common::load_registry(&PLUGINS);
+    registry
+        .load_library(atlas_example_c::LIBRARY_PATH)
+        .expect("Failed to load the C example plugin");
+    registry
+}
+
+/// Options every hasher accepts, whatever it needs.
+fn options() -> HashOptions {
+    HashOptions {
+        seed: Some(32),
+        key: Some((0u8..16).collect()),
+        secret: Some((0u8..192).collect()),
+        ..HashOptions::default()
+    }
+}
+
+fn batch(inputs: &[&[u8]]) -> Batch {
+    let mut batch = Batch::new();
+    for input in inputs {
+        batch.push(&[input]);
+    }
+    batch
+}
+
+#[test]
+fn batches_hash_like_single_calls() {
+    let registry = load_registry();
+    let options = options();
+    let inputs = [
+        &b""[..],
+        b"a",
+        b"foobar",
+        b"sound/vo/npc_vendor_idle_01.wem",
+    ];
+    let names: Vec<String> = registry
+        .hashers()
+        .map(|hasher| hasher.name().to_owned())
+        .filter(|name| !name.starts_with("Panic") && name != "Oversized")
+        .collect();
+    assert!(names.iter().any(|name| name.starts_with("C Example")));
+
+    // The same batch is reused, so widths carry over between hashers.
+    let mut batch = batch(&inputs);
+    let mut hashed = 0;
+    for name in &names {
+        let hashes: Result<Vec<_>, _> = inputs
+            .iter()
+            .map(|input| registry.hash(name, input, &options))
+            .collect();
+        let result = registry.hash_batch(name, &mut batch, &options);
+        let hashes = match (hashes, result) {
+            (Ok(hashes), Ok(())) => hashes,
+            // Options this hasher doesn't take fail the same way in a batch.
+            (Err(single), Err(batched)) => {
+                assert_eq!(single.error_code(), batched.error_code(), "{name}");
+                continue;
+            }
+            (single, batched) => panic!("{name}: {single:?} but {batched:?}"),
+        };
+        assert_eq!(batch.len(), inputs.len());
+        for (i, hash) in hashes.into_iter().enumerate() {
+            let hash = hex::decode(hash.0).expect("Hashes are hex");
+            assert_eq!(batch.input(i), inputs[i]);
+            assert_eq!(batch.hash(i), hash, "{name} mismatch for {:?}", inputs[i]);
+        }
+        hashed += 1;
+    }
+    assert!(
+        hashed > names.len() * 3 / 4,
+        "Only {hashed} hashers checked"
+    );
+
+    let mut empty = Batch::new();
+    registry
+        .hash_batch("CRC-32", &mut empty, &options)
+        .expect("An empty batch hashes");
+    assert!(empty.is_empty());
+}
+
+#[test]
+fn batch_inputs_are_joined_parts() {
+    let registry = load_registry();
+    let options = HashOptions::default();
+    let mut batch = Batch::new();
+    batch.push(&[b"id_", b"walk", b".anim"]);
+    batch.push(&[]);
+    batch.clear();
+    batch.push(&[b"id_", b"run", b".anim"]);
+    assert_eq!(batch.len(), 1);
+    assert_eq!(batch.input(0), b"id_run.anim");
+
+    registry
+        .hash_batch("FNV-1a_32", &mut batch, &options)
+        .expect("Hashing failed");
+    let hash = registry
+        .hash("FNV-1a_32", b"id_run.anim", &options)
+        .expect("Hashing failed");
+    assert_eq!(hex::encode_upper(batch.hash(0)), hash.0);
+}
+
+#[test]
+fn batch_errors_are_reported() {
+    let registry = load_registry();
+    let options = HashOptions::default();
+
+    let error = registry
+        .hash_batch("Panic", &mut batch(&[b"a", b"b"]), &options)
+        .expect_err("A panicking hasher must return an error");
+    assert_eq!(error.error_code(), Some(ErrorCode::InternalPanic));
+    assert_eq!(
+        error.to_string(),
+        "The hasher panicked: Asked to hash 1 bytes"
+    );
+
+    // Wide hashes are retried with room for them.
+    let mut wide = batch(&[b"\x01\x02", b"\x03"]);
+    registry
+        .hash_batch("Oversized", &mut wide, &options)
+        .expect("Oversized output must be retried with a larger buffer");
+    assert_eq!(wide.hash(0), [1, 2].repeat(2048));
+    assert_eq!(wide.hash(1), [3; 4096]);
+
+    // A batch holds one width, and the empty input hashes to nothing.
+    let error = registry
+        .hash_batch("Oversized", &mut batch(&[b"\x01", b""]), &options)
+        .expect_err("Hashes of different widths can't be batched");
+    assert_eq!(
+        error,
+        HashError::MixedWidth {
+            width: 4096,
+            len: 0
+        }
+    );
+
+    // The C example has no batch export, so its errors come from single calls.
+    let error = registry
+        .hash_batch(
+            "C Example FNV-1a_32 (basis as seed)",
+            &mut batch(&[b"a"]),
+            &HashOptions::seeded(u64::MAX),
+        )
+        .expect_err("A 64-bit basis must be rejected");
+    assert_eq!(error.error_code(), Some(ErrorCode::InvalidSeedWidth));
+}
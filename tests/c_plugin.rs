@@ -0,0 +1,57 @@
+mod common;
+
+use atlas::{ErrorCode, HashOptions, Registry};
+
+#[test]
+fn c_plugin_matches_rust_plugin() {
+    let dir = common::build_plugins(&["atlas-fnv"]);
+    let mut registry = Registry::new();
+    registry
+        .load_library(atlas_example_c::LIBRARY_PATH)
+        .expect("Failed to load the C example plugin");
+    registry
+        .load_library(common::library_path(&dir, "atlas-fnv"))
+        .expect("Failed to load the FNV plugin");
+
+    for input in [
+        &b""[..],
+        b"a",
+        b"foobar",
+        b"sound/vo/npc_vendor_idle_01.wem",
+    ] {
+        let expected = registry
+            .hash("FNV-1a_32", input, &HashOptions::default())
+            .expect("FNV-1a_32 failed");
+        let hash = registry
+            .hash("C Example FNV-1a_32", input, &HashOptions::default())
+            .expect("The C example plugin failed");
+        assert_eq!(hash, expected, "Mismatch for {input:?}");
+
+        let hash = registry
+            .hash(
+                "C Example FNV-1a_32 (basis as seed)",
+                input,
+                &HashOptions::seeded(0x811c9dc5),
+            )
+            .expect("The seeded C example plugin failed");
+        assert_eq!(hash, expected, "Seeded mismatch for {input:?}");
+    }
+}
+
+#[test]
+fn c_plugin_reports_errors() {
+    let mut registry = Registry::new();
+    registry
+        .load_library(atlas_example_c::LIBRARY_PATH)
+        .expect("Failed to load the C example plugin");
+
+    let error = registry
+        .hash(
+            "C Example FNV-1a_32 (basis as seed)",
+            b"a",
+            &HashOptions::seeded(u64::MAX),
+        )
+        .expect_err("A 64-bit basis must be rejected");
+    assert_eq!(error.error_code(), Some(ErrorCode::InvalidSeedWidth));
+    assert_eq!(error.to_string(), "The offset basis must fit in 32 bits");
+}
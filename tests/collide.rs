@@ -0,0 +1,133 @@
+mod common;
+
+use std::collections::BTreeSet;
+use std::sync::Arc;
+use std::sync::atomic::AtomicBool;
+
+use atlas::collide::{self, CollideError, Collider};
+use atlas::{HashOptions, Registry};
+
+/// The low `bits` of the hash of `input`, through the plugin.
+fn truncated(registry: &Registry, hasher: &str, input: &[u8], bits: u32) -> u64 {
+    let hash = common::hash(registry, hasher, input);
+    let low = &hash[hash.len().saturating_sub(8)..];
+    let value = low
+        .iter()
+        .fold(0, |value, byte| (value << 8) | u64::from(*byte));
+    value & (u64::MAX >> (64 - bits))
+}
+
+#[test]
+fn collisions_are_distinct_and_collide() {
+    let registry = common::load_registry(&["atlas-crc", "atlas-fnv"]);
+    let options = HashOptions::default();
+    let charset = b"abcdefghijklmnopqrstuvwxyz_";
+    // CRC-32/BZIP2 is hashed by its plugin, the others on the host.
+    for (hasher, bits) in [
+        ("CRC-32/BZIP2", 20),
+        ("CRC-32", 20),
+        ("FNV-1a_32", 32),
+        ("FNV-1_64", 40),
+    ] {
+        let collider = Collider::new(&registry, hasher, &options, bits, charset)
+            .expect("Failed to set up the search");
+        let mut pairs = BTreeSet::new();
+        for collision in collider.take(3) {
+            let collision = collision.expect("The search failed");
+            let [a, b] = &collision.inputs;
+            assert_ne!(a, b, "{hasher}");
+            assert!(a.iter().chain(b).all(|byte| charset.contains(byte)));
+            assert_eq!(truncated(&registry, hasher, a, bits), collision.hash);
+            assert_eq!(truncated(&registry, hasher, b, bits), collision.hash);
+            assert!(pairs.insert(collision.inputs), "{hasher} repeated a pair");
+        }
+    }
+}
+
+#[test]
+fn collisions_run_out() {
+    let registry = common::load_registry(&["atlas-crc", "atlas-fnv"]);
+    let options = HashOptions::default();
+
+    // One bit is spelled by two inputs, which collide at most once.
+    let found = Collider::new(&registry, "CRC-32", &options, 1, b"ab")
+        .expect("Failed to set up the search")
+        .collect::<Result<Vec<_>, _>>()
+        .expect("The search failed");
+    let collides =
+        truncated(&registry, "CRC-32", b"aa", 1) == truncated(&registry, "CRC-32", b"ab", 1);
+    assert_eq!(found.len(), usize::from(collides));
+
+    // Every input is a zero byte and 4 free bytes, on which CRC-32 is a bijection.
+    let every_byte: Vec<u8> = (0..=255).collect();
+    let mut collider = Collider::new(&registry, "CRC-32", &options, 32, &every_byte)
+        .expect("Failed to set up the search");
+    assert_eq!(collider.find(), Err(CollideError::NotFound));
+}
+
+#[test]
+fn fnv_multicollisions() {
+    let registry = common::load_registry(&["atlas-crc", "atlas-fnv"]);
+    let options = HashOptions::default();
+    for (hasher, bits) in [("FNV-1a_32", 32), ("FNV-0_64", 24), ("FNV-1_64", 32)] {
+        let found =
+            collide::multicollision(&registry, hasher, &options, bits, b"0123456789", 4, None)
+                .expect("The search failed");
+        let inputs: BTreeSet<_> = found.inputs().collect();
+        assert_eq!(inputs.len(), 16, "{hasher}");
+        for input in &inputs {
+            assert_eq!(truncated(&registry, hasher, input, bits), found.hash);
+        }
+    }
+}
+
+#[test]
+fn collide_errors() {
+    let registry = common::load_registry(&["atlas-crc", "atlas-fnv"]);
+    let options = HashOptions::default();
+    let error = |bits, charset: &[u8]| {
+        Collider::new(&registry, "CRC-16/ARC", &options, bits, charset).err()
+    };
+    assert_eq!(error(0, b"ab"), Some(CollideError::Bits(0)));
+    assert_eq!(error(65, b"ab"), Some(CollideError::Bits(65)));
+    assert_eq!(error(16, b"aaa"), Some(CollideError::Charset));
+    assert_eq!(
+        error(24, b"ab"),
+        Some(CollideError::HashTooShort {
+            bits: 24,
+            hash_bits: 16
+        })
+    );
+    assert_eq!(
+        collide::multicollision(&registry, "CRC-32", &options, 16, b"ab", 2, None),
+        Err(CollideError::Unsupported("CRC-32".to_owned()))
+    );
+    assert_eq!(
+        collide::multicollision(&registry, "FNV-1a_32", &options, 16, b"ab", 64, None),
+        Err(CollideError::TooMany(64))
+    );
+}
+
+#[test]
+fn cancelled_collisions_stop() {
+    let registry = common::load_registry(&["atlas-fnv"]);
+    let options = HashOptions::default();
+    let cancelled = Arc::new(AtomicBool::new(true));
+    let mut collider = Collider::new(&registry, "FNV-1a_64", &options, 64, b"ab")
+        .expect("Failed to set up the search")
+        .cancelled_by(Arc::clone(&cancelled));
+    assert_eq!(collider.find(), Err(CollideError::Cancelled));
+    assert_eq!(collider.next(), None);
+    assert_eq!(
+        collide::multicollision(
+            &registry,
+            "FNV-1a_64",
+            &options,
+            64,
+            b"ab",
+            2,
+            Some(&cancelled)
+        ),
+        Err(CollideError::Cancelled)
+    );
+}
@@ -0,0 +1,161 @@
+#![expect(
+    clippy::literal_string_with_formatting_args,
+    reason = "Templates count with {num:N}, which looks like formatting"
+)]
+
+mod common;
+
+use std::collections::BTreeMap;
+use std::sync::Arc;
+
+use atlas::HashOptions;
+use atlas::search::engine::{Engine, Event, Job, Outcome};
+use atlas::search::{Affixes, Combinator, Keyspace as _, Search, Slot};
+
+fn tokens(tokens: &[&str]) -> Vec<Vec<u8>> {
+    tokens
+        .iter()
+        .map(|token| token.as_bytes().to_vec())
+        .collect()
+}
+
+fn lists() -> BTreeMap<String, Vec<Vec<u8>>> {
+    BTreeMap::from([
+        (String::from("prefix"), tokens(&["npc_", "amb_", ""])),
+        (String::from("noun"), tokens(&["vendor", "guard", "smith"])),
+        (
+            String::from("verb"),
+            tokens(&["idle", "alert", "greet", "die"]),
+        ),
+        (String::from("none"), Vec::new()),
+    ])
+}
+
+#[test]
+fn templates_parse_into_slots() {
+    let lists = lists();
+    let combinator =
+        Combinator::parse("{prefix}{noun}_{verb}{num:2}", &lists).expect("The template is valid");
+    assert_eq!(
+        combinator.slots(),
+        [
+            Slot::Tokens(lists["prefix"].clone()),
+            Slot::Tokens(lists["noun"].clone()),
+            Slot::Tokens(tokens(&["_"])),
+            Slot::Tokens(lists["verb"].clone()),
+            Slot::Number(2),
+        ]
+    );
+    assert_eq!(combinator.size(), 3 * 3 * 4 * 100);
+    let mut input = Vec::new();
+    combinator.candidate(0, &mut input);
+    assert_eq!(input, b"npc_vendor_idle00");
+    combinator.candidate(107, &mut input);
+    assert_eq!(input, b"npc_vendor_alert07");
+    combinator.candidate(combinator.size() - 1, &mut input);
+    assert_eq!(input, b"smith_die99");
+
+    let parse = |template| Combinator::parse(template, &lists);
+    assert_eq!(parse("plain").expect("The template is valid").size(), 1);
+    assert_eq!(parse("").expect("The template is valid").size(), 1);
+    assert_eq!(
+        parse("{noun}{none}").expect("The template is valid").size(),
+        0
+    );
+    assert_eq!(
+        parse("{num:19}").expect("The template is valid").size(),
+        10u64.pow(19)
+    );
+    assert_eq!(
+        parse("{num:10}{num:10}")
+            .expect("The template is valid")
+            .size(),
+        u64::MAX
+    );
+    for bad in [
+        "{adjective}",
+        "{noun",
+        "noun}",
+        "{num:0}",
+        "{num:20}",
+        "{num:x}",
+    ] {
+        assert!(parse(bad).is_err(), "{bad}");
+    }
+}
+
+#[test]
+fn combinations_hash_like_single_candidates() {
+    let registry = Arc::new(common::load_registry(&["atlas-crc", "atlas-fnv"]));
+    let combinator =
+        Combinator::parse("{prefix}{noun}_{verb}{num:2}", &lists()).expect("The template is valid");
+    let options = HashOptions::default();
+    let mut input = Vec::new();
+    // On the host, forwards and backwards, and through the plugin.
+    for hasher in ["FNV-1a_32", "FNV-1_64", "CRC-32", "CRC-32/BZIP2"] {
+        let targets: Vec<_> = [5, 399, 400, 1234, 3599]
+            .into_iter()
+            .map(|index| {
+                combinator.candidate(index, &mut input);
+                common::hash(&registry, hasher, &[b"vo/", &input[..], b".wem"].concat())
+            })
+            .collect();
+        let search =
+            Search::new(&registry, hasher, &options, targets).with_affixes(b"vo/", b".wem");
+        let matcher = search.matcher();
+        assert_eq!(matcher.is_streamed(), hasher != "CRC-32/BZIP2");
+        // Starting part of the way through every slot.
+        for range in [0..combinator.size(), 399..1235, 1234..1234] {
+            let expected: Vec<_> = range
+                .clone()
+                .filter_map(|index| {
+                    combinator.candidate(index, &mut input);
+                    matcher.check(&input).expect("Hashing failed")
+                })
+                .collect();
+            let found = matcher
+                .check_combinations(&combinator, range.clone())
+                .expect("Hashing failed");
+            assert_eq!(found, expected, "{hasher} over {range:?}");
+        }
+    }
+}
+
+#[test]
+fn engine_searches_combinations() {
+    let registry = Arc::new(common::load_registry(&["atlas-crc", "atlas-fnv"]));
+    let combinator = Arc::new(
+        Combinator::parse("{prefix}{noun}_{verb}_{num:2}", &lists())
+            .expect("The template is valid"),
+    );
+    let inputs = ["vo/npc_smith_greet_07.wem", "vo/guard_die_42.wem"];
+    for hasher in ["FNV-1a_32", "CRC-32"] {
+        let targets = inputs.map(|input| common::hash(&registry, hasher, input.as_bytes()));
+        let job = Job {
+            affixes: Affixes {
+                prefix: b"vo/".to_vec(),
+                suffix: b".wem".to_vec(),
+            },
+            threads: 2,
+            ..Job::new(hasher, targets, combinator.clone())
+        };
+        let engine = Engine::start(Arc::clone(&registry), job);
+        let mut found = Vec::new();
+        for event in engine.events() {
+            match event {
+                Event::Found(found_match) => found.push(found_match.input),
+                Event::Finished(outcome) => {
+                    assert_eq!(outcome, Outcome::Completed, "{hasher}");
+                    break;
+                }
+                Event::Progress(_) => {}
+            }
+        }
+        found.sort();
+        assert_eq!(
+            found,
+            [&b"vo/guard_die_42.wem"[..], b"vo/npc_smith_greet_07.wem"],
+            "{hasher}"
+        );
+    }
+}
@@ -0,0 +1,63 @@
+//! Helpers shared by the integration tests.
+#![allow(dead_code, reason = "not every test uses every helper")]
+
+use std::path::PathBuf;
+use std::process::Command;
+
+use atlas::{HashOptions, Registry};
+
+/// Builds the given plugin crates, returning the directory their libraries end up in.
+pub fn build_plugins(crates: &[&str]) -> PathBuf {
+    let mut command = Command::new(env!("CARGO"));
+    command.arg("build");
+    for name in crates {
+        command.args(["--package", name]);
+    }
+    let status = command.status().expect("Failed to run cargo");
+    assert!(status.success(), "Failed to build {crates:?}");
+
+    let target_dir = std::env::var_os("CARGO_TARGET_DIR")
+        .map(PathBuf::from)
+        .unwrap_or_else(|| PathBuf::from(env!("CARGO_MANIFEST_DIR")).join("target"));
+    target_dir.join("debug")
+}
+
+/// The path of a plugin's library inside the directory returned by [`build_plugins`].
+pub fn library_path(dir: &std::path::Path, name: &str) -> PathBuf {
+    dir.join(format!(
+        "{}{}{}",
+        std::env::consts::DLL_PREFIX,
+        name.replace('-', "_"),
+        std::env::consts::DLL_SUFFIX
+    ))
+}
+
+/// A registry with the given plugin crates built and loaded.
+pub fn load_registry(crates: &[&str]) -> Registry {
+    let dir = build_plugins(crates);
+    let mut registry = Registry::new();
+    for name in crates {
+        registry
+            .load_library(library_path(&dir, name))
+            .unwrap_or_else(|e| panic!("Failed to load {name}: {e}"));
+    }
+    registry
+}
+
+/// The hash of `input` as bytes, with default options.
+pub fn hash(registry: &Registry, hasher: &str, input: &[u8]) -> Vec<u8> {
+    hash_with(registry, hasher, input, &HashOptions::default())
+}
+
+/// The hash of `input` as bytes.
+pub fn hash_with(
+    registry: &Registry,
+    hasher: &str,
+    input: &[u8],
+    options: &HashOptions,
+) -> Vec<u8> {
+    let hash = registry
+        .hash(hasher, input, options)
+        .expect("Hashing failed");
+    hex::decode(hash.0).expect("Hashes are hex")
+}
@@ -0,0 +1,194 @@
+mod common;
+
+use std::ops::Range;
+use std::sync::Arc;
+use std::time::Duration;
+
+use atlas::HashOptions;
+use atlas::search::engine::{Checkpoint, Engine, Event, Job, Outcome};
+use atlas::search::{Affixes, BruteForce, Dictionary, Keyspace as _, Match, Targets};
+
+/// Runs `engine` to the end, returning what it found sorted, and how it ended.
+fn finish(engine: &Engine) -> (Vec<Vec<u8>>, u64, Outcome) {
+    let mut found = Vec::new();
+    let mut done = 0;
+    for event in engine.events() {
+        match event {
+            Event::Progress(progress) => done = progress.done,
+            Event::Found(found_match) => found.push(found_match.input),
+            Event::Finished(outcome) => {
+                found.sort();
+                return (found, done, outcome);
+            }
+        }
+    }
+    panic!("The engine stopped without finishing");
+}
+
+#[test]
+fn keyspaces_index_like_they_iterate() {
+    let brute_force = BruteForce::new(b"xyz", 0, 4);
+    assert_eq!(brute_force.size(), 1 + 3 + 9 + 27 + 81);
+    let mut input = Vec::new();
+    for (index, candidate) in brute_force.clone().enumerate() {
+        brute_force.candidate(index as u64, &mut input);
+        assert_eq!(input, candidate);
+    }
+    let every_byte: Vec<u8> = (0..=255).collect();
+    assert_eq!(BruteForce::new(&every_byte, 0, 64).size(), u64::MAX);
+
+    let words = Dictionary::parse("alpha\nbeta\n");
+    assert_eq!(words.size(), 2);
+    words.candidate(1, &mut input);
+    assert_eq!(input, b"beta");
+}
+
+#[test]
+fn engine_finds_every_target() {
+    let registry = Arc::new(common::load_registry(&["atlas-crc", "atlas-fnv"]));
+    // CRC-32 goes through the plugin, FNV-1a_32 runs on the host.
+    for hasher in ["CRC-32", "FNV-1a_32"] {
+        let inputs = [&b"a"[..], b"zz", b"cab", b"qqq"];
+        let targets =
+            inputs.map(|input| common::hash(&registry, hasher, &[b"id_", input].concat()));
+        let keyspace = Arc::new(BruteForce::new(b"abcdefghijklmnopqrstuvwxyz", 1, 3));
+        let job = Job {
+            affixes: Affixes {
+                prefix: b"id_".to_vec(),
+                suffix: Vec::new(),
+            },
+            threads: 4,
+            ..Job::new(hasher, targets, keyspace.clone())
+        };
+        let engine = Engine::start(Arc::clone(&registry), job);
+        let (found, done, outcome) = finish(&engine);
+        assert_eq!(outcome, Outcome::Completed, "{hasher}");
+        assert_eq!(done, keyspace.size());
+        assert_eq!(found, [&b"id_a"[..], b"id_cab", b"id_qqq", b"id_zz"]);
+    }
+}
+
+#[test]
+fn engine_resumes_from_checkpoints() {
+    let registry = Arc::new(common::load_registry(&["atlas-crc", "atlas-fnv"]));
+    let words: Vec<String> = (0..10_000).map(|i| format!("word{i}")).collect();
+    let words = Dictionary::parse(&words.join("\n"));
+    let targets = [common::hash(&registry, "CRC-32", b"word1234")];
+    let job = Job::new("CRC-32", targets, Arc::new(words));
+
+    // Only the ranges left are searched, along with what was found before.
+    let earlier = Match {
+        input: b"word7".to_vec(),
+        hash: common::hash(&registry, "CRC-32", b"word7"),
+        tags: Vec::new(),
+    };
+    let checkpoint = Checkpoint {
+        remaining: vec![0..1000, 5000..6000],
+        found: vec![earlier],
+        ..Checkpoint::new(&job)
+    };
+    let engine = Engine::resume(Arc::clone(&registry), job.clone(), checkpoint.clone())
+        .expect("The checkpoint matches the job");
+    let (found, done, outcome) = finish(&engine);
+    assert_eq!(outcome, Outcome::Completed);
+    assert_eq!(done, 10_000);
+    assert_eq!(found, [b"word7"]);
+
+    let checkpoint = Checkpoint {
+        remaining: std::iter::once(1000..2000).collect(),
+        ..checkpoint
+    };
+    let engine = Engine::resume(Arc::clone(&registry), job.clone(), checkpoint.clone())
+        .expect("The checkpoint matches the job");
+    assert_eq!(finish(&engine).0, [&b"word1234"[..], b"word7"]);
+
+    // Checkpoints of another search, or with ranges that don't add up, are refused.
+    let refused =
+        |other: Checkpoint| Engine::resume(Arc::clone(&registry), job.clone(), other).is_err();
+    assert!(refused(Checkpoint {
+        total: 9999,
+        ..checkpoint.clone()
+    }));
+    assert!(refused(Checkpoint {
+        options: HashOptions::seeded(1),
+        ..checkpoint.clone()
+    }));
+    assert!(refused(Checkpoint {
+        affixes: Affixes {
+            prefix: b"_".to_vec(),
+            suffix: Vec::new(),
+        },
+        ..checkpoint.clone()
+    }));
+    assert!(refused(Checkpoint {
+        targets: std::iter::once(b"word".to_vec())
+            .collect::<Targets>()
+            .digest(),
+        ..checkpoint.clone()
+    }));
+    for remaining in [
+        vec![0..1000, 500..2000],
+        vec![5000..6000, 0..1000],
+        vec![0..1000, 9000..10_001],
+        vec![
+            0..1000,
+            Range {
+                start: 2000,
+                end: 1500,
+            },
+        ],
+    ] {
+        assert!(refused(Checkpoint {
+            remaining,
+            ..checkpoint.clone()
+        }));
+    }
+}
+
+#[test]
+fn engine_pauses_cancels_and_saves() {
+    let registry = Arc::new(common::load_registry(&["atlas-crc", "atlas-fnv"]));
+    let path = std::env::temp_dir().join(format!("atlas-search-{}.ron", std::process::id()));
+    // Far more than can be checked before it's cancelled.
+    let keyspace = Arc::new(BruteForce::new(b"abcdefghijklmnopqrstuvwxyz", 1, 12));
+    let job = Job {
+        threads: 3,
+        checkpoint: Some(path.clone()),
+        ..Job::new("CRC-32", [vec![0; 4]], keyspace)
+    };
+    let engine = Engine::start(Arc::clone(&registry), job);
+
+    std::thread::sleep(Duration::from_millis(300));
+    engine.pause();
+    assert!(engine.is_paused());
+    // Chunks in progress finish, then nothing moves.
+    std::thread::sleep(Duration::from_millis(300));
+    engine.poll().for_each(drop);
+    std::thread::sleep(Duration::from_millis(500));
+    let progress: Vec<_> = engine
+        .poll()
+        .filter_map(|event| match event {
+            Event::Progress(progress) => Some(progress),
+            _ => None,
+        })
+        .collect();
+    assert!(!progress.is_empty());
+    assert!(progress.windows(2).all(|pair| pair[0].done == pair[1].done));
+
+    engine.resume_paused();
+    engine.cancel();
+    let (_, done, outcome) = finish(&engine);
+    assert_eq!(outcome, Outcome::Cancelled);
+
+    let checkpoint = Checkpoint::load(&path).expect("The checkpoint was saved");
+    std::fs::remove_file(&path).ok();
+    assert!(done > 0);
+    assert_eq!(checkpoint.done(), done);
+    assert!(!checkpoint.remaining.is_empty());
+    assert!(
+        checkpoint
+            .remaining
+            .windows(2)
+            .all(|pair| pair[0].end <= pair[1].start)
+    );
+}
@@ -0,0 +1,121 @@
+mod common;
+
+use std::sync::Arc;
+use std::sync::atomic::{AtomicBool, Ordering};
+
+use atlas::HashOptions;
+use atlas::forge::{ForgeError, Forger};
+
+#[test]
+fn insert_reaches_target() {
+    let registry = common::load_registry(&["atlas-crc", "atlas-fnv"]);
+    let data = b"The quick brown fox jumps over the lazy dog";
+    let cases = [
+        (
+            "CRC-32",
+            HashOptions::default(),
+            &[0xde, 0xad, 0xbe, 0xef][..],
+        ),
+        ("CRC-32/BZIP2", HashOptions::default(), &[0, 0, 0, 0]),
+        ("CRC-16/ARC", HashOptions::default(), &[0x12, 0x34]),
+        ("CRC-64/XZ", HashOptions::default(), &[0xff; 8]),
+        // A width that isn't whole bytes leaves some solutions spare.
+        (
+            "CRC",
+            HashOptions::default()
+                .with_param("width", 12)
+                .with_param("poly", 0x80f)
+                .with_param("init", 0)
+                .with_param("xorout", 0),
+            &[0x0a, 0xbc],
+        ),
+    ];
+    for (hasher, options, target) in &cases {
+        let forger = Forger::new(&registry, hasher, options);
+        for offset in [0, 10, data.len()] {
+            let patch = forger
+                .insert(data, offset, target)
+                .unwrap_or_else(|e| panic!("{hasher} at {offset}: {e}"));
+            assert_eq!(patch.len(), target.len());
+            let mut patched = data.to_vec();
+            patched.splice(offset..offset, patch);
+            assert_eq!(
+                common::hash_with(&registry, hasher, &patched, options),
+                *target,
+                "{hasher} at {offset}"
+            );
+        }
+    }
+}
+
+#[test]
+fn insert_errors() {
+    let registry = common::load_registry(&["atlas-crc", "atlas-fnv"]);
+    let options = HashOptions::default();
+    let forger = Forger::new(&registry, "CRC-32", &options);
+    assert_eq!(
+        forger.insert(b"abc", 4, &[0; 4]),
+        Err(ForgeError::OffsetOutOfRange { offset: 4, len: 3 })
+    );
+    assert_eq!(
+        forger.insert(b"abc", 3, &[0; 2]),
+        Err(ForgeError::TargetLength {
+            expected: 4,
+            actual: 2
+        })
+    );
+    let forger = Forger::new(&registry, "FNV-1a_32", &options);
+    assert_eq!(
+        forger.insert(b"abc", 3, &[0; 4]),
+        Err(ForgeError::NotLinear)
+    );
+}
+
+#[test]
+fn reverse_finds_short_strings() {
+    let registry = common::load_registry(&["atlas-crc", "atlas-fnv"]);
+    let options = HashOptions::default();
+    let forger = Forger::new(&registry, "CRC-32", &options);
+    let charset = b"abcdefghijklmnopqrstuvwxyz";
+
+    let target = common::hash_with(&registry, "CRC-32", b"secret", &options);
+    let found = forger
+        .reverse(6, charset, &target)
+        .expect("Failed to set up the search")
+        .collect::<Result<Vec<_>, _>>()
+        .expect("The search failed");
+    assert!(found.contains(&b"secret".to_vec()));
+    for input in &found {
+        assert!(input.iter().all(|byte| charset.contains(byte)));
+        assert_eq!(
+            common::hash_with(&registry, "CRC-32", input, &options),
+            target
+        );
+    }
+
+    // Inputs shorter than the hash are solved for entirely.
+    let target = common::hash_with(&registry, "CRC-32", b"abc", &options);
+    let found = forger
+        .reverse(3, charset, &target)
+        .expect("Failed to set up the search")
+        .collect::<Result<Vec<_>, _>>()
+        .expect("The search failed");
+    assert_eq!(found, [b"abc".to_vec()]);
+}
+
+#[test]
+fn reverse_stops_when_cancelled() {
+    let registry = common::load_registry(&["atlas-crc", "atlas-fnv"]);
+    let options = HashOptions::default();
+    let forger = Forger::new(&registry, "CRC-32", &options);
+    let target = common::hash_with(&registry, "CRC-32", b"secret", &options);
+
+    let cancelled = Arc::new(AtomicBool::new(false));
+    let mut found = forger
+        .reverse(6, b"abcdefghijklmnopqrstuvwxyz", &target)
+        .expect("Failed to set up the search")
+        .cancelled_by(Arc::clone(&cancelled));
+    assert!(found.next().is_some_and(|input| input.is_ok()));
+    cancelled.store(true, Ordering::Relaxed);
+    assert!(found.next().is_none(), "A cancelled search must stop");
+}
@@ -0,0 +1,56 @@
+mod common;
+
+use atlas::HashOptions;
+use atlas::invert::{self, InvertError};
+
+#[test]
+fn finalisers_round_trip() {
+    for x in [0, 1, 0xdeadbeef, u32::MAX, 0x8000_0000] {
+        assert_eq!(invert::unfmix32(invert::fmix32(x)), x);
+        assert_eq!(invert::fmix32(invert::unfmix32(x)), x);
+        assert_eq!(invert::unxxh32_avalanche(invert::xxh32_avalanche(x)), x);
+    }
+    for x in [0, 1, 0x0123_4567_89ab_cdef, u64::MAX, 1 << 63] {
+        assert_eq!(invert::unfmix64(invert::fmix64(x)), x);
+        assert_eq!(invert::fmix64(invert::unfmix64(x)), x);
+        assert_eq!(invert::unxxh64_avalanche(invert::xxh64_avalanche(x)), x);
+    }
+}
+
+#[test]
+fn preimages_hash_back() {
+    let registry = common::load_registry(&["atlas-murmur", "atlas-xxhash"]);
+
+    for invertible in invert::INVERTIBLE {
+        for seed in [0, 1, 0x9e3779b1] {
+            for id in [0u128, 1, 1234, 0xdead_beef, u128::MAX] {
+                let input = &id.to_le_bytes()[..invertible.input_len];
+                let hash = registry
+                    .hash(invertible.hasher, input, &HashOptions::seeded(seed))
+                    .expect("Hashing failed");
+                let hash = hex::decode(hash.0).expect("Hashes are hex");
+                let preimage = invertible.preimage(&hash, seed).expect("Inverting failed");
+                assert_eq!(preimage, input, "{} with seed {seed:#x}", invertible.hasher);
+            }
+        }
+    }
+}
+
+#[test]
+fn preimage_errors() {
+    assert_eq!(
+        invert::preimage("FNV-1a_32", &[0; 4], 0),
+        Err(InvertError::Unsupported("FNV-1a_32".to_owned()))
+    );
+    assert_eq!(
+        invert::preimage("XXH64", &[0; 4], 0),
+        Err(InvertError::HashLength {
+            expected: 8,
+            actual: 4
+        })
+    );
+    assert_eq!(
+        invert::preimage("Murmur3_32", &[0; 4], 1 << 32),
+        Err(InvertError::SeedTooWide(1 << 32))
+    );
+}
@@ -0,0 +1,128 @@
+mod common;
+
+use std::sync::Arc;
+
+use atlas::HashOptions;
+use atlas::search::engine::{Engine, Event, Job, Outcome};
+use atlas::search::kernel::{Isa, Kernel};
+use atlas::search::{Affixes, Keyspace as _, Mask};
+
+fn affixes(prefix: &str, suffix: &str) -> Affixes {
+    Affixes {
+        prefix: prefix.as_bytes().to_vec(),
+        suffix: suffix.as_bytes().to_vec(),
+    }
+}
+
+#[test]
+fn masks_parse_like_hashcat() {
+    let mask = Mask::parse("a?d??b?h").expect("The mask is valid");
+    assert_eq!(mask.len(), 5);
+    assert_eq!(mask.size(), 10 * 16);
+    let mut input = Vec::new();
+    mask.candidate(0, &mut input);
+    assert_eq!(input, b"a0?b0");
+    mask.candidate(mask.size() - 1, &mut input);
+    assert_eq!(input, b"a9?bf");
+
+    assert_eq!(Mask::parse("").expect("The mask is valid").size(), 1);
+    assert!(Mask::parse("?x").is_err());
+    assert!(Mask::parse("ab?").is_err());
+}
+
+#[test]
+fn kernels_hash_like_the_plugins() {
+    let registry = common::load_registry(&["atlas-fnv", "atlas-murmur"]);
+    let hashers = [
+        ("FNV-1a_32", HashOptions::default()),
+        ("Murmur3_32", HashOptions::default()),
+        ("Murmur3_32", HashOptions::seeded(0x9747_b28c)),
+    ];
+    // Prefixes of every length modulo four, as Murmur3 hashes in blocks.
+    let cases = [
+        (affixes("", ""), "?l?d?u"),
+        (affixes("a", ".wem"), "?d?d"),
+        (affixes("ab", ""), "?l"),
+        (affixes("sound/vo/", "_01.wem"), "npc_?l?l?d"),
+        (affixes("abc", "x"), ""),
+        (affixes("", ""), "?h?h?h?h?h?h?h"),
+    ];
+    let supported: Vec<_> = Isa::ALL
+        .into_iter()
+        .filter(|isa| isa.is_supported())
+        .collect();
+    assert!(supported.contains(&Isa::Portable));
+    assert!(supported.contains(&Isa::detect()));
+
+    let mut input = Vec::new();
+    for (hasher, options) in &hashers {
+        let kernel = Kernel::new(&registry, hasher, options).expect("The hasher has a kernel");
+        assert_eq!(kernel.isa(), Isa::detect());
+        for (affixes, mask) in &cases {
+            let mask = Mask::parse(mask).expect("The mask is valid");
+            // Not a multiple of eight at either end.
+            let range = 3..mask.size().min(203);
+            let expected: Vec<_> = range
+                .clone()
+                .map(|index| {
+                    mask.candidate(index, &mut input);
+                    let hash = registry
+                        .hash(hasher, &affixes.wrap(&input), options)
+                        .expect("Hashing failed");
+                    hex::decode(hash.0).expect("Hashes are hex")
+                })
+                .collect();
+            for isa in &supported {
+                let kernel = kernel.with_isa(*isa).expect("The CPU has it");
+                let hashes = kernel.hashes(affixes, &mask, range.clone());
+                let hashes: Vec<_> = hashes.iter().map(|hash| hash.to_vec()).collect();
+                assert_eq!(hashes, expected, "{hasher} on {isa:?} with {affixes:?}");
+            }
+        }
+    }
+
+    assert!(Kernel::new(&registry, "FNV-1_32", &HashOptions::default()).is_none());
+    assert!(Kernel::new(&registry, "Murmur3_32", &HashOptions::seeded(1 << 32)).is_none());
+}
+
+#[test]
+fn engine_scans_masks_with_kernels() {
+    let registry = Arc::new(common::load_registry(&["atlas-fnv", "atlas-murmur"]));
+    for (hasher, options) in [
+        ("FNV-1a_32", HashOptions::default()),
+        ("Murmur3_32", HashOptions::seeded(7)),
+    ] {
+        let inputs = ["vo/npc_ab1.wem", "vo/npc_zz9.wem", "vo/npc_qa0.wem"];
+        let targets = inputs.map(|input| {
+            let hash = registry
+                .hash(hasher, input.as_bytes(), &options)
+                .expect("Hashing failed");
+            hex::decode(hash.0).expect("Hashes are hex")
+        });
+        let mask = Arc::new(Mask::parse("npc_?l?l?d").expect("The mask is valid"));
+        let job = Job {
+            options,
+            affixes: affixes("vo/", ".wem"),
+            threads: 3,
+            ..Job::new(hasher, targets, mask.clone())
+        };
+        let engine = Engine::start(Arc::clone(&registry), job);
+        let mut found = Vec::new();
+        for event in engine.events() {
+            match event {
+                Event::Found(found_match) => found.push(found_match.input),
+                Event::Finished(outcome) => {
+                    assert_eq!(outcome, Outcome::Completed, "{hasher}");
+                    break;
+                }
+                Event::Progress(_) => {}
+            }
+        }
+        found.sort();
+        assert_eq!(
+            found,
+            [&b"vo/npc_ab1.wem"[..], b"vo/npc_qa0.wem", b"vo/npc_zz9.wem"],
+            "{hasher}"
+        );
+    }
+}
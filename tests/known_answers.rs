@@ -0,0 +1,268 @@
+//! Checks every registered hasher against the known answers in `tests/vectors`.
+//!
+//! Each vector file holds one test per line, as `hasher | input | options | expected`:
+//!
+//! - `input` is a quoted string (`"abc"`), `hex:00ff..`, `repeat:N:text` for
+//!   `text` repeated `N` times, or `range:N` for the bytes `00 01 02 ..` wrapping
+//!   at 256, `N` bytes long.
+//! - `options` is `-`, or a space separated list of `seed=N`, `key=INPUT`,
+//!   `secret=INPUT` and `name=N` for the hasher's declared parameters, with
+//!   numbers in decimal or `0x` hex.
+//! - `expected` is the hash in hex, as shown by the GUI, or `!Code` for a hasher
+//!   that must fail with that [`ErrorCode`].
+//!
+//! Lines starting with `#` and blank lines are ignored.
+
+mod common;
+
+use std::path::{Path, PathBuf};
+
+use atlas::{ErrorCode, HashOptions, HashResult, Registry};
+
+/// Every plugin crate shipped in the `plugins` folder.
+const PLUGINS: &[&str] = &[
+    "atlas-checksum",
+    "atlas-classic",
+    "atlas-crc",
+    "atlas-crypto",
+    "atlas-digest",
+    "atlas-fnv",
+    "atlas-google",
+    "atlas-jenkins",
+    "atlas-md",
+    "atlas-modern",
+    "atlas-murmur",
+    "atlas-sha",
+    "atlas-siphash",
+    "atlas-xxhash",
+];
+
+struct Vector {
+    location: String,
+    hasher: String,
+    input_spec: String,
+    input: Vec<u8>,
+    options: HashOptions,
+    expected: Expected,
+}
+
+#[derive(PartialEq, Eq)]
+enum Expected {
+    Hash(String),
+    Error(ErrorCode),
+}
+
+impl std::fmt::Display for Expected {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Hash(hash) => f.write_str(hash),
+            Self::Error(code) => write!(f, "error {code:?}"),
+        }
+    }
+}
+
+fn parse_number(text: &str) -> Result<u64, String> {
+    let parsed = match text.strip_prefix("0x") {
+        Some(hex) => u64::from_str_radix(hex, 16),
+        None => text.parse(),
+    };
+    parsed.map_err(|e| format!("invalid number {text:?}: {e}"))
+}
+
+fn parse_input(spec: &str) -> Result<Vec<u8>, String> {
+    if let Some(text) = spec.strip_prefix('"') {
+        let text = text
+            .strip_suffix('"')
+            .ok_or_else(|| format!("unterminated string {spec}"))?;
+        Ok(text.as_bytes().to_vec())
+    } else if let Some(hex) = spec.strip_prefix("hex:") {
+        parse_hex(hex)
+    } else if let Some(rest) = spec.strip_prefix("repeat:") {
+        let (count, text) = rest
+            .split_once(':')
+            .ok_or_else(|| format!("expected repeat:N:text, got {spec}"))?;
+        let count = usize::try_from(parse_number(count)?).map_err(|e| e.to_string())?;
+        Ok(text.repeat(count).into_bytes())
+    } else if let Some(len) = spec.strip_prefix("range:") {
+        let len = usize::try_from(parse_number(len)?).map_err(|e| e.to_string())?;
+        Ok((0..=u8::MAX).cycle().take(len).collect())
+    } else {
+        Err(format!("unknown input {spec:?}"))
+    }
+}
+
+fn parse_hex(hex: &str) -> Result<Vec<u8>, String> {
+    if hex.len() % 2 != 0 {
+        return Err(format!("odd number of hex digits in {hex:?}"));
+    }
+    (0..hex.len())
+        .step_by(2)
+        .map(|i| {
+            hex.get(i..i + 2)
+                .and_then(|byte| u8::from_str_radix(byte, 16).ok())
+                .ok_or_else(|| format!("invalid hex {hex:?}"))
+        })
+        .collect()
+}
+
+fn parse_options(spec: &str) -> Result<HashOptions, String> {
+    let mut options = HashOptions::default();
+    if spec == "-" {
+        return Ok(options);
+    }
+    for option in spec.split_whitespace() {
+        let (name, value) = option
+            .split_once('=')
+            .ok_or_else(|| format!("expected name=value, got {option:?}"))?;
+        match name {
+            "seed" => options.seed = Some(parse_number(value)?),
+            "key" => options.key = Some(parse_input(value)?),
+            "secret" => options.secret = Some(parse_input(value)?),
+            _ => options = options.with_param(name, parse_number(value)?),
+        }
+    }
+    Ok(options)
+}
+
+fn parse_expected(spec: &str) -> Result<Expected, String> {
+    let Some(name) = spec.strip_prefix('!') else {
+        parse_hex(spec)?;
+        return Ok(Expected::Hash(spec.to_ascii_uppercase()));
+    };
+    (-100..=0)
+        .filter_map(ErrorCode::from_i32)
+        .find(|code| format!("{code:?}") == name)
+        .map(Expected::Error)
+        .ok_or_else(|| format!("unknown error code {name:?}"))
+}
+
+fn parse_line(line: &str) -> Result<Vector, String> {
+    let fields: Vec<_> = line.split(" | ").map(str::trim).collect();
+    let [hasher, input, options, expected] = fields[..] else {
+        return Err(format!("expected 4 fields, got {}", fields.len()));
+    };
+    Ok(Vector {
+        location: String::new(),
+        hasher: hasher.to_owned(),
+        input_spec: input.to_owned(),
+        input: parse_input(input)?,
+        options: parse_options(options)?,
+        expected: parse_expected(expected)?,
+    })
+}
+
+fn load_vectors(dir: &Path) -> Vec<Vector> {
+    let mut files: Vec<PathBuf> = std::fs::read_dir(dir)
+        .expect("Failed to read the vectors folder")
+        .map(|entry| entry.expect("Failed to read the vectors folder").path())
+        .filter(|path| path.extension().is_some_and(|x| x == "txt"))
+        .collect();
+    files.sort();
+
+    let mut vectors = Vec::new();
+    for file in files {
+        let text = std::fs::read_to_string(&file).expect("Failed to read a vector file");
+        let name = file.file_name().unwrap_or_default().to_string_lossy();
+        for (number, line) in text.lines().enumerate() {
+            let line = line.trim();
+            if line.is_empty() || line.starts_with('#') {
+                continue;
+            }
+            let location = format!("{name}:{}", number + 1);
+            match parse_line(line) {
+                Ok(vector) => vectors.push(Vector { location, ..vector }),
+                Err(e) => panic!("{location}: {e}"),
+            }
+        }
+    }
+    vectors
+}
+
+/// Builds the plugins and loads them the way the GUI does, from a folder of libraries.
+fn load_plugins() -> Registry {
+    let build_dir = common::build_plugins(PLUGINS);
+    let plugin_dir = build_dir.join("known-answer-plugins");
+    if plugin_dir.exists() {
+        std::fs::remove_dir_all(&plugin_dir).expect("Failed to clear the plugins folder");
+    }
+    std::fs::create_dir_all(&plugin_dir).expect("Failed to create the plugins folder");
+    for name in PLUGINS {
+        let library = common::library_path(&build_dir, name);
+        let file_name = library.file_name().expect("Library paths have a file name");
+        std::fs::copy(&library, plugin_dir.join(file_name)).expect("Failed to copy a plugin");
+    }
+
+    let mut registry = Registry::new();
+    let loaded = registry
+        .load_dir(&plugin_dir)
+        .expect("Failed to load the plugins");
+    assert_eq!(loaded, PLUGINS.len(), "Some plugins were not loaded");
+    registry
+}
+
+/// Describes how `actual` differs from `expected`, or `None` if they match.
+fn check(registry: &Registry, vector: &Vector) -> Option<String> {
+    let actual = match registry.hash(&vector.hasher, &vector.input, &vector.options) {
+        Ok(hash) => Expected::Hash(hash.0),
+        Err(e) => match e.error_code() {
+            Some(code) => Expected::Error(code),
+            None => return Some(format!("      error: {e}\n")),
+        },
+    };
+    if actual == vector.expected {
+        return None;
+    }
+
+    let mut diff = format!(
+        "    - expected: {}\n    + actual:   {actual}\n",
+        vector.expected
+    );
+    if let (Expected::Hash(expected), Expected::Hash(actual)) = (&vector.expected, &actual) {
+        let reversed = parse_hex(actual).map(|mut bytes| {
+            bytes.reverse();
+            HashResult::from(bytes).0
+        });
+        if actual.len() != expected.len() {
+            diff.push_str(&format!(
+                "      the hash is {} bits, expected {}\n",
+                actual.len() * 4,
+                expected.len() * 4
+            ));
+        } else if reversed.as_ref() == Ok(expected) {
+            diff.push_str("      the bytes are reversed, check the byte order\n");
+        }
+    }
+    Some(diff)
+}
+
+#[test]
+fn known_answers() {
+    let vectors = load_vectors(&Path::new(env!("CARGO_MANIFEST_DIR")).join("tests/vectors"));
+    let registry = load_plugins();
+
+    let mut report = String::new();
+    let mut failed = 0;
+    for vector in &vectors {
+        if let Some(diff) = check(&registry, vector) {
+            failed += 1;
+            report.push_str(&format!(
+                "  {}({}) at {}\n",
+                vector.hasher, vector.input_spec, vector.location
+            ));
+            report.push_str(&diff);
+        }
+    }
+
+    for hasher in registry.hashers() {
+        if !vectors.iter().any(|vector| vector.hasher == hasher.name()) {
+            failed += 1;
+            report.push_str(&format!("  {} has no known answers\n", hasher.name()));
+        }
+    }
+
+    assert!(
+        failed == 0,
+        "{failed} of {} known answers failed:\n{report}",
+        vectors.len()
+    );
+}
@@ -0,0 +1,133 @@
+mod common;
+
+use std::collections::HashSet;
+use std::sync::Arc;
+
+use atlas::search::engine::{Engine, Event, Job, Outcome};
+use atlas::search::{Affixes, Keyspace as _, Markov};
+
+const NAMES: [&str; 8] = [
+    "npc_vendor_idle_01",
+    "npc_vendor_idle_02",
+    "npc_vendor_greet_01",
+    "npc_guard_alert_01",
+    "npc_guard_alert_02",
+    "npc_guard_idle_03",
+    "npc_guard_greet_02",
+    "npc_smith_idle_01",
+];
+
+fn train(names: &[&str], order: usize) -> Markov {
+    Markov::train(names.iter().map(|name| name.as_bytes()), order)
+}
+
+#[test]
+fn candidates_come_cheapest_first() {
+    let model = train(&NAMES, 3);
+    assert_eq!(model.max_len(), 19);
+    let likely = model.likely(1, model.max_len(), 24);
+    assert!(likely.size() > 100);
+
+    let mut seen = HashSet::new();
+    let mut input = Vec::new();
+    let mut last = 0;
+    for index in 0..likely.size().min(20_000) {
+        likely.candidate(index, &mut input);
+        let cost = likely.cost_of(index);
+        assert!(cost >= last, "{index}");
+        assert_eq!(model.cost(&input), Some(cost), "{index}");
+        assert!(seen.insert(input.clone()), "{input:?} is listed twice");
+        last = cost;
+    }
+    // The names it learned from are among the likeliest.
+    let index = (0..likely.size())
+        .find(|&index| {
+            likely.candidate(index, &mut input);
+            input == b"npc_guard_alert_01"
+        })
+        .expect("Known names are listed");
+    assert!(index < 20, "{index}");
+    assert!(model.cost(b"npc_smith_idle_02").is_some());
+    assert!(model.cost(b"npc_vendor_idle_0x").is_none());
+}
+
+#[test]
+fn every_name_is_listed_once() {
+    for order in 1..=3 {
+        let model = train(&["ab", "ba", "aab", "abba"], order);
+        let likely = model.likely(1, 5, 40);
+        let mut listed = Vec::new();
+        let mut input = Vec::new();
+        for index in 0..likely.size() {
+            likely.candidate(index, &mut input);
+            listed.push(input.clone());
+        }
+        let mut expected = Vec::new();
+        for len in 1..=5 {
+            for bits in 0u32..1 << len {
+                let name: Vec<u8> = (0..len)
+                    .map(|i| if (bits >> i) & 1 == 0 { b'a' } else { b'b' })
+                    .collect();
+                if model.cost(&name).is_some_and(|cost| cost <= 40) {
+                    expected.push(name);
+                }
+            }
+        }
+        listed.sort();
+        expected.sort();
+        assert_eq!(listed, expected, "order {order}");
+    }
+
+    let empty = Markov::train([], 3);
+    assert_eq!(empty.likely(0, 8, 40).size(), 0);
+}
+
+#[test]
+fn engine_finds_likely_names() {
+    let registry = Arc::new(common::load_registry(&["atlas-crc", "atlas-fnv"]));
+
+    let model = train(&NAMES, 3);
+    // Names it never saw, made of pieces of those it did.
+    let unknown = ["npc_smith_idle_02", "npc_guard_idle_01"];
+    let max_cost = unknown
+        .iter()
+        .filter_map(|name| model.cost(name.as_bytes()))
+        .max()
+        .expect("The names are likely");
+    let likely = Arc::new(model.likely(1, model.max_len(), max_cost));
+    assert!(likely.size() < 1000, "{}", likely.size());
+
+    for hasher in ["CRC-32", "FNV-1a_32"] {
+        let targets = unknown
+            .map(|name| common::hash(&registry, hasher, format!("vo/{name}.wem").as_bytes()));
+        let job = Job {
+            affixes: Affixes {
+                prefix: b"vo/".to_vec(),
+                suffix: b".wem".to_vec(),
+            },
+            threads: 2,
+            ..Job::new(hasher, targets, likely.clone())
+        };
+        let engine = Engine::start(Arc::clone(&registry), job);
+        let mut found = Vec::new();
+        for event in engine.events() {
+            match event {
+                Event::Found(found_match) => found.push(found_match.input),
+                Event::Finished(outcome) => {
+                    assert_eq!(outcome, Outcome::Completed, "{hasher}");
+                    break;
+                }
+                Event::Progress(_) => {}
+            }
+        }
+        found.sort();
+        assert_eq!(
+            found,
+            [
+                &b"vo/npc_guard_idle_01.wem"[..],
+                b"vo/npc_smith_idle_02.wem"
+            ],
+            "{hasher}"
+        );
+    }
+}
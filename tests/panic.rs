@@ -0,0 +1,80 @@
+mod common;
+
+use atlas::registry::MAX_OUTPUT_LEN;
+use atlas::{ErrorCode, HashError, HashOptions};
+
+#[test]
+fn panicking_plugin_is_contained() {
+    let registry = common::load_registry(&["atlas-panic-test", "atlas-fnv"]);
+
+    for name in ["Panic", "Panic (out of bounds)"] {
+        let error = registry
+            .hash(name, b"input", &HashOptions::default())
+            .expect_err("A panicking hasher must return an error");
+        assert_eq!(error.error_code(), Some(ErrorCode::InternalPanic), "{name}");
+        assert!(
+            error.to_string().starts_with("The hasher panicked"),
+            "Unexpected message for {name}: {error}"
+        );
+    }
+
+    // The host and other plugins keep working afterwards.
+    let hash = registry
+        .hash("FNV-1a_32", b"a", &HashOptions::default())
+        .expect("FNV-1a_32 failed after a plugin panicked");
+    assert_eq!(hash.0, "E40C292C", "FNV-1a_32 returned the wrong hash");
+}
+
+#[test]
+fn unwinding_plugin_aborts_the_host() {
+    // The child process hashes with the hasher that doesn't catch its panic.
+    if std::env::var_os("ATLAS_UNWIND_CHILD").is_some() {
+        let registry = common::load_registry(&["atlas-panic-test"]);
+        let result = registry.hash("Panic (unwinding)", b"input", &HashOptions::default());
+        panic!("The host survived a plugin unwinding into it: {result:?}");
+    }
+
+    let current_exe = std::env::current_exe().expect("Failed to find the test binary");
+    let output = std::process::Command::new(current_exe)
+        .args(["unwinding_plugin_aborts_the_host", "--exact", "--nocapture"])
+        .env("ATLAS_UNWIND_CHILD", "1")
+        .output()
+        .expect("Failed to run the test binary");
+    let stderr = String::from_utf8_lossy(&output.stderr);
+    assert!(!output.status.success());
+    assert!(!stderr.contains("The host survived"), "{stderr}");
+    assert!(stderr.contains("Unwinding into the host"), "{stderr}");
+    #[cfg(unix)]
+    {
+        use std::os::unix::process::ExitStatusExt as _;
+        assert_eq!(output.status.signal(), Some(6), "Expected SIGABRT");
+    }
+}
+
+#[test]
+fn oversized_output_is_retried() {
+    let registry = common::load_registry(&["atlas-panic-test"]);
+
+    let hash = registry
+        .hash("Oversized", b"\x01\x02", &HashOptions::default())
+        .expect("Oversized output must be retried with a larger buffer");
+    assert_eq!(
+        hash.0,
+        "0102".repeat(2048),
+        "Oversized output was truncated"
+    );
+}
+
+#[test]
+fn greedy_output_is_refused() {
+    let registry = common::load_registry(&["atlas-panic-test"]);
+
+    let error = registry
+        .hash("Greedy", b"input", &HashOptions::default())
+        .expect_err("An output of usize::MAX bytes must be refused");
+    assert_eq!(error, HashError::OutputTooLarge(usize::MAX));
+    assert!(
+        error.to_string().contains(&MAX_OUTPUT_LEN.to_string()),
+        "{error}"
+    );
+}
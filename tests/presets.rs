@@ -0,0 +1,117 @@
+mod common;
+
+use std::sync::Arc;
+
+use atlas::search::engine::{Engine, Event, Outcome};
+use atlas::search::{Dictionary, Keyspace, Mask};
+use atlas::{ByteOrder, Case, Encoding, HashResult, Preset, Presets};
+
+#[test]
+fn builtin_presets_match_engines() {
+    let registry = common::load_registry(&["atlas-crc", "atlas-fnv"]);
+    let presets = Presets::new();
+    for (preset, input, expected) in [
+        ("Wwise", "Play_Footstep", "5F820C8C"),
+        ("Source", "materials/Brick/BrickWall001a.vtf", "5A3131A6"),
+        ("Unreal FName", "None", "57E25071"),
+        ("Unreal FName", "PlayerStart", "2B58250A"),
+        ("Bungie Tiger", "Bungie", "B95AA13F"),
+    ] {
+        let preset = presets.get(preset).expect("Missing built-in preset");
+        let hash = preset
+            .hash(&registry, input)
+            .unwrap_or_else(|e| panic!("{} failed: {e}", preset.name));
+        assert_eq!(
+            hash,
+            HashResult(expected.to_owned()),
+            "{}({input:?})",
+            preset.name
+        );
+    }
+}
+
+#[test]
+fn preset_searches_normalise_candidates() {
+    let registry = Arc::new(common::load_registry(&["atlas-crc", "atlas-fnv"]));
+    let presets = Presets::new();
+    let words: Arc<dyn Keyspace> = Arc::new(Dictionary::parse("Start\nend\nFinish\n"));
+    let mask: Arc<dyn Keyspace> = Arc::new(Mask::parse("?u?u").expect("Invalid mask"));
+    for (preset, keyspace, prefix, text, expected) in [
+        (
+            "Unreal FName",
+            &words,
+            "Player",
+            "PlayerStart",
+            "PLAYERSTART",
+        ),
+        // The mask's fast path hashes candidates as they are, so it's skipped.
+        ("Wwise", &mask, "Play_", "Play_Go", "play_go"),
+        ("Bungie Tiger", &words, "tag_", "tag_end", "tag_end"),
+    ] {
+        let preset = presets.get(preset).expect("Missing built-in preset");
+        let shown = preset.hash(&registry, text).expect("Hashing failed");
+        let target = hex::decode(shown.0).expect("Hashes are hex");
+        let job = preset.job(
+            Arc::clone(keyspace),
+            std::iter::once(target).collect(),
+            prefix,
+            "",
+        );
+        let engine = Engine::start(Arc::clone(&registry), job);
+        let mut found = Vec::new();
+        for event in engine.events() {
+            match event {
+                Event::Found(found_match) => found.push(found_match.input),
+                Event::Finished(outcome) => {
+                    assert_eq!(outcome, Outcome::Completed, "{}", preset.name);
+                    break;
+                }
+                Event::Progress(_) => {}
+            }
+        }
+        assert_eq!(found, [preset.normalise(expected)], "{}", preset.name);
+        assert_eq!(preset.encoding.decode(&found[0]), expected);
+    }
+    assert_eq!(Encoding::Utf16Le.decode(b"N\0o\0"), "No");
+}
+
+#[test]
+fn user_presets_round_trip() {
+    let path = std::env::temp_dir().join(format!("atlas-presets-{}.ron", std::process::id()));
+    std::fs::remove_file(&path).ok();
+    let mut presets = Presets::load(&path).expect("A missing file holds no presets");
+    assert!(presets.user().is_empty());
+
+    presets.insert(Preset {
+        seed: Some(0x811c9dc5),
+        case: Case::Upper,
+        byte_order: ByteOrder::Little,
+        ..Preset::new("Wwise", "FNV-1a_32")
+    });
+    presets.insert(Preset::new("Checksum", "CRC").with_param("width", 16));
+    presets.insert(Preset {
+        key: Some(vec![0x01, 0x00, 0x01, 0x93]),
+        ..Preset::new("Custom FNV", "FNV").with_param("variant", 1)
+    });
+    presets.save(&path).expect("Failed to save presets");
+    let loaded = Presets::load(&path).expect("Failed to load presets");
+    std::fs::remove_file(&path).expect("Failed to remove the presets");
+    assert_eq!(loaded, presets);
+
+    // The user preset hides the built-in one until it is removed.
+    let mut presets = loaded;
+    assert_eq!(presets.iter().filter(|p| p.name == "Wwise").count(), 1);
+    assert_eq!(
+        presets.get("Wwise").map(|p| p.hasher.as_str()),
+        Some("FNV-1a_32")
+    );
+    assert!(presets.remove("Wwise").is_some());
+    assert_eq!(
+        presets.get("Wwise").map(|p| p.hasher.as_str()),
+        Some("FNV-1_32")
+    );
+    assert!(
+        presets.remove("Source").is_none(),
+        "Built-in presets can't be removed"
+    );
+}
@@ -0,0 +1,113 @@
+mod common;
+
+use atlas::HashOptions;
+use atlas::search::{BruteForce, Dictionary, Search, Stream};
+
+/// Every hasher with a [`Stream`].
+const STREAMED: [&str; 11] = [
+    "FNV-0_32",
+    "FNV-0_64",
+    "FNV-1_32",
+    "FNV-1_64",
+    "FNV-1a_32",
+    "FNV-1a_64",
+    "djb2",
+    "djb2a",
+    "sdbm",
+    "BKDR",
+    "CRC-32",
+];
+
+#[test]
+fn streams_match_plugins() {
+    let registry = common::load_registry(&["atlas-classic", "atlas-crc", "atlas-fnv"]);
+    let options = HashOptions::default();
+    assert_eq!(Stream::new(&registry, "CRC-32/BZIP2", &options), None);
+    assert_eq!(Stream::new(&registry, "Java_hashCode", &options), None);
+
+    let prefix = b"sound/vo/";
+    let suffix = b".wem";
+    for hasher in STREAMED {
+        let stream = Stream::new(&registry, hasher, &options)
+            .unwrap_or_else(|| panic!("{hasher} has no stream"));
+        let start = stream.update(stream.start(), prefix);
+        for word in [&b""[..], b"a", b"npc_vendor_idle_01", &[0xff; 40]] {
+            let input = [&prefix[..], word, suffix].concat();
+            let end = stream.update(start, &[word, &suffix[..]].concat());
+            assert_eq!(
+                stream.to_bytes(end),
+                common::hash(&registry, hasher, &input)
+            );
+            assert_eq!(stream.rewind(end, suffix), Some(stream.update(start, word)));
+            assert_eq!(stream.from_bytes(&stream.to_bytes(end)), Some(end));
+        }
+    }
+}
+
+#[test]
+fn streams_without_an_inverse_hash_the_suffix() {
+    let registry = common::load_registry(&["atlas-classic"]);
+    // An even multiplier has no inverse, so BKDR can't run backwards.
+    let options = HashOptions {
+        seed: Some(130),
+        ..HashOptions::default()
+    };
+    let stream = Stream::new(&registry, "BKDR", &options).expect("BKDR has no stream");
+    assert!(!stream.runs_backwards());
+    assert_eq!(stream.rewind(stream.start(), b".wem"), None);
+
+    let target = common::hash_with(&registry, "BKDR", b"sound/vo/ab.wem", &options);
+    let search = Search::new(&registry, "BKDR", &options, [target.clone()])
+        .with_affixes(b"sound/vo/", b".wem");
+    assert!(search.matcher().is_streamed());
+    let found = search
+        .run(BruteForce::new(b"abc", 1, 3))
+        .expect("The search failed");
+    assert_eq!(found.len(), 1);
+    assert_eq!(found[0].input, b"sound/vo/ab.wem");
+    assert_eq!(found[0].hash, target);
+}
+
+#[test]
+fn search_finds_inputs_between_affixes() {
+    let registry = common::load_registry(&["atlas-classic", "atlas-crc", "atlas-fnv"]);
+    let options = HashOptions::default();
+    let words = Dictionary::parse("npc_guard_alert_02\r\n\nnpc_vendor_idle_01\nambience  \n");
+    assert_eq!(words.words.len(), 3);
+
+    // These run on the host, and CRC-32/BZIP2 through its plugin.
+    for hasher in STREAMED.into_iter().chain(["CRC-32/BZIP2"]) {
+        let targets = [&b"sound/vo/npc_vendor_idle_01.wem"[..], b"sound/vo/ab.wem"]
+            .map(|input| common::hash(&registry, hasher, input));
+        let search = Search::new(&registry, hasher, &options, targets.clone())
+            .with_affixes(b"sound/vo/", b".wem");
+
+        let found = search.run(words.iter()).expect("The search failed");
+        assert_eq!(found.len(), 1, "{hasher}");
+        assert_eq!(found[0].input, b"sound/vo/npc_vendor_idle_01.wem");
+        assert_eq!(found[0].hash, targets[0]);
+
+        let found = search
+            .run(BruteForce::new(b"abc", 1, 3))
+            .expect("The search failed");
+        assert_eq!(found.len(), 1, "{hasher}");
+        assert_eq!(found[0].input, b"sound/vo/ab.wem");
+        assert_eq!(found[0].hash, targets[1]);
+    }
+}
+
+#[test]
+fn brute_force_counts() {
+    let candidates = BruteForce::new(b"cabba", 0, 3);
+    assert_eq!(candidates.len(), Some(1 + 3 + 9 + 27));
+    let candidates: Vec<_> = candidates.collect();
+    assert_eq!(candidates.len(), 40);
+    assert_eq!(candidates[..5], [&b""[..], b"a", b"b", b"c", b"aa"]);
+    assert_eq!(candidates[39], b"ccc");
+
+    assert!(BruteForce::new(b"", 1, 4).is_empty());
+    assert!(BruteForce::new(b"ab", 3, 2).is_empty());
+    assert_eq!(BruteForce::new(b"", 0, 4).count(), 1);
+    let every_byte: Vec<u8> = (0..=255).collect();
+    assert_eq!(BruteForce::new(&every_byte, 0, 64).len(), None);
+}
@@ -0,0 +1,165 @@
+mod common;
+
+use std::collections::BTreeSet;
+use std::sync::Arc;
+
+use atlas::HashOptions;
+use atlas::search::engine::{Engine, Event, Job, Outcome};
+use atlas::search::{Affixes, Dictionary, Mask, Search, Targets};
+
+/// Deterministic pseudo-random bytes.
+fn random_hashes(seed: u64, count: usize, width: usize) -> Vec<Vec<u8>> {
+    let mut state = seed;
+    (0..count)
+        .map(|_| {
+            (0..width)
+                .map(|_| {
+                    state = state
+                        .wrapping_mul(6_364_136_223_846_793_005)
+                        .wrapping_add(1);
+                    (state >> 56) as u8
+                })
+                .collect()
+        })
+        .collect()
+}
+
+#[test]
+fn lookups_agree_with_a_set() {
+    let mut lists = Vec::new();
+    for (seed, width) in [(1, 4), (2, 8), (3, 16), (4, 2)] {
+        lists.push((format!("{width} bytes"), random_hashes(seed, 20_000, width)));
+    }
+    // Keys that are far from uniform, bunched at both ends.
+    let skewed = (0u32..1000)
+        .chain(u32::MAX - 1000..=u32::MAX)
+        .map(|key| key.to_be_bytes().to_vec());
+    lists.push((String::from("skewed"), skewed.collect()));
+    let present: BTreeSet<_> = lists
+        .iter()
+        .flat_map(|(_, hashes)| hashes.clone())
+        .collect();
+
+    for targets in [
+        Targets::from_lists(lists.clone()),
+        Targets::from_lists(lists.clone()).with_bloom(),
+    ] {
+        assert_eq!(targets.lists().len(), 5);
+        for hash in &present {
+            assert!(targets.contains(hash), "{hash:02x?} is missing");
+        }
+        for width in [2, 4, 8, 16, 3] {
+            for hash in random_hashes(99, 20_000, width) {
+                assert_eq!(
+                    targets.contains(&hash),
+                    present.contains(&hash),
+                    "{hash:02x?}"
+                );
+            }
+        }
+        assert!(!targets.contains(&[]));
+        let every: BTreeSet<_> = targets.iter().map(|(hash, _)| hash).collect();
+        assert_eq!(every, present);
+    }
+}
+
+#[test]
+fn lists_are_tagged() {
+    let dir = std::env::temp_dir().join(format!("atlas-targets-{}", std::process::id()));
+    std::fs::create_dir_all(&dir).expect("Failed to create a temporary directory");
+    let vo = dir.join("vo.txt");
+    let sfx = dir.join("sfx.txt");
+    std::fs::write(&vo, "# voice lines\n0x1F7C3C8D  npc_vendor\n\n9E5A21B0\n").expect("Write");
+    std::fs::write(&sfx, "9e5a21b0\n9e5a21b0\nDEADBEEFCAFEF00D\n").expect("Write");
+    let bad = dir.join("bad.txt");
+    std::fs::write(&bad, "1234\nnot-hex\n").expect("Write");
+
+    let targets = Targets::load([&vo, &sfx]).expect("The lists are valid");
+    let error = Targets::load([&vo, &bad]).expect_err("The list isn't valid");
+    std::fs::remove_dir_all(&dir).ok();
+
+    assert_eq!(targets.lists(), ["vo.txt", "sfx.txt"]);
+    // A hash repeated in a list counts once.
+    assert_eq!(targets.len(), 4);
+    let tags = |hash: &str| targets.tags(&hex::decode(hash).expect("Hashes are hex"));
+    assert_eq!(tags("1F7C3C8D"), ["vo.txt"]);
+    assert_eq!(tags("9E5A21B0"), ["vo.txt", "sfx.txt"]);
+    assert_eq!(tags("DEADBEEFCAFEF00D"), ["sfx.txt"]);
+    assert!(tags("00000000").is_empty());
+    assert!(error.to_string().contains("Line 2"), "{error}");
+
+    // Hashes from an iterator are in one untagged list.
+    let untagged: Targets = std::iter::once(vec![1, 2, 3, 4]).collect();
+    assert!(untagged.contains(&[1, 2, 3, 4]));
+    assert!(untagged.tags(&[1, 2, 3, 4]).is_empty());
+}
+
+#[test]
+fn matches_name_their_lists() {
+    let registry = Arc::new(common::load_registry(&[
+        "atlas-crc",
+        "atlas-fnv",
+        "atlas-murmur",
+    ]));
+    let affixes = Affixes {
+        prefix: b"vo/".to_vec(),
+        suffix: b".wem".to_vec(),
+    };
+    // Through the plugin, on the host, and with a kernel.
+    for hasher in ["CRC-32", "FNV-1_32", "FNV-1a_32", "Murmur3_32"] {
+        let hash = |input: &[u8]| common::hash(&registry, hasher, input);
+        let mut noise = random_hashes(7, 5000, 4);
+        noise.push(hash(b"vo/ab1.wem"));
+        let targets = Targets::from_lists([
+            ("dump.txt", noise),
+            ("known.txt", vec![hash(b"vo/ab1.wem"), hash(b"vo/zz9.wem")]),
+        ])
+        .with_bloom();
+        let mask = Arc::new(Mask::parse("?l?l?d").expect("The mask is valid"));
+        let job = Job {
+            affixes: affixes.clone(),
+            targets: Arc::new(targets.clone()),
+            threads: 2,
+            ..Job::new(hasher, Vec::new(), mask)
+        };
+        let engine = Engine::start(Arc::clone(&registry), job);
+        let mut found = Vec::new();
+        for event in engine.events() {
+            match event {
+                Event::Found(found_match) => found.push(found_match),
+                Event::Finished(outcome) => {
+                    assert_eq!(outcome, Outcome::Completed, "{hasher}");
+                    break;
+                }
+                Event::Progress(_) => {}
+            }
+        }
+        found.sort_by(|a, b| a.input.cmp(&b.input));
+        let found: Vec<_> = found
+            .iter()
+            .filter(|found| found.input == b"vo/ab1.wem" || found.input == b"vo/zz9.wem")
+            .map(|found| (found.input.as_slice(), found.tags.clone()))
+            .collect();
+        assert_eq!(
+            found,
+            [
+                (
+                    &b"vo/ab1.wem"[..],
+                    vec!["dump.txt".to_owned(), "known.txt".to_owned()]
+                ),
+                (&b"vo/zz9.wem"[..], vec!["known.txt".to_owned()]),
+            ],
+            "{hasher}"
+        );
+
+        let words = Dictionary::parse("ab1\nzz9\nqq0\n");
+        let options = HashOptions::default();
+        let found = Search::new(&registry, hasher, &options, Vec::new())
+            .with_targets(Arc::new(targets))
+            .with_affixes(&affixes.prefix, &affixes.suffix)
+            .run(words.iter())
+            .expect("The search failed");
+        assert_eq!(found.len(), 2, "{hasher}");
+        assert_eq!(found[1].tags, ["known.txt"], "{hasher}");
+    }
+}